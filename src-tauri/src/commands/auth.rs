@@ -0,0 +1,111 @@
+/**
+ * Tauri commands for authentication
+ * Stores Supabase session tokens in the OS keyring and keeps them fresh
+ */
+
+use crate::services::auth::{AuthCredentials, AuthError, SupabaseAuthClient};
+
+/// Keyring service/user under which the session is stored
+const KEYRING_SERVICE: &str = "com.fluentdiary.desktop";
+const KEYRING_USER: &str = "auth_credentials";
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("Failed to access keyring: {}", e))
+}
+
+/// Save session tokens to the OS keyring
+pub fn save_auth_credentials(credentials: &AuthCredentials) -> Result<(), String> {
+    let json = serde_json::to_string(credentials).map_err(|e| e.to_string())?;
+    keyring_entry()?
+        .set_password(&json)
+        .map_err(|e| format!("Failed to save credentials: {}", e))
+}
+
+/// Load session tokens from the OS keyring (None if not signed in)
+pub fn load_auth_credentials() -> Result<Option<AuthCredentials>, String> {
+    match keyring_entry()?.get_password() {
+        Ok(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Stored credentials are corrupted: {}", e)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to load credentials: {}", e)),
+    }
+}
+
+/// Remove session tokens from the OS keyring
+pub fn delete_auth_credentials() -> Result<(), String> {
+    match keyring_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete credentials: {}", e)),
+    }
+}
+
+/// Refresh the stored session using its refresh token
+/// If the refresh token is no longer valid, stored credentials are cleared and
+/// the "Session expired" error is returned so the UI can prompt a full sign-in
+#[tauri::command]
+pub async fn refresh_access_token(
+    supabase_url: String,
+    anon_key: String,
+) -> Result<AuthCredentials, String> {
+    let stored = load_auth_credentials()?.ok_or_else(|| "Not signed in".to_string())?;
+
+    let client = SupabaseAuthClient::new(&supabase_url, &anon_key);
+    match client.refresh_session(&stored.refresh_token).await {
+        Ok(credentials) => {
+            save_auth_credentials(&credentials)?;
+            println!("[refresh_access_token] Session refreshed");
+            Ok(credentials)
+        }
+        Err(AuthError::InvalidRefreshToken) => {
+            println!("[refresh_access_token] Refresh token rejected, clearing stored session");
+            delete_auth_credentials()?;
+            Err(AuthError::InvalidRefreshToken.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Sign in with email and password and store the session
+#[tauri::command]
+pub async fn sign_in_with_password(
+    supabase_url: String,
+    anon_key: String,
+    email: String,
+    password: String,
+) -> Result<AuthCredentials, String> {
+    let client = SupabaseAuthClient::new(&supabase_url, &anon_key);
+    let credentials = client
+        .sign_in_with_password(email.trim(), &password)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    save_auth_credentials(&credentials)?;
+    println!("[sign_in_with_password] Signed in");
+    Ok(credentials)
+}
+
+/// Create an account with email and password
+/// Returns None when the user must confirm their email before signing in
+#[tauri::command]
+pub async fn sign_up_with_password(
+    supabase_url: String,
+    anon_key: String,
+    email: String,
+    password: String,
+) -> Result<Option<AuthCredentials>, String> {
+    let client = SupabaseAuthClient::new(&supabase_url, &anon_key);
+    let credentials = client
+        .sign_up_with_password(email.trim(), &password)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(ref credentials) = credentials {
+        save_auth_credentials(credentials)?;
+        println!("[sign_up_with_password] Signed up and signed in");
+    } else {
+        println!("[sign_up_with_password] Signed up, awaiting email confirmation");
+    }
+    Ok(credentials)
+}
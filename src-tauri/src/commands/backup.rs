@@ -0,0 +1,90 @@
+/**
+ * Tauri commands for backup and restore
+ *
+ * Exposes the backup service to the frontend
+ */
+
+use crate::db::user::open_user_db;
+use crate::services::audio_dir;
+use crate::services::backup::{self, BackupInfo, BackupMetadata};
+use std::path::Path;
+use tauri::Manager;
+
+/// Export user.db and all session audio (including recordings saved to a
+/// user-picked directory) into a single zip archive
+#[tauri::command]
+pub async fn export_backup(app_handle: tauri::AppHandle, dest_path: String) -> Result<BackupInfo, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let session_audio = backup::session_audio_paths(&pool).await.map_err(|e| e.to_string())?;
+    let audio_directory = audio_dir::get_audio_directory(&pool).await.map_err(|e| e.to_string())?;
+
+    // Flush the WAL into user.db so the copied file has every committed change
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to checkpoint database: {}", e))?;
+    pool.close().await;
+
+    let app_version = app_handle.package_info().version.to_string();
+    tokio::task::spawn_blocking(move || {
+        backup::export_backup(
+            &app_data_dir,
+            &session_audio,
+            audio_directory.as_deref(),
+            Path::new(&dest_path),
+            &app_version,
+        )
+    })
+    .await
+    .map_err(|e| format!("Backup task failed: {}", e))?
+    .map_err(|e| e.to_string())
+}
+
+/// Restore a backup archive created by `export_backup`
+/// Fails if this install already has data, unless `force` is true
+#[tauri::command]
+pub async fn import_backup(
+    app_handle: tauri::AppHandle,
+    archive_path: String,
+    force: Option<bool>,
+) -> Result<BackupMetadata, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Recordings go back to the user's picked directory when this machine has it
+    let default_audio_dir = app_data_dir.join("audio");
+    let (metadata, restored_audio_dir) = tokio::task::spawn_blocking(move || {
+        let archive = Path::new(&archive_path);
+        let metadata = backup::read_backup_metadata(archive)?;
+        let restored_audio_dir = backup::restore_audio_dir(&metadata, &default_audio_dir);
+        let metadata = backup::import_backup(&app_data_dir, &restored_audio_dir, archive, force.unwrap_or(false))?;
+        anyhow::Ok((metadata, restored_audio_dir))
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+    .map_err(|e| e.to_string())?;
+
+    // Session audio paths are absolute, point them at where the recordings were restored
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let relinked = backup::relink_audio_paths(&pool, &restored_audio_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    println!("[import_backup] Relinked {} session audio paths to {:?}", relinked, restored_audio_dir);
+
+    // The picked directory doesn't exist here, so new recordings use the default one
+    let picked_dir = audio_dir::get_audio_directory(&pool).await.map_err(|e| e.to_string())?;
+    if picked_dir.is_some_and(|dir| dir != restored_audio_dir) {
+        audio_dir::set_audio_directory(&pool, None)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(metadata)
+}
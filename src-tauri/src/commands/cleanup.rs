@@ -5,22 +5,98 @@
  */
 
 use crate::db::user::open_user_db;
-use crate::services::cleanup::{cleanup_old_sessions, CleanupStats};
+use crate::services::audio_dir::get_audio_directory;
+use crate::services::cleanup::{
+    cleanup_by_total_size, cleanup_old_sessions,
+    cleanup_orphaned_audio as cleanup_orphaned_audio_files, cleanup_orphaned_recordings, CleanupStats,
+    OrphanCleanupStats, SizeCleanupStats,
+};
+use tauri::Manager;
 
 /// Run cleanup to delete old sessions based on retention period
+/// If `max_audio_bytes` is set, the oldest remaining sessions are then trimmed to fit that budget
+/// If `language` is set, age-based cleanup only touches sessions in that language
 #[tauri::command]
 pub async fn run_cleanup(
     app_handle: tauri::AppHandle,
     retention_days: i64,
+    max_audio_bytes: Option<u64>,
+    language: Option<String>,
 ) -> Result<CleanupStats, String> {
     println!("[run_cleanup] Starting cleanup with retention_days: {}", retention_days);
 
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
-    cleanup_old_sessions(&pool, retention_days)
+    let mut stats = cleanup_old_sessions(&pool, retention_days, language.as_deref())
         .await
         .map_err(|e| {
             eprintln!("[run_cleanup] Cleanup failed: {}", e);
             format!("Cleanup failed: {}", e)
+        })?;
+
+    if let Some(max_bytes) = max_audio_bytes {
+        let size_stats = cleanup_by_total_size(&pool, max_bytes)
+            .await
+            .map_err(|e| {
+                eprintln!("[run_cleanup] Size cleanup failed: {}", e);
+                format!("Cleanup failed: {}", e)
+            })?;
+        stats.deleted_count += size_stats.deleted_count;
+        stats.failed_count += size_stats.failed_count;
+    }
+
+    Ok(stats)
+}
+
+/// Delete the oldest sessions until total audio size fits in `max_bytes`
+#[tauri::command]
+pub async fn run_size_cleanup(
+    app_handle: tauri::AppHandle,
+    max_bytes: u64,
+) -> Result<SizeCleanupStats, String> {
+    println!("[run_size_cleanup] Starting cleanup with max_bytes: {}", max_bytes);
+
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    cleanup_by_total_size(&pool, max_bytes)
+        .await
+        .map_err(|e| {
+            eprintln!("[run_size_cleanup] Cleanup failed: {}", e);
+            format!("Cleanup failed: {}", e)
         })
 }
+
+/// Delete audio files that no longer belong to any session, in the default audio
+/// directory and the one the user picked for recordings (only WAV files there)
+#[tauri::command]
+pub async fn cleanup_orphaned_audio(app_handle: tauri::AppHandle) -> Result<OrphanCleanupStats, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    let audio_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("audio");
+
+    let mut stats = cleanup_orphaned_audio_files(&pool, &audio_dir)
+        .await
+        .map_err(|e| {
+            eprintln!("[cleanup_orphaned_audio] Cleanup failed: {}", e);
+            format!("Cleanup failed: {}", e)
+        })?;
+
+    let picked_dir = get_audio_directory(&pool).await.map_err(|e| e.to_string())?;
+    if let Some(picked_dir) = picked_dir.filter(|dir| *dir != audio_dir) {
+        let picked = cleanup_orphaned_recordings(&pool, &picked_dir)
+            .await
+            .map_err(|e| {
+                eprintln!("[cleanup_orphaned_audio] Cleanup of {:?} failed: {}", picked_dir, e);
+                format!("Cleanup failed: {}", e)
+            })?;
+        stats.deleted_count += picked.deleted_count;
+        stats.failed_count += picked.failed_count;
+        stats.reclaimed_bytes += picked.reclaimed_bytes;
+    }
+
+    Ok(stats)
+}
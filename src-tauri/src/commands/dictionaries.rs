@@ -4,45 +4,28 @@
  */
 
 use crate::db::user::open_user_db;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Dictionary {
-    pub id: i64,
-    pub language: String,
-    pub name: String,
-    pub url_template: String,
-    pub dict_type: String,
-    pub is_active: i64,
-    pub sort_order: i64,
-    pub is_default: i64,
-    pub created_at: i64,
-}
+use crate::services::dictionaries::{
+    build_dictionary_url, get_dictionaries_for_pair, update_dictionary as update_dictionary_fields,
+    validate_dict_type, validate_url_template, Dictionary, DEFAULT_TARGET_LANGUAGE,
+};
 
-/// Get all dictionaries for a language
+/// Get all dictionaries for a language pair
+/// `target_language` defaults to English when omitted
 #[tauri::command]
 pub async fn get_dictionaries(
     app_handle: tauri::AppHandle,
     language: String,
+    target_language: Option<String>,
 ) -> Result<Vec<Dictionary>, String> {
     let pool = open_user_db(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
-    let dictionaries = sqlx::query_as::<_, Dictionary>(
-        r#"
-        SELECT id, language, name, url_template, dict_type, is_active, sort_order, is_default, created_at
-        FROM dictionaries
-        WHERE language = ?
-        ORDER BY sort_order ASC
-        "#,
-    )
-    .bind(&language)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let target_language = target_language.unwrap_or_else(|| DEFAULT_TARGET_LANGUAGE.to_string());
 
-    Ok(dictionaries)
+    get_dictionaries_for_pair(&pool, &language, &target_language)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// Update dictionary active status
@@ -134,6 +117,7 @@ pub async fn reorder_dictionaries(
 pub async fn add_dictionary(
     app_handle: tauri::AppHandle,
     language: String,
+    target_language: Option<String>,
     name: String,
     url_template: String,
     dict_type: String,
@@ -142,20 +126,21 @@ pub async fn add_dictionary(
         .await
         .map_err(|e| e.to_string())?;
 
-    // Validate dict_type
-    if dict_type != "embedded" && dict_type != "popup" {
-        return Err("dict_type must be 'embedded' or 'popup'".to_string());
-    }
+    validate_dict_type(&dict_type).map_err(|e| e.to_string())?;
+    validate_url_template(&url_template).map_err(|e| e.to_string())?;
+
+    let target_language = target_language.unwrap_or_else(|| DEFAULT_TARGET_LANGUAGE.to_string());
 
-    // Get the next sort_order for this language
+    // Get the next sort_order for this language pair
     let max_sort: Option<i64> = sqlx::query_scalar(
         r#"
         SELECT MAX(sort_order)
         FROM dictionaries
-        WHERE language = ?
+        WHERE language = ? AND COALESCE(target_language, 'en') = ?
         "#,
     )
     .bind(&language)
+    .bind(&target_language)
     .fetch_one(&pool)
     .await
     .map_err(|e| e.to_string())?;
@@ -169,11 +154,12 @@ pub async fn add_dictionary(
 
     let result = sqlx::query(
         r#"
-        INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-        VALUES (?, ?, ?, ?, 1, ?, 0, ?)
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES (?, ?, ?, ?, ?, 1, ?, 0, ?)
         "#,
     )
     .bind(&language)
+    .bind(&target_language)
     .bind(&name)
     .bind(&url_template)
     .bind(&dict_type)
@@ -186,6 +172,52 @@ pub async fn add_dictionary(
     Ok(result.last_insert_rowid())
 }
 
+/// Edit a dictionary's name, URL template and type without changing its sort order
+/// Default dictionaries can only be renamed
+#[tauri::command]
+pub async fn update_dictionary(
+    app_handle: tauri::AppHandle,
+    id: i64,
+    name: String,
+    url_template: String,
+    dict_type: String,
+) -> Result<(), String> {
+    let pool = open_user_db(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    update_dictionary_fields(&pool, id, &name, &url_template, &dict_type)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Build the lookup URL for a word using a dictionary's URL template
+#[tauri::command]
+pub async fn resolve_dictionary_url(
+    app_handle: tauri::AppHandle,
+    id: i64,
+    word: String,
+) -> Result<String, String> {
+    let pool = open_user_db(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let url_template: String = sqlx::query_scalar(
+        r#"
+        SELECT url_template
+        FROM dictionaries
+        WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Dictionary {} not found", id))?;
+
+    Ok(build_dictionary_url(&url_template, &word))
+}
+
 /// Delete a custom dictionary
 /// Only allows deleting non-default dictionaries
 #[tauri::command]
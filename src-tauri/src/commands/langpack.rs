@@ -1,4 +1,10 @@
+use crate::db::langpack;
+use crate::db::user::{
+    get_setting, open_user_db, set_setting, AUTO_RELEMMATIZE_SETTING, EDITABLE_TRANSLATION_PACKS_SETTING,
+    LEMMA_STEMMING_SETTING,
+};
 use crate::services::lemmatization;
+use crate::services::translation::{self, TranslationPackStats};
 
 /// Tauri command: Get lemma (base form) for a word
 ///
@@ -24,3 +30,97 @@ pub async fn lemmatize_batch(app_handle: tauri::AppHandle, words: Vec<String>, l
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Tauri command: Whether words missing from the lemma DB are resolved via a stemmer
+#[tauri::command]
+pub async fn get_lemma_stemming_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let value = get_setting(&pool, LEMMA_STEMMING_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Tauri command: Turn the stemming fallback for session vocabulary on or off
+#[tauri::command]
+pub async fn set_lemma_stemming_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, LEMMA_STEMMING_SETTING, Some(if enabled { "true" } else { "false" }))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: Whether vocabulary is re-lemmatized automatically after a lemma DB download
+#[tauri::command]
+pub async fn get_auto_relemmatize_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let value = get_setting(&pool, AUTO_RELEMMATIZE_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Tauri command: Turn automatic re-lemmatization after lemma DB downloads on or off
+/// When off, `langpack_updated` is still emitted so the frontend can prompt instead
+#[tauri::command]
+pub async fn set_auto_relemmatize_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, AUTO_RELEMMATIZE_SETTING, Some(if enabled { "true" } else { "false" }))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: Whether corrections may be written into downloaded translation packs
+#[tauri::command]
+pub async fn get_translation_pack_editing_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let value = get_setting(&pool, EDITABLE_TRANSLATION_PACKS_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Tauri command: Allow or forbid writing corrections into downloaded translation packs
+#[tauri::command]
+pub async fn set_translation_pack_editing_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, EDITABLE_TRANSLATION_PACKS_SETTING, Some(if enabled { "true" } else { "false" }))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: Add a translation to the downloaded {from}-{to} pack
+///
+/// Only allowed after `set_translation_pack_editing_enabled(true)`; custom translations
+/// are the non-destructive alternative. Returns false if the pack already had it.
+#[tauri::command]
+pub async fn append_official_translation(
+    app_handle: tauri::AppHandle,
+    lemma: String,
+    from: String,
+    to: String,
+    translation: String,
+) -> Result<bool, String> {
+    if !get_translation_pack_editing_enabled(app_handle.clone()).await? {
+        return Err("Editing translation packs is disabled".to_string());
+    }
+
+    langpack::append_official_translation(&lemma, &from, &to, &translation, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: Rows and distinct source lemmas in the {from}-{to} translation pack
+///
+/// Compared with the lemma DB's size this shows roughly how complete the pack is.
+/// Fails if the pack isn't installed.
+#[tauri::command]
+pub async fn get_translation_pack_stats(
+    app_handle: tauri::AppHandle,
+    from: String,
+    to: String,
+) -> Result<TranslationPackStats, String> {
+    translation::get_translation_pack_stats(&from, &to, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -3,7 +3,11 @@
  * Exposes language pack service to the frontend
  */
 
-use crate::services::language_packs::{self, RequiredPacks};
+use crate::commands::models::DownloadStateWrapper;
+use crate::services::download;
+use crate::services::language_packs::{self, InstalledPackInfo, LanguagePackHealth, RequiredPacks};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 /// Check if a language's lemma database is installed
 #[tauri::command]
@@ -28,29 +32,47 @@ pub fn get_installed_languages(app_handle: tauri::AppHandle) -> Result<Vec<Strin
     language_packs::get_installed_languages(&app_handle).map_err(|e| e.to_string())
 }
 
+/// Get installed language packs with size, path and whether they're bundled
+#[tauri::command]
+pub fn get_installed_language_packs(app_handle: tauri::AppHandle) -> Result<Vec<InstalledPackInfo>, String> {
+    language_packs::get_installed_language_packs(&app_handle).map_err(|e| e.to_string())
+}
+
 /// Download lemma database for a language
 #[tauri::command]
 pub async fn download_lemmas(
     app_handle: tauri::AppHandle,
+    download_state: tauri::State<'_, DownloadStateWrapper>,
     lang: String,
     url: String,
+    mirrors: Option<Vec<String>>,
+    sha256: Option<String>,
 ) -> Result<(), String> {
-    language_packs::download_lemmas(&lang, &url, app_handle)
-        .await
-        .map_err(|e| e.to_string())
+    let mirrors = mirrors.unwrap_or_default();
+    let cancel = download_state.0.lock().unwrap().begin_language_download();
+    let result = language_packs::download_lemmas(&lang, &url, &mirrors, sha256.as_deref(), cancel, app_handle).await;
+    download_state.0.lock().unwrap().end_language_download();
+
+    result.map_err(|e| e.to_string())
 }
 
 /// Download translation database
 #[tauri::command]
 pub async fn download_translation(
     app_handle: tauri::AppHandle,
+    download_state: tauri::State<'_, DownloadStateWrapper>,
     from_lang: String,
     to_lang: String,
     url: String,
+    mirrors: Option<Vec<String>>,
+    sha256: Option<String>,
 ) -> Result<(), String> {
-    language_packs::download_translation(&from_lang, &to_lang, &url, app_handle)
-        .await
-        .map_err(|e| e.to_string())
+    let mirrors = mirrors.unwrap_or_default();
+    let cancel = download_state.0.lock().unwrap().begin_language_download();
+    let result = language_packs::download_translation(&from_lang, &to_lang, &url, &mirrors, sha256.as_deref(), cancel, app_handle).await;
+    download_state.0.lock().unwrap().end_language_download();
+
+    result.map_err(|e| e.to_string())
 }
 
 /// Delete a language pack
@@ -59,6 +81,21 @@ pub fn delete_language_pack(app_handle: tauri::AppHandle, lang: String) -> Resul
     language_packs::delete_language_pack(&lang, &app_handle).map_err(|e| e.to_string())
 }
 
+/// Delete a translation database (e.g. one that failed verification)
+#[tauri::command]
+pub fn delete_translation_pack(app_handle: tauri::AppHandle, from_lang: String, to_lang: String) -> Result<(), String> {
+    language_packs::delete_translation_pack(&from_lang, &to_lang, &app_handle).map_err(|e| e.to_string())
+}
+
+/// Check a language's installed lemma and translation databases for corruption
+/// Call after downloads; unhealthy packs can be deleted and downloaded again
+#[tauri::command]
+pub async fn verify_language_pack(app_handle: tauri::AppHandle, lang: String) -> Result<LanguagePackHealth, String> {
+    language_packs::verify_language_pack(&lang, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get required packs for a language pair
 /// Returns which lemmas and translations need to be downloaded
 #[tauri::command]
@@ -76,9 +113,25 @@ pub fn get_required_packs(
 #[tauri::command]
 pub async fn download_language_pair(
     app_handle: tauri::AppHandle,
+    download_state: tauri::State<'_, DownloadStateWrapper>,
     primary_lang: String,
     target_lang: String,
     manifest_url: String,
+) -> Result<(), String> {
+    let cancel = download_state.0.lock().unwrap().begin_language_download();
+    let result = download_language_pair_with_cancel(app_handle, primary_lang, target_lang, manifest_url, cancel).await;
+    download_state.0.lock().unwrap().end_language_download();
+
+    result
+}
+
+/// Body of `download_language_pair`; all spawned downloads share the `cancel` flag
+async fn download_language_pair_with_cancel(
+    app_handle: tauri::AppHandle,
+    primary_lang: String,
+    target_lang: String,
+    manifest_url: String,
+    cancel: Arc<AtomicBool>,
 ) -> Result<(), String> {
     println!(
         "[download_language_pair] primary={}, target={}, manifest={}",
@@ -96,6 +149,29 @@ pub async fn download_language_pair(
 
     println!("[download_language_pair] Required packs: {:?}", required);
 
+    // Make sure everything fits on disk before starting any download
+    let lemma_bytes: u64 = required
+        .lemmas
+        .iter()
+        .filter_map(|lang| manifest.languages.get(lang))
+        .filter(|info| !info.bundled)
+        .filter_map(|info| info.lemmas_size)
+        .sum();
+    let translation_bytes: u64 = required
+        .translations
+        .iter()
+        .filter_map(|(from_lang, to_lang)| {
+            manifest.translations.iter().find(|p| {
+                (p.from_lang == *from_lang && p.to_lang == *to_lang)
+                    || (p.from_lang == *to_lang && p.to_lang == *from_lang)
+            })
+        })
+        .filter_map(|pack| pack.size)
+        .sum();
+    let langpacks_dir = language_packs::get_langpacks_dir(&app_handle).map_err(|e| e.to_string())?;
+    download::ensure_disk_space(&langpacks_dir, lemma_bytes + translation_bytes)
+        .map_err(|e| e.to_string())?;
+
     // Download lemmas in parallel
     let mut lemma_downloads = Vec::new();
     for lang in &required.lemmas {
@@ -103,10 +179,13 @@ pub async fn download_language_pair(
             if !lang_info.bundled {
                 let app_clone = app_handle.clone();
                 let url = lang_info.lemmas_url.clone();
+                let mirrors = lang_info.mirrors.clone();
+                let sha256 = lang_info.sha256.clone();
                 let lang_clone = lang.clone();
+                let cancel = cancel.clone();
 
                 lemma_downloads.push(tokio::spawn(async move {
-                    language_packs::download_lemmas(&lang_clone, &url, app_clone).await
+                    language_packs::download_lemmas(&lang_clone, &url, &mirrors, sha256.as_deref(), cancel, app_clone).await
                 }));
             }
         }
@@ -130,11 +209,14 @@ pub async fn download_language_pair(
             println!("[download_language_pair] Found translation pack: {}-{} (URL: {})", from_lang, to_lang, pack.url);
             let app_clone = app_handle.clone();
             let url = pack.url.clone();
+            let mirrors = pack.mirrors.clone();
+            let sha256 = pack.sha256.clone();
             let from = from_lang.clone();
             let to = to_lang.clone();
+            let cancel = cancel.clone();
 
             translation_downloads.push(tokio::spawn(async move {
-                language_packs::download_translation(&from, &to, &url, app_clone).await
+                language_packs::download_translation(&from, &to, &url, &mirrors, sha256.as_deref(), cancel, app_clone).await
             }));
         } else {
             println!("[download_language_pair] WARNING: No translation pack found for {}-{}", from_lang, to_lang);
@@ -171,6 +253,11 @@ struct Manifest {
 struct LanguageInfo {
     lemmas_url: String,
     bundled: bool,
+    sha256: Option<String>,
+    lemmas_size: Option<u64>,
+    /// Fallback URLs tried in order if `lemmas_url` fails
+    #[serde(default)]
+    mirrors: Vec<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -178,6 +265,11 @@ struct TranslationInfo {
     from_lang: String,
     to_lang: String,
     url: String,
+    sha256: Option<String>,
+    size: Option<u64>,
+    /// Fallback URLs tried in order if `url` fails
+    #[serde(default)]
+    mirrors: Vec<String>,
 }
 
 /// Fetch and parse the language pack manifest
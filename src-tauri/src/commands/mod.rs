@@ -1,5 +1,7 @@
 // Tauri commands - exposes services to frontend
 
+pub mod auth;
+pub mod backup;
 pub mod cleanup;
 pub mod dictionaries;
 pub mod langpack;
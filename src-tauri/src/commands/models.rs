@@ -2,11 +2,16 @@
  * Tauri commands for Whisper model management
  */
 
+use crate::commands::recording::{TranscriptionGuard, TranscriptionStateWrapper};
+use crate::db::user::{get_setting, open_user_db, set_setting, DEFAULT_MODEL_SETTING};
+use crate::services::benchmark::{self, BenchmarkResult};
 use crate::services::model_download::{
     delete_model, download_model, get_available_models, get_default_model,
-    get_installed_models, get_model_path, is_model_installed,
-    InstalledModelInfo, WhisperModel,
+    get_installed_models, get_model_path, import_custom_model, is_model_installed, validate_model,
+    InstalledModelInfo, ModelValidation, WhisperModel,
 };
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter};
@@ -15,6 +20,11 @@ use tauri::{AppHandle, Emitter};
 pub struct DownloadState {
     pub in_progress: bool,
     pub current_model: Option<String>,
+    /// Cancellation flag for the current model download
+    pub cancel_token: Arc<AtomicBool>,
+    /// Cancellation flag shared by all running language pack downloads
+    pub language_cancel_token: Arc<AtomicBool>,
+    pub language_downloads_active: usize,
 }
 
 impl DownloadState {
@@ -22,8 +32,26 @@ impl DownloadState {
         Self {
             in_progress: false,
             current_model: None,
+            cancel_token: Arc::new(AtomicBool::new(false)),
+            language_cancel_token: Arc::new(AtomicBool::new(false)),
+            language_downloads_active: 0,
         }
     }
+
+    /// Register a language pack download and get its cancellation flag
+    /// A fresh flag is issued once all previous language downloads have finished.
+    pub fn begin_language_download(&mut self) -> Arc<AtomicBool> {
+        if self.language_downloads_active == 0 {
+            self.language_cancel_token = Arc::new(AtomicBool::new(false));
+        }
+        self.language_downloads_active += 1;
+        self.language_cancel_token.clone()
+    }
+
+    /// Mark a language pack download as finished (successfully or not)
+    pub fn end_language_download(&mut self) {
+        self.language_downloads_active = self.language_downloads_active.saturating_sub(1);
+    }
 }
 
 pub struct DownloadStateWrapper(pub Arc<Mutex<DownloadState>>);
@@ -53,6 +81,30 @@ pub fn get_default_whisper_model() -> String {
     get_default_model()
 }
 
+/// Get the user's chosen default model (None if not set)
+#[tauri::command]
+pub async fn get_default_model_setting(app: AppHandle) -> Result<Option<String>, String> {
+    let pool = open_user_db(&app).await.map_err(|e| e.to_string())?;
+    get_setting(&pool, DEFAULT_MODEL_SETTING)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set the model `transcribe` uses when no model is passed
+/// Pass None to go back to picking the most accurate installed model
+#[tauri::command]
+pub async fn set_default_model_setting(app: AppHandle, model_name: Option<String>) -> Result<(), String> {
+    if let Some(name) = &model_name {
+        // Reject names that don't map to a known or imported model
+        get_model_path(&app, name).map_err(|e| e.to_string())?;
+    }
+
+    let pool = open_user_db(&app).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, DEFAULT_MODEL_SETTING, model_name.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get path to a model file
 #[tauri::command]
 pub fn get_whisper_model_path(app: AppHandle, model_name: String) -> Result<String, String> {
@@ -68,25 +120,29 @@ pub fn get_installed_whisper_models(app: AppHandle) -> Result<Vec<InstalledModel
 }
 
 /// Download a Whisper model with progress events
+/// `parallel` fetches large models as several concurrent chunks (off by default)
 #[tauri::command]
 pub async fn download_whisper_model(
     model_name: String,
+    parallel: Option<bool>,
     app: AppHandle,
     download_state: tauri::State<'_, DownloadStateWrapper>,
 ) -> Result<String, String> {
     // Check if download already in progress
-    {
+    let cancel_token = {
         let mut state = download_state.0.lock().unwrap();
         if state.in_progress {
             return Err("Download already in progress".to_string());
         }
         state.in_progress = true;
         state.current_model = Some(model_name.clone());
-    }
+        state.cancel_token = Arc::new(AtomicBool::new(false));
+        state.cancel_token.clone()
+    };
 
     // Download with progress callback
     let app_clone = app.clone();
-    let result = download_model(&app, &model_name, move |progress| {
+    let result = download_model(&app, &model_name, parallel.unwrap_or(false), cancel_token, move |progress| {
         // Emit progress event to frontend
         let _ = app_clone.emit("model-download-progress", progress);
     })
@@ -105,12 +161,84 @@ pub async fn download_whisper_model(
     }
 }
 
+/// Import a ggml model file from disk (e.g. a fine-tuned model)
+/// The model is loaded once to validate it, so this runs off the main thread.
+#[tauri::command]
+pub async fn import_whisper_model(
+    app: AppHandle,
+    source_path: String,
+    display_name: String,
+) -> Result<InstalledModelInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        import_custom_model(&app, &PathBuf::from(source_path), &display_name)
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))?
+    .map_err(|e| e.to_string())
+}
+
+/// Check that an installed model loads, e.g. after a download finishes
+/// A corrupted or truncated file is reported invalid with a suggestion to re-download
+#[tauri::command]
+pub async fn validate_whisper_model(app: AppHandle, model_name: String) -> Result<ModelValidation, String> {
+    tokio::task::spawn_blocking(move || validate_model(&app, &model_name))
+        .await
+        .map_err(|e| format!("Validation task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Measure how fast an installed model transcribes on this machine
+/// The result is stored and used by `get_system_specs` for its recommendation
+/// Fails if another transcription is already running (it would skew the measurement too)
+#[tauri::command]
+pub async fn benchmark_transcription(
+    app: AppHandle,
+    transcription_state: tauri::State<'_, TranscriptionStateWrapper>,
+    model_name: String,
+) -> Result<BenchmarkResult, String> {
+    let _guard = TranscriptionGuard::acquire(&transcription_state.0)?;
+
+    benchmark::benchmark_model(&app, &model_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Delete a downloaded model
 #[tauri::command]
 pub fn delete_whisper_model(app: AppHandle, model_name: String) -> Result<(), String> {
     delete_model(&app, &model_name).map_err(|e| e.to_string())
 }
 
+/// Cancel the model download that is currently running
+/// Returns false if there was nothing to cancel
+#[tauri::command]
+pub fn cancel_model_download(
+    download_state: tauri::State<'_, DownloadStateWrapper>,
+) -> bool {
+    let state = download_state.0.lock().unwrap();
+    if !state.in_progress {
+        return false;
+    }
+    println!("[cancel_model_download] Cancelling download of {:?}", state.current_model);
+    state.cancel_token.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Cancel all running language pack downloads
+/// Returns false if there was nothing to cancel
+#[tauri::command]
+pub fn cancel_language_download(
+    download_state: tauri::State<'_, DownloadStateWrapper>,
+) -> bool {
+    let state = download_state.0.lock().unwrap();
+    if state.language_downloads_active == 0 {
+        return false;
+    }
+    println!("[cancel_language_download] Cancelling {} language download(s)", state.language_downloads_active);
+    state.language_cancel_token.store(true, Ordering::Relaxed);
+    true
+}
+
 /// Check if any download is in progress
 #[tauri::command]
 pub fn is_download_in_progress(
@@ -4,14 +4,23 @@
  * Exposes recording, transcription, and session management to the frontend
  */
 
-use crate::db::user::open_user_db;
-use crate::services::recording::{DeviceInfo, RecorderState, RecordingResult};
-use crate::services::sessions::{complete_session, create_session, SessionStats};
-use crate::services::transcription::transcribe_audio_file;
+use crate::db::user::{get_setting, open_user_db, set_setting, DEFAULT_MODEL_SETTING, PREFERRED_DEVICE_SETTING};
+use crate::services::audio_dir::{self, recording_path, resolve_audio_directory};
+use crate::services::lang::require_lang_code;
+use crate::services::model_download::{get_model_path, select_transcription_model, CUSTOM_MODEL_PREFIX};
+use crate::services::recording::{
+    check_recording_audible, check_wav_input, copy_partial_wav, find_markers, preferred_or_default, remove_marker,
+    repair_wav, write_marker, DetailedDeviceInfo, DeviceConfig, DeviceInfo, RecorderState, RecordingDsp, RecordingMarker,
+    RecordingResult, DEFAULT_AUDIBLE_THRESHOLD, MAX_PRE_ROLL_SECONDS,
+};
+use crate::services::sessions::{complete_session, create_session, rollback_session_on_error, SessionStats};
+use crate::services::transcription::{
+    transcribe_audio_file, waveform_peaks, ProgressCallback, TranscriptionOptions, TranscriptionWithSegments,
+};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use tauri::{Manager, State};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager, State};
 
 /// Global recorder state (shared across commands)
 pub struct RecorderStateWrapper(pub Mutex<RecorderState>);
@@ -20,6 +29,129 @@ pub struct RecorderStateWrapper(pub Mutex<RecorderState>);
 unsafe impl Send for RecorderStateWrapper {}
 unsafe impl Sync for RecorderStateWrapper {}
 
+/// Where recordings go unless the user picked a directory: `<app data>/audio`
+fn default_audio_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("audio"))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Directory new recordings are written to, created and checked for writability
+async fn recordings_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let default_dir = default_audio_dir(app_handle)?;
+    let pool = open_user_db(app_handle).await.map_err(|e| e.to_string())?;
+    resolve_audio_directory(&pool, &default_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recordings left unfinished in the default or the user's audio directory
+pub async fn find_interrupted_recordings(app_handle: &tauri::AppHandle) -> Vec<RecordingMarker> {
+    let mut dirs: Vec<PathBuf> = default_audio_dir(app_handle).into_iter().collect();
+    if let Ok(pool) = open_user_db(app_handle).await {
+        if let Ok(Some(dir)) = audio_dir::get_audio_directory(&pool).await {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    dirs.iter().flat_map(|dir| find_markers(dir.as_path())).collect()
+}
+
+/// The directory the user picked for recordings (None when using the default)
+#[tauri::command]
+pub async fn get_audio_directory(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let dir = audio_dir::get_audio_directory(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(dir.map(|dir| dir.to_string_lossy().to_string()))
+}
+
+/// Save new recordings to `path` (must be absolute; created if missing and checked
+/// for writability), or back to the default directory when None
+/// Existing recordings stay where they are.
+#[tauri::command]
+pub async fn set_audio_directory(app_handle: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    audio_dir::set_audio_directory(&pool, path.as_deref().map(Path::new))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Emitted when the preferred device is missing and recording uses the system default
+pub const DEVICE_FALLBACK_EVENT: &str = "recording_device_fallback";
+
+/// Payload of `DEVICE_FALLBACK_EVENT`
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceFallback {
+    /// Saved device that couldn't be found
+    pub preferred_device: String,
+    /// Default device recorded from instead (None if it has no name)
+    pub fallback_device: Option<String>,
+}
+
+/// The input device the user picked for recordings (None when using the system default)
+#[tauri::command]
+pub async fn get_preferred_device(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_setting(&pool, PREFERRED_DEVICE_SETTING)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Record from `device_name` when `start_recording` isn't given a device, or from the
+/// system default when None
+/// The device doesn't have to be connected now; recording falls back to the default while it's missing.
+#[tauri::command]
+pub async fn set_preferred_device(app_handle: tauri::AppHandle, device_name: Option<String>) -> Result<(), String> {
+    let device_name = device_name.filter(|name| !name.trim().is_empty());
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, PREFERRED_DEVICE_SETTING, device_name.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recordings left unfinished by a crash, found at startup
+pub struct InterruptedRecordingsWrapper(pub Mutex<Vec<RecordingMarker>>);
+
+/// Shared state so only one full transcription runs at a time
+/// (two at once thrash the CPU and can run out of memory)
+#[derive(Default)]
+pub struct TranscriptionState {
+    pub in_progress: bool,
+}
+
+pub struct TranscriptionStateWrapper(pub Arc<Mutex<TranscriptionState>>);
+
+/// Marks a transcription as running; clears the flag when dropped, even on error
+pub struct TranscriptionGuard {
+    state: Arc<Mutex<TranscriptionState>>,
+}
+
+impl TranscriptionGuard {
+    /// Claim the transcription slot, failing if another transcription holds it
+    pub fn acquire(state: &Arc<Mutex<TranscriptionState>>) -> Result<Self, String> {
+        let mut current = state.lock().map_err(|e| e.to_string())?;
+        if current.in_progress {
+            return Err("A transcription is already in progress".to_string());
+        }
+        current.in_progress = true;
+
+        Ok(Self { state: state.clone() })
+    }
+}
+
+impl Drop for TranscriptionGuard {
+    fn drop(&mut self) {
+        let mut current = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        current.in_progress = false;
+    }
+}
+
 /// Get list of available recording devices
 #[tauri::command]
 pub async fn get_recording_devices(_app_handle: tauri::AppHandle,
@@ -29,40 +161,167 @@ pub async fn get_recording_devices(_app_handle: tauri::AppHandle,
     state.enumerate_devices()
 }
 
+/// Get recording devices with the sample rates, channel counts and formats they support
+#[tauri::command]
+pub async fn get_recording_devices_detailed(
+    recorder: State<'_, RecorderStateWrapper>,
+) -> Result<Vec<DetailedDeviceInfo>, String> {
+    let state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
+    state.enumerate_devices_detailed()
+}
+
+/// Get the sample rate, channels and sample format a device would record with
+/// Uses the default input device if `device_name` is None
+#[tauri::command]
+pub async fn probe_device(
+    recorder: State<'_, RecorderStateWrapper>,
+    device_name: Option<String>,
+) -> Result<DeviceConfig, String> {
+    let state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
+    state.probe_device(device_name)
+}
+
 /// Start recording audio
+/// Without a `device_name` the preferred device is used, or the system default if it's
+/// missing (emitting `DEVICE_FALLBACK_EVENT`)
+/// `dsp` enables optional high-pass filtering / noise gating (off when omitted)
+/// `force_16k_mono` records straight to 16kHz mono even if the device defaults to
+/// something else (off when omitted)
 #[tauri::command]
 pub async fn start_recording(_app_handle: tauri::AppHandle,
     app: tauri::AppHandle,
     recorder: State<'_, RecorderStateWrapper>,
     device_name: Option<String>,
     session_id: String,
+    dsp: Option<RecordingDsp>,
+    force_16k_mono: Option<bool>,
 ) -> Result<(), String> {
-    // Get app data directory
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Create audio subdirectory
-    let audio_dir = app_data_dir.join("audio");
-    std::fs::create_dir_all(&audio_dir)
-        .map_err(|e| format!("Failed to create audio directory: {}", e))?;
+    // Audio directory (created if needed), then an absolute output path in it
+    let audio_dir = recordings_dir(&app).await?;
+    let output_path = recording_path(&audio_dir, &session_id);
 
-    // Create output path with absolute path
-    let output_path = audio_dir.join(format!("{}.wav", session_id));
+    let preferred_device = match device_name {
+        Some(_) => None,
+        None => {
+            let pool = open_user_db(&app).await.map_err(|e| e.to_string())?;
+            get_setting(&pool, PREFERRED_DEVICE_SETTING)
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    };
 
     // Start recording
     let mut state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
-    state.start_recording(device_name, output_path)
+    let device_name = match preferred_device {
+        Some(preferred) => {
+            let devices = state.enumerate_devices()?;
+            let device = preferred_or_default(Some(preferred.clone()), &devices);
+            if device.is_none() {
+                println!("[start_recording] Preferred device '{}' not found, using the default", preferred);
+                let fallback = DeviceFallback {
+                    preferred_device: preferred,
+                    fallback_device: devices.into_iter().find(|d| d.is_default).map(|d| d.name),
+                };
+                let _ = app.emit(DEVICE_FALLBACK_EVENT, fallback);
+            }
+            device
+        }
+        None => device_name,
+    };
+    state.start_recording(
+        device_name,
+        output_path.clone(),
+        dsp.unwrap_or_default(),
+        force_16k_mono.unwrap_or(false),
+    )?;
+
+    // Lets the recording be recovered if the app quits before it's stopped
+    if let Err(e) = write_marker(&session_id, &output_path) {
+        println!("[start_recording] {}", e);
+    }
+    Ok(())
+}
+
+/// Keep the last `seconds` of microphone audio buffered so recordings include
+/// the moment before the record button was pressed (0 disables)
+/// Applies to recordings started on the same device
+#[tauri::command]
+pub async fn set_pre_roll(
+    recorder: State<'_, RecorderStateWrapper>,
+    device_name: Option<String>,
+    seconds: f32,
+) -> Result<(), String> {
+    if !(0.0..=MAX_PRE_ROLL_SECONDS).contains(&seconds) {
+        return Err(format!("Pre-roll must be between 0 and {} seconds", MAX_PRE_ROLL_SECONDS));
+    }
+
+    let mut state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
+    state.set_pre_roll(device_name, seconds)
 }
 
 /// Stop recording and return metadata
+/// `normalize` (default false) rescales the recording to a consistent loudness
 #[tauri::command]
 pub async fn stop_recording(_app_handle: tauri::AppHandle,
     recorder: State<'_, RecorderStateWrapper>,
+    normalize: Option<bool>,
 ) -> Result<RecordingResult, String> {
     let mut state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
-    state.stop_recording()
+    let result = state.stop_recording(normalize.unwrap_or(false))?;
+    remove_marker(Path::new(&result.file_path));
+    Ok(result)
+}
+
+/// Get recordings that were still in progress when the app last quit
+#[tauri::command]
+pub async fn get_interrupted_recordings(
+    interrupted: State<'_, InterruptedRecordingsWrapper>,
+) -> Result<Vec<RecordingMarker>, String> {
+    let markers = interrupted.0.lock().map_err(|e| e.to_string())?;
+    Ok(markers.clone())
+}
+
+/// An interrupted recording made readable again
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredRecording {
+    pub session_id: String,
+    pub audio_path: String,
+    pub duration_seconds: f32,
+}
+
+/// Repair the WAV of a recording interrupted by a crash so it can be transcribed
+/// and completed like any other session
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn recover_interrupted_recording(
+    interrupted: State<'_, InterruptedRecordingsWrapper>,
+    sessionId: String,
+) -> Result<RecoveredRecording, String> {
+    let marker = {
+        let markers = interrupted.0.lock().map_err(|e| e.to_string())?;
+        markers
+            .iter()
+            .find(|marker| marker.session_id == sessionId)
+            .cloned()
+            .ok_or_else(|| format!("No interrupted recording for session {}", sessionId))?
+    };
+
+    let audio_path = Path::new(&marker.audio_path);
+    let duration_seconds = repair_wav(audio_path)?;
+    remove_marker(audio_path);
+    interrupted
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .retain(|m| m.session_id != sessionId);
+
+    println!("[recover_interrupted_recording] Recovered {:.1}s for session {}", duration_seconds, sessionId);
+    Ok(RecoveredRecording {
+        session_id: marker.session_id,
+        audio_path: marker.audio_path,
+        duration_seconds,
+    })
 }
 
 /// Check if currently recording
@@ -78,62 +337,53 @@ pub async fn is_recording(_app_handle: tauri::AppHandle, recorder: State<'_, Rec
 pub struct TranscriptionResponse {
     pub text: String,
     pub segments: Vec<crate::services::transcription::TranscriptSegment>,
+    /// Set when the GPU couldn't be used and transcription fell back to the CPU
+    pub warning: Option<String>,
+    /// Language confidently detected instead of the requested one (only with `check_language`),
+    /// so the frontend can warn before the session's vocabulary is filed under the wrong language
+    pub language_mismatch: Option<String>,
+}
+
+/// Payload of the `transcription_progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub audio_path: String,
+    /// 0-100
+    pub percent: u8,
 }
 
 /// Transcribe an audio file
+/// Emits `transcription_progress` events while Whisper runs
+/// `use_gpu` defaults to true; it only matters in builds with a GPU backend
+/// `initial_prompt` biases Whisper toward expected words; read-aloud sessions
+/// use their `source_text` when no prompt is given
+/// `trim_silence` (default true) skips silence at the start and end of the recording
+/// `translate_to_english` (default false) returns an English translation instead of
+/// the original text, whatever language was spoken; segment timings are kept
+/// `check_language` (default false) also detects the spoken language when one is
+/// specified and reports a confident mismatch in `language_mismatch`
+/// `n_threads` sets the CPU threads Whisper uses (all cores but one when omitted, at most one per core)
+/// Fails if another transcription is already running
 #[tauri::command]
 pub async fn transcribe(app_handle: tauri::AppHandle,
+    transcription_state: State<'_, TranscriptionStateWrapper>,
     audio_path: String,
     language: String,
     model_path: Option<String>,
     session_type: Option<String>,
+    use_gpu: Option<bool>,
+    initial_prompt: Option<String>,
+    source_text: Option<String>,
+    trim_silence: Option<bool>,
+    translate_to_english: Option<bool>,
+    check_language: Option<bool>,
+    n_threads: Option<u32>,
 ) -> Result<TranscriptionResponse, String> {
+    let _guard = TranscriptionGuard::acquire(&transcription_state.0)?;
     let audio = Path::new(&audio_path);
 
-    // Get app data directory for absolute model paths
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    let models_dir = app_data_dir.join("models");
-
-    // Use default model path if not provided
-    // TODO: Make this configurable via settings
-    // Priority: large-v3 > large-v2 > large > medium > small > base > tiny
-    let model = model_path.map(PathBuf::from).unwrap_or_else(|| {
-        let large_v3 = models_dir.join("ggml-large-v3.bin");
-        let large_v2 = models_dir.join("ggml-large-v2.bin");
-        let large = models_dir.join("ggml-large.bin");
-        let medium = models_dir.join("ggml-medium.bin");
-        let small = models_dir.join("ggml-small.bin");
-        let base = models_dir.join("ggml-base.bin");
-        let tiny = models_dir.join("ggml-tiny.bin");
-
-        if large_v3.exists() {
-            large_v3
-        } else if large_v2.exists() {
-            large_v2
-        } else if large.exists() {
-            large
-        } else if medium.exists() {
-            medium
-        } else if small.exists() {
-            small
-        } else if base.exists() {
-            base
-        } else {
-            tiny
-        }
-    });
-
-    // Check if model exists
-    if !model.exists() {
-        return Err(format!(
-            "Whisper model not found at: {}. Please download a model first.",
-            model.display()
-        ));
-    }
+    let model = resolve_transcription_model(&app_handle, model_path).await?;
 
     // Determine language setting based on session type
     // For 'tutor' and 'conversation' modes, use auto-detection (None)
@@ -153,13 +403,250 @@ pub async fn transcribe(app_handle: tauri::AppHandle,
         }
     };
 
-    let result = transcribe_audio_file(audio, &model, language_opt)
+    let initial_prompt = match session_type.as_deref() {
+        Some("read_aloud") => initial_prompt.or(source_text),
+        _ => initial_prompt,
+    };
+    let options = TranscriptionOptions {
+        use_gpu: use_gpu.unwrap_or(true),
+        initial_prompt,
+        trim_silence: trim_silence.unwrap_or(true),
+        translate_to_english: translate_to_english.unwrap_or(false),
+        check_language: check_language.unwrap_or(false),
+        n_threads,
+        ..Default::default()
+    };
+
+    // Report progress so long recordings don't look stuck
+    let progress_app = app_handle.clone();
+    let progress_audio_path = audio_path.clone();
+    let on_progress: ProgressCallback = Box::new(move |percent| {
+        let _ = progress_app.emit(
+            "transcription_progress",
+            TranscriptionProgress {
+                audio_path: progress_audio_path.clone(),
+                percent,
+            },
+        );
+    });
+
+    let result = transcribe_audio_file(audio, &model, language_opt, options, Some(on_progress))
         .await
         .map_err(|e| e.to_string())?;
 
     Ok(TranscriptionResponse {
         text: result.text,
         segments: result.segments,
+        warning: result.warning,
+        language_mismatch: result.language_mismatch,
+    })
+}
+
+/// Resolve the Whisper model to transcribe with and check that it exists
+///
+/// Uses the user's default model if no path is provided, falling back to the most
+/// accurate installed model (see MODEL_PRIORITY). Imported models can be passed by
+/// name ("custom:<id>") instead of by path.
+async fn resolve_transcription_model(
+    app_handle: &tauri::AppHandle,
+    model_path: Option<String>,
+) -> Result<PathBuf, String> {
+    // Get app data directory for absolute model paths
+    let models_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("models");
+
+    let model = match model_path {
+        Some(name) if name.starts_with(CUSTOM_MODEL_PREFIX) => {
+            get_model_path(app_handle, &name).map_err(|e| e.to_string())?
+        }
+        Some(path) => PathBuf::from(path),
+        None => {
+            let pool = open_user_db(app_handle).await.map_err(|e| e.to_string())?;
+            let selected = get_setting(&pool, DEFAULT_MODEL_SETTING)
+                .await
+                .map_err(|e| e.to_string())?;
+            select_transcription_model(&models_dir, selected.as_deref())
+        }
+    };
+
+    if !model.exists() {
+        return Err(format!(
+            "Whisper model not found at: {}. Please download a model first.",
+            model.display()
+        ));
+    }
+
+    Ok(model)
+}
+
+/// Options for `transcribe_file`; everything is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscribeFileOptions {
+    /// Model path or "custom:<id>" (the default model when omitted)
+    pub model: Option<String>,
+    /// Language code; auto-detected when omitted
+    pub language: Option<String>,
+    /// Beam search width; greedy decoding when omitted
+    pub beam_size: Option<u32>,
+    pub use_gpu: Option<bool>,
+    pub initial_prompt: Option<String>,
+    /// Return English text whatever language was spoken
+    pub translate_to_english: Option<bool>,
+    /// Decoding hints; per-language defaults when omitted
+    pub suppress_blank: Option<bool>,
+    pub suppress_non_speech_tokens: Option<bool>,
+    pub no_speech_threshold: Option<f32>,
+    /// CPU threads for Whisper; all cores but one when omitted
+    pub n_threads: Option<u32>,
+}
+
+/// Transcribe any WAV file on disk without creating a session
+/// Session-independent: nothing is saved and no vocabulary is recorded.
+/// `path` must be absolute; non-WAV files are rejected.
+/// Fails if another transcription is already running
+#[tauri::command]
+pub async fn transcribe_file(
+    app_handle: tauri::AppHandle,
+    transcription_state: State<'_, TranscriptionStateWrapper>,
+    path: String,
+    options: Option<TranscribeFileOptions>,
+) -> Result<TranscriptionWithSegments, String> {
+    let _guard = TranscriptionGuard::acquire(&transcription_state.0)?;
+    let audio = PathBuf::from(&path);
+    check_wav_input(&audio)?;
+
+    let options = options.unwrap_or_default();
+    let model = resolve_transcription_model(&app_handle, options.model).await?;
+    let language = options.language.filter(|lang| !lang.trim().is_empty());
+
+    let transcription_options = TranscriptionOptions {
+        use_gpu: options.use_gpu.unwrap_or(true),
+        initial_prompt: options.initial_prompt,
+        beam_size: options.beam_size,
+        translate_to_english: options.translate_to_english.unwrap_or(false),
+        suppress_blank: options.suppress_blank,
+        suppress_non_speech_tokens: options.suppress_non_speech_tokens,
+        no_speech_threshold: options.no_speech_threshold,
+        n_threads: options.n_threads,
+        ..Default::default()
+    };
+
+    println!("[transcribe_file] Transcribing {}", audio.display());
+    transcribe_audio_file(&audio, &model, language.as_deref(), transcription_options, None)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get (min, max) peak pairs of a WAV file in `buckets` slices for drawing its waveform
+#[tauri::command]
+pub async fn get_audio_waveform(path: String, buckets: usize) -> Result<Vec<(f32, f32)>, String> {
+    tokio::task::spawn_blocking(move || waveform_peaks(Path::new(&path), buckets))
+        .await
+        .map_err(|e| format!("Waveform task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Whether a recording contains audio worth keeping, so the UI can warn about silent
+/// ones (e.g. a muted microphone) before the session is completed
+/// `threshold` is the level (0.0-1.0 of full scale) that counts as sound, about -40 dBFS by default
+#[tauri::command]
+pub async fn check_recording_audible_command(path: String, threshold: Option<f32>) -> Result<bool, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_AUDIBLE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err("Threshold must be between 0 and 1".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || check_recording_audible(Path::new(&path), threshold))
+        .await
+        .map_err(|e| format!("Audio check failed: {}", e))?
+}
+
+/// Model used for live previews: fast enough to keep up while the user is speaking
+const PARTIAL_MODEL_FILE: &str = "ggml-tiny.bin";
+
+/// Roughly transcribe a recording that is still in progress (live preview)
+/// Only the audio captured so far is transcribed, with the tiny model unless `model_path`
+/// is given; results are approximate and nothing is saved
+///
+/// Previews don't take the `TranscriptionGuard`: the final transcription has to start as
+/// soon as recording stops, even if a preview is still finishing. Instead a preview is
+/// skipped (empty, with a warning) while a full transcription runs.
+#[tauri::command]
+pub async fn transcribe_partial(app_handle: tauri::AppHandle,
+    recorder: State<'_, RecorderStateWrapper>,
+    transcription_state: State<'_, TranscriptionStateWrapper>,
+    audio_path: String,
+    model_path: Option<String>,
+    language: String,
+) -> Result<TranscriptionResponse, String> {
+    let models_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("models");
+
+    let model = match model_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let tiny = models_dir.join(PARTIAL_MODEL_FILE);
+            if tiny.exists() {
+                tiny
+            } else {
+                select_transcription_model(&models_dir, None)
+            }
+        }
+    };
+    if !model.exists() {
+        return Err(format!(
+            "Whisper model not found at: {}. Please download a model first.",
+            model.display()
+        ));
+    }
+
+    if transcription_state.0.lock().map_err(|e| e.to_string())?.in_progress {
+        return Ok(TranscriptionResponse {
+            text: String::new(),
+            segments: Vec::new(),
+            warning: Some("Preview skipped while another transcription is running".to_string()),
+            language_mismatch: None,
+        });
+    }
+
+    // Flush the writer so the header covers everything captured so far
+    let max_samples = {
+        let state = recorder.inner().0.lock().map_err(|e| e.to_string())?;
+        match state.snapshot_recording() {
+            Ok((path, samples)) if path == Path::new(&audio_path) => Some(samples),
+            _ => None,
+        }
+    };
+
+    let temp_dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    let partial_path = temp_dir.path().join("partial.wav");
+    let copied = copy_partial_wav(Path::new(&audio_path), &partial_path, max_samples)?;
+    if copied == 0 {
+        return Ok(TranscriptionResponse {
+            text: String::new(),
+            segments: Vec::new(),
+            warning: None,
+            language_mismatch: None,
+        });
+    }
+
+    let language_opt = if language.is_empty() { None } else { Some(language.as_str()) };
+    let result = transcribe_audio_file(&partial_path, &model, language_opt, TranscriptionOptions::default(), None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(TranscriptionResponse {
+        text: result.text,
+        segments: result.segments,
+        warning: result.warning,
+        language_mismatch: None,
     })
 }
 
@@ -178,6 +665,7 @@ pub struct CompleteSessionRequest {
 }
 
 /// Create a new recording session
+/// Language codes are normalized ("es-ES" -> "es"); unknown ones are rejected
 #[tauri::command]
 pub async fn create_recording_session(app_handle: tauri::AppHandle,
     language: String,
@@ -186,6 +674,8 @@ pub async fn create_recording_session(app_handle: tauri::AppHandle,
     text_library_id: Option<String>,
     source_text: Option<String>,
 ) -> Result<String, String> {
+    let language = require_lang_code(&language).map_err(|e| e.to_string())?;
+    let primary_language = require_lang_code(&primary_language).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
     create_session(
         &pool,
@@ -199,26 +689,67 @@ pub async fn create_recording_session(app_handle: tauri::AppHandle,
     .map_err(|e| e.to_string())
 }
 
+/// Create a session and start recording it in one step
+/// If the recorder fails to start, the new session is deleted again so no empty
+/// session is left behind. Returns the session id.
+#[tauri::command]
+pub async fn begin_session(
+    app_handle: tauri::AppHandle,
+    recorder: State<'_, RecorderStateWrapper>,
+    language: String,
+    primary_language: String,
+    device_name: Option<String>,
+    session_type: Option<String>,
+    text_library_id: Option<String>,
+    source_text: Option<String>,
+    dsp: Option<RecordingDsp>,
+) -> Result<String, String> {
+    let session_id = create_recording_session(
+        app_handle.clone(),
+        language,
+        primary_language,
+        session_type,
+        text_library_id,
+        source_text,
+    )
+    .await?;
+
+    let started = start_recording(
+        app_handle.clone(),
+        app_handle.clone(),
+        recorder,
+        device_name,
+        session_id.clone(),
+        dsp,
+        None,
+    )
+    .await;
+
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    rollback_session_on_error(&pool, &session_id, started)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(session_id)
+}
+
 /// Complete a recording session with transcript and stats
 #[tauri::command]
 pub async fn complete_recording_session(app_handle: tauri::AppHandle,
     request: CompleteSessionRequest,
 ) -> Result<SessionStats, String> {
+    let language = require_lang_code(&request.language).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
-    // Serialize segments to JSON
-    let segments_json = serde_json::to_string(&request.segments)
-        .map_err(|e| format!("Failed to serialize segments: {}", e))?;
-
     complete_session(
         &pool,
         &app_handle,
         &request.session_id,
         &request.audio_path,
         &request.transcript,
-        &segments_json,
+        &request.segments,
         request.duration_seconds,
-        &request.language,
+        &language,
         request.session_type.as_deref(),
         request.text_library_id.as_deref(),
         request.source_text.as_deref(),
@@ -240,3 +771,29 @@ pub async fn delete_audio_file(path: String) -> Result<(), String> {
     std::fs::remove_file(&path)
         .map_err(|e| format!("Failed to delete audio file: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcription_guard_rejects_concurrent_runs() {
+        let state = Arc::new(Mutex::new(TranscriptionState::default()));
+
+        let guard = TranscriptionGuard::acquire(&state).unwrap();
+        assert!(state.lock().unwrap().in_progress);
+        let err = TranscriptionGuard::acquire(&state).err().unwrap();
+        assert!(err.contains("already in progress"));
+
+        drop(guard);
+        assert!(!state.lock().unwrap().in_progress);
+
+        // Released on the error path too
+        let failing = || -> Result<(), String> {
+            let _guard = TranscriptionGuard::acquire(&state)?;
+            Err("Whisper failed".to_string())
+        };
+        assert!(failing().is_err());
+        assert!(TranscriptionGuard::acquire(&state).is_ok());
+    }
+}
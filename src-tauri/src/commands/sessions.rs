@@ -3,7 +3,9 @@
  */
 
 use crate::db::user::open_user_db;
-use crate::services::sessions::{delete_session, get_all_sessions, get_session, get_sessions_by_language, get_session_words, SessionData, SessionWord};
+use crate::services::pagination::Page;
+use crate::services::transcription::TranscriptSegment;
+use crate::services::sessions::{annotate_transcript, delete_session, export_session, get_all_sessions, get_all_sessions_paged, get_session, get_sessions_by_language, get_sessions_by_language_paged, get_sessions_filtered, get_read_aloud_score, get_session_filler_words, get_session_segments, get_session_subtitles, get_session_words, recompute_all_session_stats, recompute_session_word_newness, reprocess_session, retry_failed_words, set_session_pinned, split_session, toggle_session_favorite, get_favorite_sessions, DEFAULT_SPLIT_GAP_SECONDS, AnnotatedWord, FailedWord, FillerWordCount, ReadAloudScore, SessionData, SessionExport, SessionStats, SessionWord};
 
 /// Get all sessions (all languages)
 #[tauri::command]
@@ -14,6 +16,19 @@ pub async fn get_all_sessions_command(app_handle: tauri::AppHandle) -> Result<Ve
         .map_err(|e| e.to_string())
 }
 
+/// Get one page of sessions (all languages), with the total count
+#[tauri::command]
+pub async fn get_all_sessions_paged_command(
+    app_handle: tauri::AppHandle,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<SessionData>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_all_sessions_paged(&pool, limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get a single session by ID
 #[tauri::command]
 #[allow(non_snake_case)]
@@ -33,6 +48,46 @@ pub async fn get_sessions_by_language_command(app_handle: tauri::AppHandle, lang
         .map_err(|e| e.to_string())
 }
 
+/// Get one page of sessions for a language, with the total count
+#[tauri::command]
+pub async fn get_sessions_by_language_paged_command(
+    app_handle: tauri::AppHandle,
+    language: String,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<SessionData>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_sessions_by_language_paged(&pool, &language, limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get a language's sessions within a time window and/or of one session type
+/// Any filter left out is not applied; `limit` None returns every match
+#[tauri::command]
+pub async fn get_sessions_filtered_command(
+    app_handle: tauri::AppHandle,
+    language: String,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    session_type: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<SessionData>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_sessions_filtered(
+        &pool,
+        &language,
+        start_ts,
+        end_ts,
+        session_type.as_deref(),
+        limit,
+        offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 /// Get vocabulary words for a session
 #[tauri::command]
 #[allow(non_snake_case)]
@@ -43,6 +98,48 @@ pub async fn get_session_words_command(app_handle: tauri::AppHandle, sessionId:
         .map_err(|e| e.to_string())
 }
 
+/// Get a session's transcript segments with timings, for read-along highlighting
+/// Empty for sessions without stored segments
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_session_segments_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<Vec<TranscriptSegment>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_session_segments(&pool, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get a session's transcript as SRT or WebVTT subtitles (`format` is "srt" or "vtt")
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_session_subtitles_command(app_handle: tauri::AppHandle, sessionId: String, format: String) -> Result<String, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_session_subtitles(&pool, &sessionId, &format.to_lowercase())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get a session's transcript word by word, each marked new, known or mastered
+/// (for the highlighted transcript view)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn annotate_transcript_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<Vec<AnnotatedWord>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    annotate_transcript(&pool, &app_handle, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export a session as a zip (recording, transcript, SRT subtitles and metadata) to share it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export_session_command(app_handle: tauri::AppHandle, sessionId: String, destPath: String) -> Result<SessionExport, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    export_session(&pool, &sessionId, std::path::Path::new(&destPath))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Delete a session and its related data
 #[tauri::command]
 #[allow(non_snake_case)]
@@ -62,3 +159,109 @@ pub async fn delete_session_command(app_handle: tauri::AppHandle, sessionId: Str
     println!("[delete_session_command] Delete completed successfully");
     Ok(())
 }
+
+/// Pin or unpin a session so retention cleanup keeps it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn set_session_pinned_command(app_handle: tauri::AppHandle, sessionId: String, pinned: bool) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_session_pinned(&pool, &sessionId, pinned)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Mark or unmark a session as a favorite; returns whether it is now a favorite
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn toggle_session_favorite_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<bool, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    toggle_session_favorite(&pool, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get favorite sessions, for one language or all of them
+#[tauri::command]
+pub async fn get_favorite_sessions_command(app_handle: tauri::AppHandle, language: Option<String>) -> Result<Vec<SessionData>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_favorite_sessions(&pool, language.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-run vocabulary processing with a corrected transcript
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn reprocess_session_command(app_handle: tauri::AppHandle, sessionId: String, correctedTranscript: String) -> Result<SessionStats, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    reprocess_session(&pool, &app_handle, &sessionId, &correctedTranscript)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Retry saving words that failed when a session was completed (`failedWords` from its stats)
+/// Returns the words that failed again
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn retry_failed_words_command(app_handle: tauri::AppHandle, sessionId: String, failedWords: Vec<FailedWord>) -> Result<Vec<FailedWord>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    retry_failed_words(&pool, &sessionId, &failedWords)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recalculate word counts and WPM of every completed session from its stored transcript
+/// Emits `recompute_stats_progress`; returns the number of sessions updated
+#[tauri::command]
+pub async fn recompute_all_session_stats_command(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    recompute_all_session_stats(&pool, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recalculate which of a session's words were new (fixes sessions saved with every
+/// word marked as already known); returns the session's corrected new word count
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn recompute_session_word_newness_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<i64, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    recompute_session_word_newness(&pool, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Split a session into several at pauses longer than `minGapSeconds` (default 10s)
+/// The original is replaced by its parts; returns the new session IDs
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn split_session_command(
+    app_handle: tauri::AppHandle,
+    sessionId: String,
+    minGapSeconds: Option<f32>,
+) -> Result<Vec<String>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    split_session(&pool, &app_handle, &sessionId, minGapSeconds.unwrap_or(DEFAULT_SPLIT_GAP_SECONDS))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the word-level comparison between a read-aloud session and its source text
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_read_aloud_score_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<ReadAloudScore, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_read_aloud_score(&pool, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the filler word breakdown for a session
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_session_filler_words_command(app_handle: tauri::AppHandle, sessionId: String) -> Result<FillerWordCount, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_session_filler_words(&pool, &sessionId)
+        .await
+        .map_err(|e| e.to_string())
+}
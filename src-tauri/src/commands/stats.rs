@@ -4,8 +4,8 @@
 
 use crate::db::user::open_user_db;
 use crate::services::stats::{
-    get_daily_session_counts, get_overall_stats, get_top_words, get_vocab_growth, get_wpm_trends,
-    DailySessionCount, OverallStats, TopWord, VocabGrowth, WpmTrend,
+    get_daily_session_counts, get_overall_stats, get_stats_by_language, get_top_words, get_vocab_growth,
+    get_wpm_trends, DailySessionCount, LanguageStatsBreakdown, OverallStats, TopWord, VocabGrowth, WpmTrend,
 };
 
 /// Get overall statistics
@@ -17,6 +17,15 @@ pub async fn get_stats_overall(app_handle: tauri::AppHandle, language: Option<St
         .map_err(|e| e.to_string())
 }
 
+/// Get overall statistics combined and for each language, in one call
+#[tauri::command]
+pub async fn get_stats_all_languages(app_handle: tauri::AppHandle) -> Result<LanguageStatsBreakdown, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_stats_by_language(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get top N most practiced words
 #[tauri::command]
 pub async fn get_stats_top_words(app_handle: tauri::AppHandle, 
@@ -53,11 +62,14 @@ pub async fn get_stats_wpm_trends(app_handle: tauri::AppHandle,
         .map_err(|e| e.to_string())
 }
 
-/// Get vocabulary growth over time
+/// Get vocabulary growth over time (the last `days` only when given)
 #[tauri::command]
-pub async fn get_stats_vocab_growth(app_handle: tauri::AppHandle, language: String) -> Result<Vec<VocabGrowth>, String> {
+pub async fn get_stats_vocab_growth(app_handle: tauri::AppHandle,
+    language: String,
+    days: Option<i64>,
+) -> Result<Vec<VocabGrowth>, String> {
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
-    get_vocab_growth(&pool, &language)
+    get_vocab_growth(&pool, &language, days)
         .await
         .map_err(|e| e.to_string())
 }
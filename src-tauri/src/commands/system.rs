@@ -1,8 +1,11 @@
 /**
  * System information commands
- * Get CPU and RAM specs for intelligent Whisper model recommendations
+ * Get CPU, RAM and GPU specs for intelligent Whisper model recommendations
  */
 
+use crate::db::user::{self, open_user_db, IntegrityCheckResult, VacuumStats};
+use crate::services::benchmark::{load_benchmarks, recommend_from_benchmarks};
+use crate::services::model_download::get_models_dir;
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 use tauri::{AppHandle, Manager};
@@ -15,13 +18,25 @@ pub struct SystemSpecs {
     pub cpu_cores: usize,
     /// CPU brand/model name
     pub cpu_brand: String,
+    /// Whether whisper.cpp can use a GPU in this build (see `detect_gpu`)
+    pub gpu_available: bool,
+    /// GPU name, when the platform reports one
+    pub gpu_name: Option<String>,
     /// Recommended Whisper model based on system specs
     pub recommended_model: String,
 }
 
+/// A GPU that whisper.cpp can use
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuInfo {
+    pub available: bool,
+    pub name: Option<String>,
+}
+
 /// Get system specifications and model recommendation
+/// Measured benchmarks (see `benchmark_transcription`) take precedence over the heuristic
 #[tauri::command]
-pub fn get_system_specs() -> SystemSpecs {
+pub fn get_system_specs(app: AppHandle) -> SystemSpecs {
     let mut sys = System::new_all();
     sys.refresh_all();
 
@@ -36,17 +51,104 @@ pub fn get_system_specs() -> SystemSpecs {
         .map(|cpu| cpu.brand().to_string())
         .unwrap_or_else(|| "Unknown".to_string());
 
-    // Recommend model based on specs
-    let recommended_model = recommend_model(total_memory_gb, cpu_cores);
+    let gpu = detect_gpu();
+
+    // Recommend model based on measured speed if available, otherwise on specs
+    let benchmarks = get_models_dir(&app)
+        .and_then(|dir| load_benchmarks(&dir))
+        .unwrap_or_default();
+    let recommended_model = recommend_from_benchmarks(&benchmarks)
+        .unwrap_or_else(|| recommend_model(total_memory_gb, cpu_cores, gpu.available));
 
     SystemSpecs {
         total_memory_gb,
         cpu_cores,
         cpu_brand,
+        gpu_available: gpu.available,
+        gpu_name: gpu.name,
         recommended_model,
     }
 }
 
+/// Detect a GPU that whisper.cpp can use
+///
+/// A GPU only counts if this build has the matching whisper-rs backend enabled
+/// (cargo features `metal`, `cuda` or `vulkan`); without one, transcription runs
+/// on the CPU no matter what hardware is present.
+pub fn detect_gpu() -> GpuInfo {
+    let backends: [fn() -> Option<GpuInfo>; 3] = [detect_metal, detect_cuda, detect_vulkan];
+    backends
+        .iter()
+        .find_map(|detect| detect())
+        .unwrap_or_default()
+}
+
+/// Every Mac the app supports has a Metal-capable GPU
+#[cfg(all(target_os = "macos", feature = "metal"))]
+fn detect_metal() -> Option<GpuInfo> {
+    let name = command_output("system_profiler", &["SPDisplaysDataType"]).and_then(|output| {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Chipset Model:"))
+            .map(|name| name.trim().to_string())
+    });
+
+    Some(GpuInfo { available: true, name })
+}
+
+#[cfg(not(all(target_os = "macos", feature = "metal")))]
+fn detect_metal() -> Option<GpuInfo> {
+    None
+}
+
+/// NVIDIA GPU with a working driver (nvidia-smi ships with the driver)
+#[cfg(feature = "cuda")]
+fn detect_cuda() -> Option<GpuInfo> {
+    let output = command_output("nvidia-smi", &["--query-gpu=name", "--format=csv,noheader"])?;
+    let name = output.lines().next()?.trim().to_string();
+
+    Some(GpuInfo {
+        available: true,
+        name: Some(name),
+    })
+}
+
+#[cfg(not(feature = "cuda"))]
+fn detect_cuda() -> Option<GpuInfo> {
+    None
+}
+
+/// First hardware Vulkan device (software rasterizers like llvmpipe don't count)
+#[cfg(feature = "vulkan")]
+fn detect_vulkan() -> Option<GpuInfo> {
+    let output = command_output("vulkaninfo", &["--summary"])?;
+    let name = output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("deviceName"))
+        .map(|rest| rest.trim_start_matches([' ', '=']).trim().to_string())
+        .find(|name| !name.to_lowercase().contains("llvmpipe"))?;
+
+    Some(GpuInfo {
+        available: true,
+        name: Some(name),
+    })
+}
+
+#[cfg(not(feature = "vulkan"))]
+fn detect_vulkan() -> Option<GpuInfo> {
+    None
+}
+
+/// Stdout of a command, or None if it can't be run or fails
+#[cfg(any(feature = "metal", feature = "cuda", feature = "vulkan"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
 /// Recommend a Whisper model based on system specifications
 ///
 /// Logic based on real-world Whisper performance benchmarks:
@@ -68,8 +170,17 @@ pub fn get_system_specs() -> SystemSpecs {
 /// - Small: ~3x real-time (1min audio = 20sec)
 /// - Medium: ~2x real-time (1min audio = 30sec)
 /// - Large: ~1x real-time (1min audio = 60sec)
-fn recommend_model(ram_gb: f64, cpu_cores: usize) -> String {
-    if ram_gb >= 16.0 && cpu_cores >= 8 {
+///
+/// With GPU acceleration the larger models run several times faster, so medium and
+/// large become practical when there's enough memory to hold them.
+fn recommend_model(ram_gb: f64, cpu_cores: usize, gpu_available: bool) -> String {
+    if gpu_available && ram_gb >= 16.0 {
+        // GPU with plenty of memory: large-v3 is fast enough for interactive use
+        "large-v3".to_string()
+    } else if gpu_available && ram_gb >= 8.0 {
+        // GPU with moderate memory: medium fits comfortably
+        "medium".to_string()
+    } else if ram_gb >= 16.0 && cpu_cores >= 8 {
         // High-end systems: recommend small (not large - it's too slow for real-time use)
         // Small provides excellent accuracy with much better speed
         "small".to_string()
@@ -121,3 +232,38 @@ pub fn reset_app_data(app: AppHandle) -> Result<(), String> {
     println!("[reset_app_data] App data reset complete!");
     Ok(())
 }
+
+/// Compact user.db to reclaim space left by deleted sessions
+#[tauri::command]
+pub async fn vacuum_user_db(app: AppHandle) -> Result<VacuumStats, String> {
+    let pool = open_user_db(&app).await.map_err(|e| e.to_string())?;
+    user::vacuum_db(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Check user.db for corruption
+#[tauri::command]
+pub async fn integrity_check_user_db(app: AppHandle) -> Result<IntegrityCheckResult, String> {
+    let pool = open_user_db(&app).await.map_err(|e| e.to_string())?;
+    user::integrity_check(&pool).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommend_model_cpu_only() {
+        assert_eq!(recommend_model(32.0, 16, false), "small");
+        assert_eq!(recommend_model(8.0, 4, false), "base");
+        assert_eq!(recommend_model(4.0, 2, false), "tiny");
+    }
+
+    #[test]
+    fn test_recommend_model_with_gpu() {
+        assert_eq!(recommend_model(32.0, 16, true), "large-v3");
+        assert_eq!(recommend_model(16.0, 4, true), "large-v3");
+        assert_eq!(recommend_model(8.0, 4, true), "medium");
+        // Not enough memory for the bigger models, same as CPU-only
+        assert_eq!(recommend_model(4.0, 2, true), "tiny");
+    }
+}
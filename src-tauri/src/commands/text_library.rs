@@ -4,9 +4,11 @@
 
 use crate::db::user::open_user_db;
 use crate::services::text_library::{
-    create_text_library_item, delete_text_library_item, get_all_text_library_items,
-    get_text_library_by_language, get_text_library_item, update_text_library_item,
-    CreateTextLibraryItem, TextLibraryItem, UpdateTextLibraryItem,
+    build_practice_text, create_text_library_item, create_text_library_item_from_url, delete_text_library_item,
+    get_all_text_library_items, get_all_text_library_tags, get_text_coverage,
+    get_text_library_by_language, get_text_library_by_tag, get_text_library_item, save_practice_text, update_text_library_item, CreateTextLibraryItem,
+    PracticeText, TextCoverage, TextLibraryItem, UpdateTextLibraryItem, NEEDS_PRACTICE_TAG,
+    get_reading_speed, set_reading_speed, ReadingSpeed,
 };
 
 /// Create a new text library item
@@ -49,6 +51,29 @@ pub async fn get_text_library_by_language_command(app_handle: tauri::AppHandle,
         .map_err(|e| e.to_string())
 }
 
+/// Get text library items in a language that carry a tag
+#[tauri::command]
+pub async fn get_text_library_by_tag_command(app_handle: tauri::AppHandle,
+    language: String,
+    tag: String,
+) -> Result<Vec<TextLibraryItem>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_text_library_by_tag(&pool, &language, &tag)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the distinct tags used by text library items in a language
+#[tauri::command]
+pub async fn get_all_text_library_tags_command(app_handle: tauri::AppHandle,
+    language: String,
+) -> Result<Vec<String>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_all_text_library_tags(&pool, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Update a text library item
 #[tauri::command]
 pub async fn update_text_library_item_command(app_handle: tauri::AppHandle, 
@@ -69,3 +94,67 @@ pub async fn delete_text_library_item_command(app_handle: tauri::AppHandle, id:
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Import a web page's article text into the text library
+#[tauri::command]
+pub async fn import_text_from_url(app_handle: tauri::AppHandle, 
+    url: String,
+    language: String,
+) -> Result<TextLibraryItem, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    create_text_library_item_from_url(&pool, &url, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the share of a text's words already in the user's vocabulary
+#[tauri::command]
+pub async fn get_text_coverage_command(app_handle: tauri::AppHandle, 
+    text_id: String,
+    language: String,
+) -> Result<TextCoverage, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_text_coverage(&pool, &text_id, &language, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Build a read-aloud text from the user's own sentences containing their weak words
+/// `tag` defaults to "needs-practice"; `save` stores the text in the text library
+#[tauri::command]
+pub async fn build_practice_text_command(app_handle: tauri::AppHandle,
+    language: String,
+    tag: Option<String>,
+    save: Option<bool>,
+) -> Result<PracticeText, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let practice = build_practice_text(&pool, &language, tag.as_deref().unwrap_or(NEEDS_PRACTICE_TAG))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if save.unwrap_or(false) {
+        save_practice_text(&pool, &language, &practice)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(practice)
+}
+
+/// Get the reading speed used for estimated durations
+#[tauri::command]
+pub async fn get_reading_speed_command(app_handle: tauri::AppHandle) -> Result<ReadingSpeed, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    get_reading_speed(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set the reading speed (overall and per language) used for estimated durations
+#[tauri::command]
+pub async fn set_reading_speed_command(app_handle: tauri::AppHandle, speed: ReadingSpeed) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_reading_speed(&pool, &speed)
+        .await
+        .map_err(|e| e.to_string())
+}
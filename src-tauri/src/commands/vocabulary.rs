@@ -3,20 +3,30 @@
  * Exposes vocabulary service to the frontend
  */
 
-use crate::db::user::open_user_db;
-use crate::services::vocabulary::{self, VocabStats, VocabWord, VocabWordWithTranslation};
+use crate::db::user::{
+    self, get_setting, open_user_db, resolve_primary_language, set_setting, UserProfile, VOCAB_NUMBERS_SETTING,
+};
+use crate::services::lang::require_lang_code;
+use crate::services::pagination::Page;
+use crate::services::translation::get_translation_provider;
+use crate::services::vocabulary::{
+    self, ClearedVocabulary, RecommendedWord, RetranslateSummary, SessionTypeDiscovery, SharedLemma, TagCounts, VocabStats, VocabWord,
+    VocabWordWithTranslation,
+};
 
 /// Record a word in user's vocabulary
 /// Returns true if word is new, false if already existed
+/// `language` is normalized ("ES", "spanish" -> "es"); unknown codes are rejected
 #[tauri::command]
 pub async fn record_word(app_handle: tauri::AppHandle, 
     lemma: String,
     language: String,
     form_spoken: String,
 ) -> Result<bool, String> {
+    let language = require_lang_code(&language).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
-    vocabulary::record_word(&pool, &lemma, &language, &form_spoken)
+    vocabulary::record_word(&pool, &lemma, &language, &form_spoken, None)
         .await
         .map_err(|e| e.to_string())
 }
@@ -31,6 +41,21 @@ pub async fn get_user_vocab(app_handle: tauri::AppHandle, language: String) -> R
         .map_err(|e| e.to_string())
 }
 
+/// Get one page of vocabulary for a language, with the total word count
+#[tauri::command]
+pub async fn get_user_vocab_paged(
+    app_handle: tauri::AppHandle,
+    language: String,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<VocabWord>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::get_user_vocab_paged(&pool, &language, limit, offset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Check if a word is new (not in vocabulary)
 #[tauri::command]
 pub async fn is_new_word(app_handle: tauri::AppHandle, lemma: String, language: String) -> Result<bool, String> {
@@ -51,6 +76,30 @@ pub async fn get_vocab_stats(app_handle: tauri::AppHandle, language: String) ->
         .map_err(|e| e.to_string())
 }
 
+/// Count new words by the session type they were first spoken in
+#[tauri::command]
+pub async fn get_vocab_discovery_by_session_type(
+    app_handle: tauri::AppHandle,
+    language: String,
+) -> Result<Vec<SessionTypeDiscovery>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::get_vocab_discovery_by_session_type(&pool, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Normalize and de-duplicate the spoken forms of every word ("Estoy," / "estoy")
+/// Returns the number of words changed
+#[tauri::command]
+pub async fn dedupe_vocab_forms(app_handle: tauri::AppHandle) -> Result<i32, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::dedupe_forms_spoken(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Clean up vocabulary lemmas by removing punctuation
 /// Returns the number of lemmas cleaned
 #[tauri::command]
@@ -63,17 +112,48 @@ pub async fn clean_vocab_punctuation(app_handle: tauri::AppHandle, ) -> Result<i
 }
 
 /// Get recently learned vocabulary with translations
+/// Translates into the profile's primary language unless `primary_language` is given
 #[tauri::command]
 pub async fn get_recent_vocab(
     app_handle: tauri::AppHandle,
     language: String,
-    primary_language: String,
+    primary_language: Option<String>,
     days: i32,
     limit: i32,
 ) -> Result<Vec<VocabWordWithTranslation>, String> {
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
-    vocabulary::get_recent_vocab(&pool, &app_handle, &language, &primary_language, days, limit)
+    vocabulary::get_recent_vocab(&pool, &language, primary_language.as_deref(), days, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Suggest common words of a language the user hasn't spoken yet, with translations
+#[tauri::command]
+pub async fn recommend_new_words(
+    app_handle: tauri::AppHandle,
+    language: String,
+    limit: Option<usize>,
+) -> Result<Vec<RecommendedWord>, String> {
+    let language = require_lang_code(&language).map_err(|e| e.to_string())?;
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::recommend_new_words(&pool, &app_handle, &language, limit.unwrap_or(20))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get words not spoken for `not_seen_days` days, oldest first (mastered words excluded)
+#[tauri::command]
+pub async fn get_stale_words(
+    app_handle: tauri::AppHandle,
+    language: String,
+    not_seen_days: i32,
+    limit: i32,
+) -> Result<Vec<VocabWord>, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::get_stale_words(&pool, &language, not_seen_days, limit)
         .await
         .map_err(|e| e.to_string())
 }
@@ -92,6 +172,26 @@ pub async fn delete_vocab_word(
         .map_err(|e| e.to_string())
 }
 
+/// Delete all vocabulary (and custom translations) for a language, keeping sessions
+/// `confirm_language` must name the same language, so a stray call can't wipe vocabulary
+#[tauri::command]
+pub async fn clear_vocabulary(
+    app_handle: tauri::AppHandle,
+    language: String,
+    confirm_language: String,
+) -> Result<ClearedVocabulary, String> {
+    let language = require_lang_code(&language).map_err(|e| e.to_string())?;
+    if require_lang_code(&confirm_language).ok().as_deref() != Some(language.as_str()) {
+        return Err(format!("Confirm by passing '{}' as confirm_language", language));
+    }
+
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::clear_vocabulary(&pool, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Toggle mastered status for a word
 /// Returns the new mastered status (true if now mastered, false if unmarked)
 #[tauri::command]
@@ -117,6 +217,8 @@ pub async fn set_custom_translation(
     custom_translation: String,
     notes: Option<String>,
 ) -> Result<(), String> {
+    let lang_from = require_lang_code(&lang_from).map_err(|e| e.to_string())?;
+    let lang_to = require_lang_code(&lang_to).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
     vocabulary::set_custom_translation(&pool, &lemma, &lang_from, &lang_to, &custom_translation, notes.as_deref())
@@ -125,14 +227,20 @@ pub async fn set_custom_translation(
 }
 
 /// Get a custom translation if it exists
+/// `lang_to` defaults to the profile's primary language
 #[tauri::command]
 pub async fn get_custom_translation(
     app_handle: tauri::AppHandle,
     lemma: String,
     lang_from: String,
-    lang_to: String,
+    lang_to: Option<String>,
 ) -> Result<Option<String>, String> {
+    let lang_from = require_lang_code(&lang_from).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let lang_to = resolve_primary_language(&pool, lang_to.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let lang_to = require_lang_code(&lang_to).map_err(|e| e.to_string())?;
 
     vocabulary::get_custom_translation(&pool, &lemma, &lang_from, &lang_to)
         .await
@@ -147,6 +255,8 @@ pub async fn delete_custom_translation(
     lang_from: String,
     lang_to: String,
 ) -> Result<(), String> {
+    let lang_from = require_lang_code(&lang_from).map_err(|e| e.to_string())?;
+    let lang_to = require_lang_code(&lang_to).map_err(|e| e.to_string())?;
     let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
 
     vocabulary::delete_custom_translation(&pool, &lemma, &lang_from, &lang_to)
@@ -200,6 +310,38 @@ pub async fn remove_vocab_tag(
         .map_err(|e| e.to_string())
 }
 
+/// Tag many words at once (replacing their current tag)
+/// Returns the number of words updated
+#[tauri::command]
+pub async fn add_vocab_tag_bulk(
+    app_handle: tauri::AppHandle,
+    lemmas: Vec<String>,
+    language: String,
+    tag: String,
+) -> Result<u64, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::add_tag_bulk(&pool, &language, &lemmas, &tag)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a tag from many words at once
+/// Returns the number of words that had it
+#[tauri::command]
+pub async fn remove_vocab_tag_bulk(
+    app_handle: tauri::AppHandle,
+    lemmas: Vec<String>,
+    language: String,
+    tag: String,
+) -> Result<u64, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::remove_tag_bulk(&pool, &language, &lemmas, &tag)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get vocabulary filtered by tag
 #[tauri::command]
 pub async fn get_vocab_by_tag(
@@ -213,3 +355,109 @@ pub async fn get_vocab_by_tag(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Count vocabulary words per tag, plus untagged words
+#[tauri::command]
+pub async fn get_tag_counts(
+    app_handle: tauri::AppHandle,
+    language: String,
+) -> Result<TagCounts, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::get_tag_counts(&pool, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Lemmas recorded in both languages, with their usage count in each
+#[tauri::command]
+pub async fn get_cross_language_overlap(
+    app_handle: tauri::AppHandle,
+    lang_a: String,
+    lang_b: String,
+) -> Result<Vec<SharedLemma>, String> {
+    let lang_a = require_lang_code(&lang_a).map_err(|e| e.to_string())?;
+    let lang_b = require_lang_code(&lang_b).map_err(|e| e.to_string())?;
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::get_cross_language_overlap(&pool, &lang_a, &lang_b)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether numbers ("2024") from transcripts are recorded as vocabulary
+#[tauri::command]
+pub async fn get_vocab_numbers_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let value = get_setting(&pool, VOCAB_NUMBERS_SETTING)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Turn recording numbers as vocabulary on or off
+#[tauri::command]
+pub async fn set_vocab_numbers_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    set_setting(&pool, VOCAB_NUMBERS_SETTING, Some(if enabled { "true" } else { "false" }))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-translate all words of a language into a new primary language
+/// Emits `retranslate_progress` events ({ processed, total }) while running
+#[tauri::command]
+pub async fn retranslate_vocab(
+    app_handle: tauri::AppHandle,
+    language: String,
+    new_primary_language: String,
+) -> Result<RetranslateSummary, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    vocabulary::retranslate_vocab(&pool, &app_handle, &language, &new_primary_language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Find lemmas in `from_lang` that translate to `word` in `to_lang`
+/// e.g. word "run", from "es", to "en" -> ["correr"]; custom translations come first
+/// `to_lang` defaults to the profile's primary language
+#[tauri::command]
+pub async fn reverse_translate(
+    app_handle: tauri::AppHandle,
+    word: String,
+    from_lang: String,
+    to_lang: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let from_lang = require_lang_code(&from_lang).map_err(|e| e.to_string())?;
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+    let to_lang = resolve_primary_language(&pool, to_lang.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let to_lang = require_lang_code(&to_lang).map_err(|e| e.to_string())?;
+    let provider = get_translation_provider(&app_handle, Some(&pool))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    provider
+        .reverse_translate(&word, &from_lang, &to_lang, limit.unwrap_or(20))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the user's profile (primary language etc.)
+#[tauri::command]
+pub async fn get_profile(app_handle: tauri::AppHandle) -> Result<UserProfile, String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    user::get_profile(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Save the user's profile
+#[tauri::command]
+pub async fn set_profile(app_handle: tauri::AppHandle, profile: UserProfile) -> Result<(), String> {
+    let pool = open_user_db(&app_handle).await.map_err(|e| e.to_string())?;
+
+    user::set_profile(&pool, &profile).await.map_err(|e| e.to_string())
+}
@@ -28,7 +28,7 @@ pub async fn open_lemma_db(lang: &str, app: &AppHandle) -> Result<SqlitePool> {
 /// Priority order:
 /// 1. Bundled resources (English only)
 /// 2. Downloaded packs in app data directory
-fn get_lemma_db_path(lang: &str, app: &AppHandle) -> Result<PathBuf> {
+pub(crate) fn get_lemma_db_path(lang: &str, app: &AppHandle) -> Result<PathBuf> {
     use tauri::Manager;
 
     // 1. Check if English (bundled with app)
@@ -62,10 +62,138 @@ fn get_lemma_db_path(lang: &str, app: &AppHandle) -> Result<PathBuf> {
     )
 }
 
+/// Opens a connection to a pairwise translation database
+///
+/// # Arguments
+/// * `from` - Source language code (e.g., "es")
+/// * `to` - Target language code (e.g., "en")
+/// * `app` - Tauri app handle for path resolution
+///
+/// # Returns
+/// Connection pool to langpacks/translations/{from}-{to}.db
+pub async fn open_translation_db(from: &str, to: &str, app: &AppHandle) -> Result<SqlitePool> {
+    let db_path = get_translation_db_path(from, to, app)?;
+
+    let connection_string = format!("sqlite://{}?mode=ro", db_path.display());
+
+    SqlitePool::connect(&connection_string)
+        .await
+        .context(format!("Failed to open translation database for {}-{}", from, to))
+}
+
+/// Opens a pairwise translation database for writing
+///
+/// Only for `append_official_translation`; everything else reads packs read-only.
+async fn open_translation_db_writable(from: &str, to: &str, app: &AppHandle) -> Result<SqlitePool> {
+    let db_path = get_translation_db_path(from, to, app)?;
+
+    let connection_string = format!("sqlite://{}?mode=rw", db_path.display());
+
+    SqlitePool::connect(&connection_string)
+        .await
+        .context(format!("Failed to open translation database for {}-{} for writing", from, to))
+}
+
+/// Adds a translation to the downloaded {from}-{to} pack itself
+///
+/// The row is appended, so existing entries for the lemma keep priority; it fills
+/// gaps and adds alternatives. Re-downloading the pack discards it.
+///
+/// # Returns
+/// false if the pack already had this translation
+pub async fn append_official_translation(
+    lemma: &str,
+    from: &str,
+    to: &str,
+    translation: &str,
+    app: &AppHandle,
+) -> Result<bool> {
+    let pool = open_translation_db_writable(from, to, app).await?;
+    let inserted = insert_translation(&pool, lemma, translation).await;
+    pool.close().await;
+    inserted
+}
+
+/// Appends a `translations` row unless the same pair is already there
+async fn insert_translation(pool: &SqlitePool, lemma: &str, translation: &str) -> Result<bool> {
+    let lemma = lemma.trim().to_lowercase();
+    let translation = translation.trim();
+    if lemma.is_empty() || translation.is_empty() {
+        anyhow::bail!("Lemma and translation can't be empty");
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO translations (lemma, translation)
+         SELECT ?1, ?2
+         WHERE NOT EXISTS (SELECT 1 FROM translations WHERE lemma = ?1 AND translation = ?2)",
+    )
+    .bind(&lemma)
+    .bind(translation)
+    .execute(pool)
+    .await
+    .context("Failed to add translation")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Resolves path to a downloaded translation database
+fn get_translation_db_path(from: &str, to: &str, app: &AppHandle) -> Result<PathBuf> {
+    use tauri::Manager;
+
+    let db_path = app
+        .path()
+        .app_data_dir()
+        .context("Failed to get app data directory")?
+        .join("langpacks")
+        .join("translations")
+        .join(format!("{}-{}.db", from, to));
+
+    if !db_path.exists() {
+        anyhow::bail!(
+            "Translation database not found for {}-{}. Please download the translation pack first.",
+            from,
+            to
+        );
+    }
+
+    Ok(db_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_insert_translation_appends_row() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE translations (id INTEGER PRIMARY KEY, lemma TEXT NOT NULL, translation TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO translations (lemma, translation) VALUES ('banco', 'bank')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(insert_translation(&pool, " Banco ", "bench").await.unwrap());
+        assert!(insert_translation(&pool, "perro", "dog").await.unwrap());
+        assert!(!insert_translation(&pool, "banco", "bench").await.unwrap());
+        assert!(insert_translation(&pool, "gato", " ").await.is_err());
+
+        // Pack entries come first, appended ones after
+        let banco: Vec<String> = sqlx::query_scalar("SELECT translation FROM translations WHERE lemma = 'banco' ORDER BY id ASC")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(banco, vec!["bank", "bench"]);
+
+        let perro: Option<String> = sqlx::query_scalar("SELECT translation FROM translations WHERE lemma = 'perro' ORDER BY id ASC")
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+        assert_eq!(perro.as_deref(), Some("dog"));
+    }
+
     #[tokio::test]
     async fn test_open_lemma_db_spanish() {
         // Note: This test requires the Spanish lemma database to be downloaded
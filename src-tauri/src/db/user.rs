@@ -6,10 +6,14 @@
  * - Vocab (user's discovered words with usage tracking)
  * - Session Words (junction table linking sessions to words)
  * - Text Library (imported texts for read-aloud practice)
+ * - Settings (key/value user preferences, including the profile)
+ *
+ * Schema changes are numbered migrations tracked in the schema_version table.
  */
 
 use anyhow::{Context, Result};
-use sqlx::sqlite::SqlitePool;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
 use std::path::PathBuf;
 use tauri::Manager;
 
@@ -29,7 +33,7 @@ pub fn get_user_db_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
 }
 
 /// Initialize user database with schema
-/// Creates tables if they don't exist
+/// Creates the file if needed and brings the schema up to date
 pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqlitePool> {
     let db_path = get_user_db_path(app_handle)?;
     println!("[initialize_user_db] Database path: {:?}", db_path);
@@ -39,6 +43,273 @@ pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqliteP
         .await
         .context("Failed to connect to user database")?;
 
+    run_migrations(&pool).await?;
+
+    Ok(pool)
+}
+
+/// Open connection to existing user database
+pub async fn open_user_db(app_handle: &tauri::AppHandle) -> Result<SqlitePool> {
+    let db_path = get_user_db_path(app_handle)?;
+
+    if !db_path.exists() {
+        // Database doesn't exist, initialize it
+        return initialize_user_db(app_handle).await;
+    }
+
+    let connection_string = format!("sqlite://{}?mode=rw", db_path.display());
+
+    let pool = SqlitePool::connect(&connection_string)
+        .await
+        .context("Failed to open user database")?;
+
+    // Run migrations for existing databases
+    run_migrations(&pool).await?;
+
+    Ok(pool)
+}
+
+/// Latest schema version, i.e. the number of the last step in `apply_migration`
+pub const LATEST_SCHEMA_VERSION: i64 = 12;
+
+/// Current schema version of the database (0 if no migration has run yet)
+pub async fn get_schema_version(pool: &SqlitePool) -> Result<i64> {
+    create_schema_version_table(pool).await?;
+
+    let (version,): (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(version.unwrap_or(0))
+}
+
+async fn create_schema_version_table(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )
+        "#
+    )
+    .execute(pool)
+    .await
+    .context("Failed to create schema_version table")?;
+
+    Ok(())
+}
+
+/// Apply every migration newer than the recorded schema version, in order
+///
+/// Each step runs in its own transaction together with its schema_version row,
+/// so a failed step leaves the database at the previous version.
+/// Databases created before schema_version existed start at 0; the steps check
+/// for existing tables and columns, so replaying them on those is safe.
+/// Returns the number of steps applied.
+pub async fn run_migrations(pool: &SqlitePool) -> Result<usize> {
+    let current = get_schema_version(pool).await?;
+    let mut applied = 0;
+
+    for version in (current + 1)..=LATEST_SCHEMA_VERSION {
+        let mut tx = pool.begin().await?;
+
+        // Claim the version first: the write takes the database lock, and if another
+        // connection already applied this step the insert is a no-op and we skip it
+        let claimed = sqlx::query(
+            "INSERT OR IGNORE INTO schema_version (version, description, applied_at) VALUES (?, '', ?)"
+        )
+        .bind(version)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if claimed == 0 {
+            tx.rollback().await?;
+            continue;
+        }
+
+        let description = apply_migration(&mut tx, version)
+            .await
+            .with_context(|| format!("Migration {} failed", version))?;
+
+        sqlx::query("UPDATE schema_version SET description = ? WHERE version = ?")
+            .bind(description)
+            .bind(version)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        println!("[DB Migration] Applied migration {}: {}", version, description);
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+/// Run a single migration step and return its description
+/// Steps must stay idempotent and must never be edited once released - add a new one instead
+async fn apply_migration(conn: &mut SqliteConnection, version: i64) -> Result<&'static str> {
+    match version {
+        1 => {
+            create_initial_tables(conn).await?;
+            Ok("Create sessions, vocab, text_library and session_words tables")
+        }
+        2 => {
+            add_column_if_missing(conn, "sessions", "primary_language", "TEXT DEFAULT 'en'").await?;
+            add_column_if_missing(conn, "sessions", "segments", "TEXT").await?;
+            Ok("Add primary_language and segments to sessions")
+        }
+        3 => {
+            if add_column_if_missing(conn, "vocab", "tags", "TEXT DEFAULT '[]'").await? {
+                // One-time conversion of the old mastered flag into a tag
+                sqlx::query(
+                    r#"
+                    UPDATE vocab
+                    SET tags = CASE
+                        WHEN mastered = 1 THEN '["mastered"]'
+                        ELSE '[]'
+                    END
+                    "#
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+
+            // Create index for filtering by tags
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_tags ON vocab(tags)")
+                .execute(&mut *conn)
+                .await?;
+            Ok("Add tags to vocab")
+        }
+        4 => {
+            // Custom translations table for user-customized translations
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS custom_translations (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    lemma TEXT NOT NULL,
+                    lang_from TEXT NOT NULL,
+                    lang_to TEXT NOT NULL,
+                    custom_translation TEXT NOT NULL,
+                    notes TEXT,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL,
+
+                    UNIQUE(lemma, lang_from, lang_to)
+                )
+                "#
+            )
+            .execute(&mut *conn)
+            .await
+            .context("Failed to create custom_translations table")?;
+
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_custom_translations_lookup ON custom_translations(lemma, lang_from, lang_to)")
+                .execute(&mut *conn)
+                .await?;
+            Ok("Create custom_translations table")
+        }
+        5 => {
+            // Dictionaries table for external dictionary lookups
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS dictionaries (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    language TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    url_template TEXT NOT NULL,
+                    target_language TEXT DEFAULT 'en',
+                    dict_type TEXT NOT NULL CHECK(dict_type IN ('embedded', 'popup')),
+                    is_active INTEGER NOT NULL DEFAULT 1,
+                    sort_order INTEGER NOT NULL,
+                    is_default INTEGER NOT NULL DEFAULT 1,
+                    created_at INTEGER NOT NULL
+                )
+                "#
+            )
+            .execute(&mut *conn)
+            .await
+            .context("Failed to create dictionaries table")?;
+
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_dictionaries_lang ON dictionaries(language, is_active, sort_order)")
+                .execute(&mut *conn)
+                .await?;
+
+            // Lookups also treat NULL as English
+            add_column_if_missing(conn, "dictionaries", "target_language", "TEXT DEFAULT 'en'").await?;
+
+            seed_default_dictionaries(conn).await?;
+            Ok("Create dictionaries table with default dictionaries")
+        }
+        6 => {
+            add_column_if_missing(conn, "sessions", "is_pinned", "INTEGER DEFAULT 0").await?;
+            Ok("Add is_pinned to sessions (kept by retention cleanup)")
+        }
+        7 => {
+            add_column_if_missing(conn, "sessions", "accuracy_score", "REAL").await?;
+            Ok("Add accuracy_score to sessions (read-aloud scoring)")
+        }
+        8 => {
+            add_column_if_missing(conn, "sessions", "filler_count", "INTEGER").await?;
+            Ok("Add filler_count to sessions")
+        }
+        9 => {
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS settings (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL,
+                    updated_at INTEGER NOT NULL
+                )"
+            )
+            .execute(&mut *conn)
+            .await?;
+            Ok("Create settings table")
+        }
+        10 => {
+            add_column_if_missing(conn, "sessions", "articulation_wpm", "REAL").await?;
+            Ok("Add articulation_wpm to sessions")
+        }
+        11 => {
+            add_column_if_missing(conn, "vocab", "first_session_id", "TEXT").await?;
+            Ok("Add first_session_id to vocab")
+        }
+        12 => {
+            add_column_if_missing(conn, "sessions", "is_favorite", "INTEGER DEFAULT 0").await?;
+            Ok("Add is_favorite to sessions")
+        }
+        _ => anyhow::bail!("Unknown migration version {}", version),
+    }
+}
+
+/// Add a column unless the table already has it
+/// Returns true if the column was added
+async fn add_column_if_missing(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<bool> {
+    let (exists,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if exists > 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+        .execute(&mut *conn)
+        .await
+        .with_context(|| format!("Failed to add {}.{}", table, column))?;
+
+    Ok(true)
+}
+
+/// Base schema: sessions, vocab, text_library and session_words
+async fn create_initial_tables(conn: &mut SqliteConnection) -> Result<()> {
     // Create sessions table
     sqlx::query(
         r#"
@@ -66,37 +337,23 @@ pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqliteP
         )
         "#
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .context("Failed to create sessions table")?;
 
     // Create sessions indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_sessions_language ON sessions(language)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_sessions_created_at ON sessions(created_at DESC)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_sessions_type ON sessions(session_type)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
-    // Migration: Add primary_language column to existing sessions tables
-    // This will add the column if it doesn't exist (for existing databases)
-    let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN primary_language TEXT DEFAULT 'en'")
-        .execute(&pool)
-        .await;
-    // Ignore errors - column might already exist
-
-    // Migration: Add segments column to existing sessions tables
-    // This will add the column if it doesn't exist (for existing databases)
-    let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN segments TEXT")
-        .execute(&pool)
-        .await;
-    // Ignore errors - column might already exist
-
     // Create vocab table
     sqlx::query(
         r#"
@@ -120,58 +377,21 @@ pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqliteP
         )
         "#
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .context("Failed to create vocab table")?;
 
     // Create vocab indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_language ON vocab(language)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_first_seen ON vocab(first_seen_at)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_usage_count ON vocab(usage_count DESC)")
-        .execute(&pool)
-        .await?;
-
-    // Migration: Add tags column to vocab table (check if it exists first)
-    let column_exists: i32 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM pragma_table_info('vocab') WHERE name = 'tags'"
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or(0);
-
-    if column_exists == 0 {
-        // Column doesn't exist, add it
-        sqlx::query("ALTER TABLE vocab ADD COLUMN tags TEXT DEFAULT '[]'")
-            .execute(&pool)
-            .await?;
-
-        println!("[DB Migration] Added tags column to vocab table");
-
-        // Migration: Convert existing mastered boolean to tags (one-time conversion)
-        sqlx::query(
-            r#"
-            UPDATE vocab
-            SET tags = CASE
-                WHEN mastered = 1 THEN '["mastered"]'
-                ELSE '[]'
-            END
-            "#
-        )
-        .execute(&pool)
-        .await?;
-
-        println!("[DB Migration] Converted existing mastered values to tags");
-    }
-
-    // Create index for filtering by tags
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_tags ON vocab(tags)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     // Create text_library table
@@ -196,17 +416,17 @@ pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqliteP
         )
         "#
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .context("Failed to create text_library table")?;
 
     // Create text_library indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_text_library_language ON text_library(language)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_text_library_created_at ON text_library(created_at DESC)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     // Create session_words table
@@ -223,405 +443,347 @@ pub async fn initialize_user_db(app_handle: &tauri::AppHandle) -> Result<SqliteP
         )
         "#
     )
-    .execute(&pool)
+    .execute(&mut *conn)
     .await
     .context("Failed to create session_words table")?;
 
     // Create session_words indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_session_words_session ON session_words(session_id)")
-        .execute(&pool)
+        .execute(&mut *conn)
         .await?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_session_words_new ON session_words(is_new)")
-        .execute(&pool)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Insert the built-in dictionaries if the table is empty
+async fn seed_default_dictionaries(conn: &mut SqliteConnection) -> Result<()> {
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
+        .fetch_one(&mut *conn)
         .await?;
 
-    // Create custom_translations table for user-customized translations
+    if count.0 > 0 {
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Spanish dictionaries
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS custom_translations (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            lemma TEXT NOT NULL,
-            lang_from TEXT NOT NULL,
-            lang_to TEXT NOT NULL,
-            custom_translation TEXT NOT NULL,
-            notes TEXT,
-            created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL,
-
-            UNIQUE(lemma, lang_from, lang_to)
-        )
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('es', 'en', 'WordReference', 'https://www.wordreference.com/es/en/translation.asp?spen=[WORD]', 'popup', 1, 1, 1, ?),
+            ('es', 'en', 'SpanishDict', 'https://www.spanishdict.com/translate/[WORD]', 'popup', 1, 2, 1, ?),
+            ('es', 'en', 'Google Translate', 'https://translate.google.com/?sl=es&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
         "#
     )
-    .execute(&pool)
-    .await
-    .context("Failed to create custom_translations table")?;
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-    // Create custom_translations index
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_custom_translations_lookup ON custom_translations(lemma, lang_from, lang_to)")
-        .execute(&pool)
-        .await?;
-
-    // Create dictionaries table for external dictionary lookups
+    // Spanish→French and French→Spanish dictionaries
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS dictionaries (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            language TEXT NOT NULL,
-            name TEXT NOT NULL,
-            url_template TEXT NOT NULL,
-            dict_type TEXT NOT NULL CHECK(dict_type IN ('embedded', 'popup')),
-            is_active INTEGER NOT NULL DEFAULT 1,
-            sort_order INTEGER NOT NULL,
-            is_default INTEGER NOT NULL DEFAULT 1,
-            created_at INTEGER NOT NULL
-        )
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('es', 'fr', 'WordReference', 'https://www.wordreference.com/esfr/[WORD]', 'popup', 1, 1, 1, ?),
+            ('es', 'fr', 'Google Translate', 'https://translate.google.com/?sl=es&tl=fr&text=[WORD]&op=translate', 'popup', 0, 2, 1, ?),
+            ('fr', 'es', 'WordReference', 'https://www.wordreference.com/fres/[WORD]', 'popup', 1, 1, 1, ?),
+            ('fr', 'es', 'Google Translate', 'https://translate.google.com/?sl=fr&tl=es&text=[WORD]&op=translate', 'popup', 0, 2, 1, ?)
         "#
     )
-    .execute(&pool)
-    .await
-    .context("Failed to create dictionaries table")?;
-
-    // Create dictionaries index
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_dictionaries_lang ON dictionaries(language, is_active, sort_order)")
-        .execute(&pool)
-        .await?;
-
-    // Seed default dictionaries if table is empty
-    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
-        .fetch_one(&pool)
-        .await?;
-
-    if count.0 == 0 {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        // Spanish dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('es', 'WordReference', 'https://www.wordreference.com/es/en/translation.asp?spen=[WORD]', 'popup', 1, 1, 1, ?),
-                ('es', 'SpanishDict', 'https://www.spanishdict.com/translate/[WORD]', 'popup', 1, 2, 1, ?),
-                ('es', 'Google Translate', 'https://translate.google.com/?sl=es&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
-
-        // French dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('fr', 'WordReference', 'https://www.wordreference.com/fren/[WORD]', 'popup', 1, 1, 1, ?),
-                ('fr', 'Larousse', 'https://www.larousse.fr/dictionnaires/francais-anglais/[WORD]', 'popup', 1, 2, 1, ?),
-                ('fr', 'Google Translate', 'https://translate.google.com/?sl=fr&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
+
+    // French dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('fr', 'en', 'WordReference', 'https://www.wordreference.com/fren/[WORD]', 'popup', 1, 1, 1, ?),
+            ('fr', 'en', 'Larousse', 'https://www.larousse.fr/dictionnaires/francais-anglais/[WORD]', 'popup', 1, 2, 1, ?),
+            ('fr', 'en', 'Google Translate', 'https://translate.google.com/?sl=fr&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // German dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('de', 'WordReference', 'https://www.wordreference.com/deen/[WORD]', 'popup', 1, 1, 1, ?),
-                ('de', 'Dict.cc', 'https://www.dict.cc/?s=[WORD]', 'popup', 1, 2, 1, ?),
-                ('de', 'Google Translate', 'https://translate.google.com/?sl=de&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    // German dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('de', 'en', 'WordReference', 'https://www.wordreference.com/deen/[WORD]', 'popup', 1, 1, 1, ?),
+            ('de', 'en', 'Dict.cc', 'https://www.dict.cc/?s=[WORD]', 'popup', 1, 2, 1, ?),
+            ('de', 'en', 'Google Translate', 'https://translate.google.com/?sl=de&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // Italian dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('it', 'WordReference', 'https://www.wordreference.com/iten/[WORD]', 'popup', 1, 1, 1, ?),
-                ('it', 'Google Translate', 'https://translate.google.com/?sl=it&tl=en&text=[WORD]&op=translate', 'popup', 0, 2, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    // Italian dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('it', 'en', 'WordReference', 'https://www.wordreference.com/iten/[WORD]', 'popup', 1, 1, 1, ?),
+            ('it', 'en', 'Google Translate', 'https://translate.google.com/?sl=it&tl=en&text=[WORD]&op=translate', 'popup', 0, 2, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // English dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('en', 'Merriam-Webster', 'https://www.merriam-webster.com/dictionary/[WORD]', 'popup', 1, 1, 1, ?),
-                ('en', 'Cambridge', 'https://dictionary.cambridge.org/dictionary/english/[WORD]', 'popup', 1, 2, 1, ?),
-                ('en', 'Oxford', 'https://www.oxfordlearnersdictionaries.com/definition/english/[WORD]', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    // English dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('en', 'en', 'Merriam-Webster', 'https://www.merriam-webster.com/dictionary/[WORD]', 'popup', 1, 1, 1, ?),
+            ('en', 'en', 'Cambridge', 'https://dictionary.cambridge.org/dictionary/english/[WORD]', 'popup', 1, 2, 1, ?),
+            ('en', 'en', 'Oxford', 'https://www.oxfordlearnersdictionaries.com/definition/english/[WORD]', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // Portuguese dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('pt', 'WordReference', 'https://www.wordreference.com/pten/[WORD]', 'popup', 1, 1, 1, ?),
-                ('pt', 'Linguee', 'https://www.linguee.com/portuguese-english/search?source=auto&query=[WORD]', 'popup', 1, 2, 1, ?),
-                ('pt', 'Google Translate', 'https://translate.google.com/?sl=pt&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    // Portuguese dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('pt', 'en', 'WordReference', 'https://www.wordreference.com/pten/[WORD]', 'popup', 1, 1, 1, ?),
+            ('pt', 'en', 'Linguee', 'https://www.linguee.com/portuguese-english/search?source=auto&query=[WORD]', 'popup', 1, 2, 1, ?),
+            ('pt', 'en', 'Google Translate', 'https://translate.google.com/?sl=pt&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // Dutch dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('nl', 'WordReference', 'https://www.wordreference.com/nlen/[WORD]', 'popup', 1, 1, 1, ?),
-                ('nl', 'Van Dale', 'https://www.vandale.nl/gratis-woordenboek/nederlands-engels/vertaling/[WORD]', 'popup', 1, 2, 1, ?),
-                ('nl', 'Google Translate', 'https://translate.google.com/?sl=nl&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    // Dutch dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('nl', 'en', 'WordReference', 'https://www.wordreference.com/nlen/[WORD]', 'popup', 1, 1, 1, ?),
+            ('nl', 'en', 'Van Dale', 'https://www.vandale.nl/gratis-woordenboek/nederlands-engels/vertaling/[WORD]', 'popup', 1, 2, 1, ?),
+            ('nl', 'en', 'Google Translate', 'https://translate.google.com/?sl=nl&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-        // Russian dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('ru', 'WordReference', 'https://www.wordreference.com/ruen/[WORD]', 'popup', 1, 1, 1, ?),
-                ('ru', 'Reverso Context', 'https://context.reverso.net/translation/russian-english/[WORD]', 'popup', 1, 2, 1, ?),
-                ('ru', 'Google Translate', 'https://translate.google.com/?sl=ru&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
-    }
+    // Russian dictionaries
+    sqlx::query(
+        r#"
+        INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
+        VALUES
+            ('ru', 'en', 'WordReference', 'https://www.wordreference.com/ruen/[WORD]', 'popup', 1, 1, 1, ?),
+            ('ru', 'en', 'Reverso Context', 'https://context.reverso.net/translation/russian-english/[WORD]', 'popup', 1, 2, 1, ?),
+            ('ru', 'en', 'Google Translate', 'https://translate.google.com/?sl=ru&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
+        "#
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
 
-    Ok(pool)
+    Ok(())
 }
 
-/// Open connection to existing user database
-pub async fn open_user_db(app_handle: &tauri::AppHandle) -> Result<SqlitePool> {
-    let db_path = get_user_db_path(app_handle)?;
+/// Settings key for the Whisper model used when none is passed to `transcribe`
+pub const DEFAULT_MODEL_SETTING: &str = "default_model";
 
-    if !db_path.exists() {
-        // Database doesn't exist, initialize it
-        return initialize_user_db(app_handle).await;
-    }
+/// Settings key enabling the stemming fallback for words missing from lemma DBs ("true"/"false")
+pub const LEMMA_STEMMING_SETTING: &str = "lemma_stemming_fallback";
 
-    let connection_string = format!("sqlite://{}?mode=rw", db_path.display());
+/// Settings key for re-lemmatizing a language's vocabulary after its lemma DB is downloaded ("true"/"false")
+pub const AUTO_RELEMMATIZE_SETTING: &str = "auto_relemmatize_on_pack_update";
 
-    let pool = SqlitePool::connect(&connection_string)
-        .await
-        .context("Failed to open user database")?;
+/// Settings key for the directory new recordings are saved to (app data "audio" folder when unset)
+pub const AUDIO_DIRECTORY_SETTING: &str = "audio_directory";
 
-    // Run migrations for existing databases
+/// Settings key for the input device name recordings use when none is passed (system default when unset)
+pub const PREFERRED_DEVICE_SETTING: &str = "preferred_device";
 
-    // Migration: Add primary_language column to existing sessions tables
-    let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN primary_language TEXT DEFAULT 'en'")
-        .execute(&pool)
-        .await;
-    // Ignore errors - column might already exist
+/// Settings key for recording numbers ("2024") as vocabulary ("true"/"false", off by default)
+pub const VOCAB_NUMBERS_SETTING: &str = "vocab_include_numbers";
 
-    // Migration: Add segments column to existing sessions tables
-    let _ = sqlx::query("ALTER TABLE sessions ADD COLUMN segments TEXT")
-        .execute(&pool)
-        .await;
-    // Ignore errors - column might already exist
+/// Settings key for the user's primary (native) language, used for translations
+pub const PRIMARY_LANGUAGE_SETTING: &str = "primary_language";
 
-    // Migration: Add custom_translations table if it doesn't exist
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS custom_translations (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            lemma TEXT NOT NULL,
-            lang_from TEXT NOT NULL,
-            lang_to TEXT NOT NULL,
-            custom_translation TEXT NOT NULL,
-            notes TEXT,
-            created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL,
+/// Primary language used until the user picks one
+pub const DEFAULT_PRIMARY_LANGUAGE: &str = "en";
 
-            UNIQUE(lemma, lang_from, lang_to)
-        )
-        "#
-    )
-    .execute(&pool)
-    .await
-    .context("Failed to create custom_translations table")?;
+/// User preferences that apply across target languages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserProfile {
+    /// Language that vocabulary is translated into
+    pub primary_language: String,
+}
 
-    // Create custom_translations index
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_custom_translations_lookup ON custom_translations(lemma, lang_from, lang_to)")
-        .execute(&pool)
-        .await?;
+/// Read the stored profile, falling back to defaults for anything unset
+pub async fn get_profile(pool: &SqlitePool) -> Result<UserProfile> {
+    let primary_language = get_setting(pool, PRIMARY_LANGUAGE_SETTING)
+        .await?
+        .unwrap_or_else(|| DEFAULT_PRIMARY_LANGUAGE.to_string());
 
-    // Migration: Create dictionaries table for external dictionary lookups
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS dictionaries (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            language TEXT NOT NULL,
-            name TEXT NOT NULL,
-            url_template TEXT NOT NULL,
-            dict_type TEXT NOT NULL CHECK(dict_type IN ('embedded', 'popup')),
-            is_active INTEGER NOT NULL DEFAULT 1,
-            sort_order INTEGER NOT NULL,
-            is_default INTEGER NOT NULL DEFAULT 1,
-            created_at INTEGER NOT NULL
-        )
-        "#
-    )
-    .execute(&pool)
-    .await
-    .context("Failed to create dictionaries table")?;
+    Ok(UserProfile { primary_language })
+}
 
-    // Create dictionaries index
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_dictionaries_lang ON dictionaries(language, is_active, sort_order)")
-        .execute(&pool)
-        .await?;
+/// Store the profile
+pub async fn set_profile(pool: &SqlitePool, profile: &UserProfile) -> Result<()> {
+    let primary_language = profile.primary_language.trim().to_lowercase();
+    if primary_language.is_empty() {
+        anyhow::bail!("Primary language can't be empty");
+    }
 
-    // Seed default dictionaries if table is empty
-    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
-        .fetch_one(&pool)
-        .await?;
+    set_setting(pool, PRIMARY_LANGUAGE_SETTING, Some(&primary_language)).await
+}
 
-    if count.0 == 0 {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+/// `primary_language` if the caller passed one, otherwise the one from the profile
+pub async fn resolve_primary_language(pool: &SqlitePool, primary_language: Option<&str>) -> Result<String> {
+    match primary_language {
+        Some(language) => Ok(language.to_string()),
+        None => Ok(get_profile(pool).await?.primary_language),
+    }
+}
 
-        // Spanish dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('es', 'WordReference', 'https://www.wordreference.com/es/en/translation.asp?spen=[WORD]', 'popup', 1, 1, 1, ?),
-                ('es', 'SpanishDict', 'https://www.spanishdict.com/translate/[WORD]', 'popup', 1, 2, 1, ?),
-                ('es', 'Google Translate', 'https://translate.google.com/?sl=es&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+/// Settings key allowing corrections to be written into downloaded translation packs ("true"/"false")
+pub const EDITABLE_TRANSLATION_PACKS_SETTING: &str = "editable_translation_packs";
 
-        // French dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('fr', 'WordReference', 'https://www.wordreference.com/fren/[WORD]', 'popup', 1, 1, 1, ?),
-                ('fr', 'Larousse', 'https://www.larousse.fr/dictionnaires/francais-anglais/[WORD]', 'popup', 1, 2, 1, ?),
-                ('fr', 'Google Translate', 'https://translate.google.com/?sl=fr&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+/// Settings key for the reading speeds used to estimate text durations (JSON)
+pub const READING_SPEED_SETTING: &str = "reading_speed";
 
-        // German dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('de', 'WordReference', 'https://www.wordreference.com/deen/[WORD]', 'popup', 1, 1, 1, ?),
-                ('de', 'Dict.cc', 'https://www.dict.cc/?s=[WORD]', 'popup', 1, 2, 1, ?),
-                ('de', 'Google Translate', 'https://translate.google.com/?sl=de&tl=en&text=[WORD]&op=translate', 'popup', 0, 3, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+/// Read a setting (None if it was never set)
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to read setting")?;
 
-        // Italian dictionaries
-        sqlx::query(
-            r#"
-            INSERT INTO dictionaries (language, name, url_template, dict_type, is_active, sort_order, is_default, created_at)
-            VALUES
-                ('it', 'WordReference', 'https://www.wordreference.com/iten/[WORD]', 'popup', 1, 1, 1, ?),
-                ('it', 'Google Translate', 'https://translate.google.com/?sl=it&tl=en&text=[WORD]&op=translate', 'popup', 0, 2, 1, ?)
-            "#
-        )
-        .bind(now)
-        .bind(now)
-        .execute(&pool)
-        .await?;
+    Ok(row.map(|(value,)| value))
+}
+
+/// Store a setting, or remove it when `value` is None
+pub async fn set_setting(pool: &SqlitePool, key: &str, value: Option<&str>) -> Result<()> {
+    match value {
+        Some(value) => {
+            sqlx::query(
+                "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"
+            )
+            .bind(key)
+            .bind(value)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(pool)
+            .await
+            .context("Failed to save setting")?;
+        }
+        None => {
+            sqlx::query("DELETE FROM settings WHERE key = ?")
+                .bind(key)
+                .execute(pool)
+                .await
+                .context("Failed to clear setting")?;
+        }
     }
 
-    // Migration: Add tags column to vocab table (check if it exists first)
-    let column_exists: i32 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM pragma_table_info('vocab') WHERE name = 'tags'"
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or(0);
+    Ok(())
+}
 
-    if column_exists == 0 {
-        println!("[open_user_db] Adding tags column to vocab table...");
+/// Database size before and after a VACUUM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumStats {
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+}
 
-        // Column doesn't exist, add it
-        sqlx::query("ALTER TABLE vocab ADD COLUMN tags TEXT DEFAULT '[]'")
-            .execute(&pool)
-            .await?;
+/// Result of `PRAGMA integrity_check`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityCheckResult {
+    pub ok: bool,
+    /// "ok", or the problems SQLite found
+    pub messages: Vec<String>,
+}
 
-        println!("[open_user_db] Added tags column to vocab table");
+/// Size of the database in bytes (page count * page size)
+async fn database_size(pool: &SqlitePool) -> Result<u64> {
+    let (page_count,): (i64,) = sqlx::query_as("PRAGMA page_count").fetch_one(pool).await?;
+    let (page_size,): (i64,) = sqlx::query_as("PRAGMA page_size").fetch_one(pool).await?;
+    Ok((page_count * page_size) as u64)
+}
 
-        // Migration: Convert existing mastered boolean to tags (one-time conversion)
-        sqlx::query(
-            r#"
-            UPDATE vocab
-            SET tags = CASE
-                WHEN mastered = 1 THEN '["mastered"]'
-                ELSE '[]'
-            END
-            "#
-        )
-        .execute(&pool)
-        .await?;
+/// Rebuild the database file to reclaim space left by deleted rows
+/// Also truncates the WAL so the -wal file doesn't keep the old pages around
+pub async fn vacuum_db(pool: &SqlitePool) -> Result<VacuumStats> {
+    let size_before_bytes = database_size(pool).await?;
 
-        println!("[open_user_db] Converted existing mastered values to tags");
-    }
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .context("Failed to vacuum database")?;
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await
+        .context("Failed to checkpoint database")?;
+
+    let size_after_bytes = database_size(pool).await?;
+    println!(
+        "[vacuum_db] {} -> {} bytes",
+        size_before_bytes, size_after_bytes
+    );
+
+    Ok(VacuumStats {
+        size_before_bytes,
+        size_after_bytes,
+    })
+}
 
-    // Create index for filtering by tags
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_vocab_tags ON vocab(tags)")
-        .execute(&pool)
-        .await?;
+/// Run SQLite's integrity check
+pub async fn integrity_check(pool: &SqlitePool) -> Result<IntegrityCheckResult> {
+    let rows: Vec<(String,)> = sqlx::query_as("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .context("Failed to run integrity check")?;
 
-    Ok(pool)
+    let messages: Vec<String> = rows.into_iter().map(|(message,)| message).collect();
+    let ok = messages.len() == 1 && messages[0] == "ok";
+
+    Ok(IntegrityCheckResult { ok, messages })
 }
 
 #[cfg(test)]
@@ -663,4 +825,172 @@ mod tests {
         // Clean up
         // drop(pool);
     }
+
+    #[tokio::test]
+    async fn test_vacuum_shrinks_database_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("user.db");
+        let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", db_path.display()))
+            .await
+            .unwrap();
+
+        sqlx::query("CREATE TABLE sessions (id INTEGER PRIMARY KEY, transcript TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for i in 0..2000 {
+            sqlx::query("INSERT INTO sessions (id, transcript) VALUES (?, ?)")
+                .bind(i)
+                .bind("hola ".repeat(100))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("DELETE FROM sessions").execute(&pool).await.unwrap();
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&pool).await.unwrap();
+
+        let size_before = std::fs::metadata(&db_path).unwrap().len();
+        let stats = vacuum_db(&pool).await.unwrap();
+        let size_after = std::fs::metadata(&db_path).unwrap().len();
+
+        assert!(size_after < size_before, "{} should be < {}", size_after, size_before);
+        assert!(stats.size_after_bytes < stats.size_before_bytes);
+
+        let check = integrity_check(&pool).await.unwrap();
+        assert!(check.ok);
+        assert_eq!(check.messages, vec!["ok".to_string()]);
+    }
+
+    async fn memory_pool() -> SqlitePool {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn column_names(pool: &SqlitePool, table: &str) -> Vec<String> {
+        sqlx::query("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get(0))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fresh_db_reaches_latest_version() {
+        let pool = memory_pool().await;
+
+        let applied = run_migrations(&pool).await.unwrap();
+
+        assert_eq!(applied, LATEST_SCHEMA_VERSION as usize);
+        assert_eq!(get_schema_version(&pool).await.unwrap(), LATEST_SCHEMA_VERSION);
+
+        let sessions = column_names(&pool, "sessions").await;
+        for column in ["primary_language", "segments", "is_pinned", "is_favorite", "accuracy_score", "filler_count", "articulation_wpm"] {
+            assert!(sessions.contains(&column.to_string()), "missing sessions.{}", column);
+        }
+        let vocab = column_names(&pool, "vocab").await;
+        for column in ["tags", "first_session_id"] {
+            assert!(vocab.contains(&column.to_string()), "missing vocab.{}", column);
+        }
+        assert!(column_names(&pool, "settings").await.contains(&"value".to_string()));
+
+        let (dictionaries,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(dictionaries > 0);
+    }
+
+    #[tokio::test]
+    async fn test_running_migrations_twice_is_noop() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        let (dictionaries_before,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(run_migrations(&pool).await.unwrap(), 0);
+        assert_eq!(get_schema_version(&pool).await.unwrap(), LATEST_SCHEMA_VERSION);
+
+        let (dictionaries_after,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dictionaries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(dictionaries_before, dictionaries_after);
+    }
+
+    #[tokio::test]
+    async fn test_migrates_database_without_schema_version() {
+        // A database from before schema_version existed: some columns already added,
+        // vocab still using the mastered flag
+        let pool = memory_pool().await;
+        sqlx::query(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, language TEXT NOT NULL, started_at INTEGER NOT NULL,
+             session_type TEXT, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, segments TEXT, is_pinned INTEGER DEFAULT 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL,
+             first_seen_at INTEGER NOT NULL, last_seen_at INTEGER NOT NULL, usage_count INTEGER DEFAULT 1,
+             mastered BOOLEAN DEFAULT 0, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO vocab (language, lemma, first_seen_at, last_seen_at, mastered, created_at, updated_at) VALUES ('es', 'hablar', 0, 0, 1, 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+
+        assert_eq!(get_schema_version(&pool).await.unwrap(), LATEST_SCHEMA_VERSION);
+        assert!(column_names(&pool, "sessions").await.contains(&"filler_count".to_string()));
+
+        let (tags,): (String,) = sqlx::query_as("SELECT tags FROM vocab WHERE lemma = 'hablar'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tags, r#"["mastered"]"#);
+    }
+
+    #[tokio::test]
+    async fn test_settings_round_trip() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        assert_eq!(get_setting(&pool, DEFAULT_MODEL_SETTING).await.unwrap(), None);
+
+        set_setting(&pool, DEFAULT_MODEL_SETTING, Some("small")).await.unwrap();
+        set_setting(&pool, DEFAULT_MODEL_SETTING, Some("base")).await.unwrap();
+        assert_eq!(get_setting(&pool, DEFAULT_MODEL_SETTING).await.unwrap(), Some("base".to_string()));
+
+        set_setting(&pool, DEFAULT_MODEL_SETTING, None).await.unwrap();
+        assert_eq!(get_setting(&pool, DEFAULT_MODEL_SETTING).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_profile_primary_language() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        assert_eq!(get_profile(&pool).await.unwrap().primary_language, DEFAULT_PRIMARY_LANGUAGE);
+
+        set_profile(&pool, &UserProfile { primary_language: " FR ".to_string() }).await.unwrap();
+        assert_eq!(get_profile(&pool).await.unwrap().primary_language, "fr");
+        assert_eq!(resolve_primary_language(&pool, None).await.unwrap(), "fr");
+        assert_eq!(resolve_primary_language(&pool, Some("de")).await.unwrap(), "de");
+
+        assert!(set_profile(&pool, &UserProfile { primary_language: "  ".to_string() }).await.is_err());
+        assert_eq!(get_profile(&pool).await.unwrap().primary_language, "fr");
+    }
 }
@@ -1,7 +1,7 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use fluent_diary::commands::{cleanup, dictionaries, langpack, language_packs, models, recording, sessions, stats, system, text_library, vocabulary};
+use fluent_diary::commands::{auth, backup, cleanup, dictionaries, langpack, language_packs, models, recording, sessions, stats, system, text_library, vocabulary};
 use fluent_diary::services::recording::RecorderState;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
@@ -31,11 +31,21 @@ fn main() {
             } else {
                 println!("[App][Rust] Main window not yet available at setup");
             }
+
+            // Recordings whose marker survived were interrupted by a crash
+            let interrupted = tauri::async_runtime::block_on(recording::find_interrupted_recordings(app.handle()));
+            if !interrupted.is_empty() {
+                println!("[App][Rust] Found {} interrupted recording(s)", interrupted.len());
+            }
+            app.manage(recording::InterruptedRecordingsWrapper(Mutex::new(interrupted)));
             Ok(())
         })
         .manage(recording::RecorderStateWrapper(Mutex::new(
             RecorderState::new(),
         )))
+        .manage(recording::TranscriptionStateWrapper(Arc::new(Mutex::new(
+            recording::TranscriptionState::default(),
+        ))))
         .manage(models::DownloadStateWrapper(Arc::new(Mutex::new(
             models::DownloadState::new(),
         ))))
@@ -43,28 +53,66 @@ fn main() {
             greet,
             log_marker,
             langpack::get_lemma,
+            langpack::get_lemma_stemming_enabled,
+            langpack::set_lemma_stemming_enabled,
+            langpack::get_auto_relemmatize_enabled,
+            langpack::set_auto_relemmatize_enabled,
             langpack::lemmatize_batch,
+            langpack::get_translation_pack_editing_enabled,
+            langpack::set_translation_pack_editing_enabled,
+            langpack::append_official_translation,
+            langpack::get_translation_pack_stats,
             vocabulary::record_word,
             vocabulary::get_user_vocab,
+            vocabulary::get_user_vocab_paged,
             vocabulary::is_new_word,
             vocabulary::get_vocab_stats,
+            vocabulary::get_vocab_discovery_by_session_type,
             vocabulary::clean_vocab_punctuation,
+            vocabulary::dedupe_vocab_forms,
             vocabulary::get_recent_vocab,
+            vocabulary::get_stale_words,
+            vocabulary::recommend_new_words,
             vocabulary::delete_vocab_word,
+            vocabulary::clear_vocabulary,
             vocabulary::toggle_vocab_mastered,
             vocabulary::add_vocab_tag,
             vocabulary::remove_vocab_tag,
+            vocabulary::add_vocab_tag_bulk,
+            vocabulary::remove_vocab_tag_bulk,
             vocabulary::get_vocab_by_tag,
+            vocabulary::get_tag_counts,
+            vocabulary::get_cross_language_overlap,
+            vocabulary::get_vocab_numbers_enabled,
+            vocabulary::set_vocab_numbers_enabled,
             vocabulary::set_custom_translation,
             vocabulary::get_custom_translation,
             vocabulary::delete_custom_translation,
             vocabulary::fix_vocab_lemmas,
+            vocabulary::retranslate_vocab,
+            vocabulary::reverse_translate,
+            vocabulary::get_profile,
+            vocabulary::set_profile,
             recording::get_recording_devices,
+            recording::get_recording_devices_detailed,
+            recording::probe_device,
+            recording::get_preferred_device,
+            recording::set_preferred_device,
             recording::start_recording,
+            recording::set_pre_roll,
             recording::stop_recording,
             recording::is_recording,
             recording::transcribe,
+            recording::transcribe_partial,
+            recording::transcribe_file,
+            recording::get_audio_directory,
+            recording::set_audio_directory,
+            recording::get_interrupted_recordings,
+            recording::recover_interrupted_recording,
+            recording::get_audio_waveform,
+            recording::check_recording_audible_command,
             recording::create_recording_session,
+            recording::begin_session,
             recording::complete_recording_session,
             recording::read_audio_file,
             recording::delete_audio_file,
@@ -72,44 +120,90 @@ fn main() {
             models::check_model_installed,
             models::check_default_model_installed,
             models::get_default_whisper_model,
+            models::get_default_model_setting,
+            models::set_default_model_setting,
             models::get_whisper_model_path,
             models::get_installed_whisper_models,
             models::download_whisper_model,
             models::delete_whisper_model,
+            models::import_whisper_model,
+            models::validate_whisper_model,
+            models::benchmark_transcription,
             models::is_download_in_progress,
+            models::cancel_model_download,
+            models::cancel_language_download,
             stats::get_stats_overall,
+            stats::get_stats_all_languages,
             stats::get_stats_top_words,
             stats::get_stats_daily_sessions,
             stats::get_stats_wpm_trends,
             stats::get_stats_vocab_growth,
             sessions::get_all_sessions_command,
+            sessions::get_all_sessions_paged_command,
             sessions::get_session_command,
             sessions::get_sessions_by_language_command,
+            sessions::get_sessions_by_language_paged_command,
+            sessions::get_sessions_filtered_command,
             sessions::get_session_words_command,
+            sessions::get_session_segments_command,
+            sessions::get_session_subtitles_command,
+            sessions::annotate_transcript_command,
+            sessions::export_session_command,
             sessions::delete_session_command,
+            sessions::set_session_pinned_command,
+            sessions::toggle_session_favorite_command,
+            sessions::get_favorite_sessions_command,
+            sessions::reprocess_session_command,
+            sessions::retry_failed_words_command,
+            sessions::recompute_all_session_stats_command,
+            sessions::recompute_session_word_newness_command,
+            sessions::split_session_command,
+            sessions::get_read_aloud_score_command,
+            sessions::get_session_filler_words_command,
             cleanup::run_cleanup,
+            cleanup::run_size_cleanup,
+            cleanup::cleanup_orphaned_audio,
+            backup::export_backup,
+            backup::import_backup,
             text_library::create_text_library_item_command,
             text_library::get_text_library_item_command,
             text_library::get_all_text_library_items_command,
             text_library::get_text_library_by_language_command,
+            text_library::get_text_library_by_tag_command,
+            text_library::get_all_text_library_tags_command,
             text_library::update_text_library_item_command,
             text_library::delete_text_library_item_command,
+            text_library::import_text_from_url,
+            text_library::get_text_coverage_command,
+            text_library::build_practice_text_command,
+            text_library::get_reading_speed_command,
+            text_library::set_reading_speed_command,
             language_packs::is_lemmas_installed,
             language_packs::is_translation_installed,
             language_packs::get_installed_languages,
+            language_packs::get_installed_language_packs,
             language_packs::download_lemmas,
             language_packs::download_translation,
             language_packs::delete_language_pack,
+            language_packs::delete_translation_pack,
+            language_packs::verify_language_pack,
             language_packs::get_required_packs,
             language_packs::download_language_pair,
             system::get_system_specs,
             system::reset_app_data,
+            system::vacuum_user_db,
+            system::integrity_check_user_db,
             dictionaries::get_dictionaries,
             dictionaries::update_dictionary_active,
             dictionaries::update_dictionary_sort_order,
             dictionaries::reorder_dictionaries,
             dictionaries::add_dictionary,
+            dictionaries::update_dictionary,
             dictionaries::delete_dictionary,
+            dictionaries::resolve_dictionary_url,
+            auth::refresh_access_token,
+            auth::sign_in_with_password,
+            auth::sign_up_with_password,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
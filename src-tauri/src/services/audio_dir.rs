@@ -0,0 +1,118 @@
+/**
+ * Recording storage location
+ *
+ * Recordings go to `<app data>/audio` unless the user picked another directory
+ * (e.g. on an external drive). Sessions store absolute audio paths, so files
+ * recorded before the directory changed keep working where they are.
+ */
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+use crate::db::user::{get_setting, set_setting, AUDIO_DIRECTORY_SETTING};
+
+/// File briefly created to check that a directory accepts recordings
+const WRITE_PROBE_FILE: &str = ".fluentwhisper-write-test";
+
+/// Check that `dir` can hold recordings: absolute, a directory (created if missing) and writable
+pub fn validate_audio_directory(dir: &Path) -> Result<()> {
+    if !dir.is_absolute() {
+        anyhow::bail!("Audio directory must be an absolute path: {}", dir.display());
+    }
+    if dir.exists() && !dir.is_dir() {
+        anyhow::bail!("Audio directory is not a directory: {}", dir.display());
+    }
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create audio directory {}", dir.display()))?;
+
+    let probe = dir.join(WRITE_PROBE_FILE);
+    std::fs::write(&probe, b"")
+        .with_context(|| format!("Audio directory is not writable: {}", dir.display()))?;
+    let _ = std::fs::remove_file(&probe);
+
+    Ok(())
+}
+
+/// The directory the user picked for recordings, if any
+pub async fn get_audio_directory(pool: &SqlitePool) -> Result<Option<PathBuf>> {
+    Ok(get_setting(pool, AUDIO_DIRECTORY_SETTING).await?.map(PathBuf::from))
+}
+
+/// Store the directory for new recordings (None goes back to the default)
+pub async fn set_audio_directory(pool: &SqlitePool, dir: Option<&Path>) -> Result<()> {
+    match dir {
+        Some(dir) => {
+            validate_audio_directory(dir)?;
+            set_setting(pool, AUDIO_DIRECTORY_SETTING, Some(&dir.to_string_lossy())).await
+        }
+        None => set_setting(pool, AUDIO_DIRECTORY_SETTING, None).await,
+    }
+}
+
+/// Directory new recordings are written to, created if needed
+/// `default_dir` is used when the user hasn't picked one.
+pub async fn resolve_audio_directory(pool: &SqlitePool, default_dir: &Path) -> Result<PathBuf> {
+    let dir = get_audio_directory(pool)
+        .await?
+        .unwrap_or_else(|| default_dir.to_path_buf());
+
+    validate_audio_directory(&dir)?;
+    Ok(dir)
+}
+
+/// Where a session's recording is written
+pub fn recording_path(audio_dir: &Path, session_id: &str) -> PathBuf {
+    audio_dir.join(format!("{}.wav", session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_recordings_go_to_configured_directory() {
+        let pool = setup_test_db().await;
+        let default_dir = tempfile::tempdir().unwrap();
+        let external = tempfile::tempdir().unwrap();
+        let configured = external.path().join("FluentWhisper").join("audio");
+
+        assert_eq!(resolve_audio_directory(&pool, default_dir.path()).await.unwrap(), default_dir.path());
+
+        set_audio_directory(&pool, Some(&configured)).await.unwrap();
+        let audio_dir = resolve_audio_directory(&pool, default_dir.path()).await.unwrap();
+        assert_eq!(audio_dir, configured);
+        assert!(configured.is_dir());
+
+        let path = recording_path(&audio_dir, "session-1");
+        assert_eq!(path, configured.join("session-1.wav"));
+        std::fs::write(&path, b"RIFF").unwrap();
+        assert!(path.is_file());
+        assert!(!configured.join(WRITE_PROBE_FILE).exists());
+
+        set_audio_directory(&pool, None).await.unwrap();
+        assert_eq!(resolve_audio_directory(&pool, default_dir.path()).await.unwrap(), default_dir.path());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_audio_directories_are_rejected() {
+        let pool = setup_test_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("not-a-dir");
+        std::fs::write(&file, b"").unwrap();
+
+        assert!(set_audio_directory(&pool, Some(Path::new("relative/audio"))).await.is_err());
+        assert!(set_audio_directory(&pool, Some(&file)).await.is_err());
+        assert_eq!(get_audio_directory(&pool).await.unwrap(), None);
+    }
+}
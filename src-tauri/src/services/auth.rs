@@ -0,0 +1,365 @@
+/**
+ * Supabase auth service
+ *
+ * Talks to the Supabase GoTrue REST API:
+ * - Refreshing an expired session with the stored refresh token
+ * - Email/password sign-in and sign-up
+ */
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Session tokens returned by Supabase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthCredentials {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Unix timestamp (seconds) when the access token expires
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    /// The refresh token was revoked or expired - the user must sign in again
+    #[error("Session expired, please sign in again")]
+    InvalidRefreshToken,
+
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+
+    #[error("Email not confirmed, check your inbox for the confirmation link")]
+    EmailNotConfirmed,
+
+    #[error("Auth request failed ({status}): {message}")]
+    Supabase { status: u16, message: String },
+
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+}
+
+/// Token payload of `/auth/v1/token` responses
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_in: Option<i64>,
+    expires_at: Option<i64>,
+}
+
+/// Error payload of GoTrue responses (older and newer servers use different field names)
+#[derive(Debug, Default, Deserialize)]
+struct ErrorResponse {
+    error: Option<String>,
+    error_code: Option<String>,
+    error_description: Option<String>,
+    msg: Option<String>,
+    message: Option<String>,
+}
+
+impl ErrorResponse {
+    fn code(&self) -> Option<&str> {
+        self.error_code.as_deref().or(self.error.as_deref())
+    }
+
+    fn message(&self) -> String {
+        self.error_description
+            .as_deref()
+            .or(self.msg.as_deref())
+            .or(self.message.as_deref())
+            .or(self.code())
+            .unwrap_or("Unknown error")
+            .to_string()
+    }
+
+    /// Map a failed response to the most specific AuthError
+    fn into_auth_error(self, status: reqwest::StatusCode) -> AuthError {
+        let message = self.message();
+        match self.code() {
+            Some("invalid_credentials") => AuthError::InvalidCredentials,
+            Some("email_not_confirmed") => AuthError::EmailNotConfirmed,
+            // Older servers only report `invalid_grant` with a description
+            _ if message.contains("Invalid login credentials") => AuthError::InvalidCredentials,
+            _ if message.contains("Email not confirmed") => AuthError::EmailNotConfirmed,
+            _ => AuthError::Supabase {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+/// Minimal client for the Supabase auth endpoints
+pub struct SupabaseAuthClient {
+    http: reqwest::Client,
+    base_url: String,
+    anon_key: String,
+}
+
+impl SupabaseAuthClient {
+    pub fn new(base_url: &str, anon_key: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            anon_key: anon_key.to_string(),
+        }
+    }
+
+    /// Exchange a refresh token for a new access/refresh token pair
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<AuthCredentials, AuthError> {
+        let url = format!("{}/auth/v1/token?grant_type=refresh_token", self.base_url);
+
+        let response = self
+            .http
+            .post(&url)
+            .header("apikey", &self.anon_key)
+            .json(&serde_json::json!({ "refresh_token": refresh_token }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error: ErrorResponse = response.json().await.unwrap_or_default();
+            let invalid_token = error.code().map_or(false, |c| {
+                c == "invalid_grant" || c == "refresh_token_not_found" || c == "refresh_token_already_used"
+            }) || error.message().contains("Invalid Refresh Token");
+
+            if invalid_token {
+                return Err(AuthError::InvalidRefreshToken);
+            }
+            return Err(error.into_auth_error(status));
+        }
+
+        let tokens: TokenResponse = response.json().await?;
+        Ok(tokens.into_credentials())
+    }
+
+    /// Sign in with email and password
+    pub async fn sign_in_with_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<AuthCredentials, AuthError> {
+        let url = format!("{}/auth/v1/token?grant_type=password", self.base_url);
+
+        let response = self
+            .http
+            .post(&url)
+            .header("apikey", &self.anon_key)
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error: ErrorResponse = response.json().await.unwrap_or_default();
+            return Err(error.into_auth_error(status));
+        }
+
+        let tokens: TokenResponse = response.json().await?;
+        Ok(tokens.into_credentials())
+    }
+
+    /// Create an account with email and password
+    /// Returns None when the project requires email confirmation before a session is issued
+    pub async fn sign_up_with_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<Option<AuthCredentials>, AuthError> {
+        let url = format!("{}/auth/v1/signup", self.base_url);
+
+        let response = self
+            .http
+            .post(&url)
+            .header("apikey", &self.anon_key)
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error: ErrorResponse = response.json().await.unwrap_or_default();
+            return Err(error.into_auth_error(status));
+        }
+
+        // With auto-confirm the session is returned directly, otherwise only the user
+        let body: serde_json::Value = response.json().await?;
+        if body.get("access_token").is_none() {
+            return Ok(None);
+        }
+
+        let tokens: TokenResponse = serde_json::from_value(body).map_err(|e| AuthError::Supabase {
+            status: status.as_u16(),
+            message: format!("Unexpected sign-up response: {}", e),
+        })?;
+        Ok(Some(tokens.into_credentials()))
+    }
+}
+
+impl TokenResponse {
+    fn into_credentials(self) -> AuthCredentials {
+        let expires_at = self.expires_at.or_else(|| {
+            self.expires_in
+                .map(|secs| chrono::Utc::now().timestamp() + secs)
+        });
+
+        AuthCredentials {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve a single canned JSON response and return the server's base URL
+    async fn spawn_mock_auth_server(status_line: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status_line,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_refresh_session_success() {
+        let base_url = spawn_mock_auth_server(
+            "200 OK",
+            r#"{"access_token":"new-access","token_type":"bearer","expires_in":3600,"expires_at":1700003600,"refresh_token":"new-refresh","user":{"id":"u1"}}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let credentials = client.refresh_session("old-refresh").await.unwrap();
+
+        assert_eq!(
+            credentials,
+            AuthCredentials {
+                access_token: "new-access".to_string(),
+                refresh_token: "new-refresh".to_string(),
+                expires_at: Some(1700003600),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_session_expired_token() {
+        let base_url = spawn_mock_auth_server(
+            "400 Bad Request",
+            r#"{"code":400,"error_code":"refresh_token_not_found","msg":"Invalid Refresh Token: Refresh Token Not Found"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let err = client.refresh_session("revoked").await.unwrap_err();
+
+        assert!(matches!(err, AuthError::InvalidRefreshToken));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_password_success() {
+        let base_url = spawn_mock_auth_server(
+            "200 OK",
+            r#"{"access_token":"access","token_type":"bearer","expires_in":3600,"expires_at":1700003600,"refresh_token":"refresh","user":{"id":"u1"}}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let credentials = client.sign_in_with_password("ana@example.com", "secret").await.unwrap();
+
+        assert_eq!(credentials.access_token, "access");
+        assert_eq!(credentials.refresh_token, "refresh");
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_password_bad_credentials() {
+        let base_url = spawn_mock_auth_server(
+            "400 Bad Request",
+            r#"{"code":400,"error_code":"invalid_credentials","msg":"Invalid login credentials"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let err = client.sign_in_with_password("ana@example.com", "wrong").await.unwrap_err();
+
+        assert!(matches!(err, AuthError::InvalidCredentials));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_password_unconfirmed_email() {
+        let base_url = spawn_mock_auth_server(
+            "400 Bad Request",
+            r#"{"error":"invalid_grant","error_description":"Email not confirmed"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let err = client.sign_in_with_password("ana@example.com", "secret").await.unwrap_err();
+
+        assert!(matches!(err, AuthError::EmailNotConfirmed));
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_requires_confirmation() {
+        let base_url = spawn_mock_auth_server(
+            "200 OK",
+            r#"{"id":"u1","email":"ana@example.com","confirmation_sent_at":"2024-01-01T00:00:00Z"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let session = client.sign_up_with_password("ana@example.com", "secret").await.unwrap();
+
+        assert_eq!(session, None);
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_existing_user() {
+        let base_url = spawn_mock_auth_server(
+            "422 Unprocessable Entity",
+            r#"{"code":422,"error_code":"user_already_exists","msg":"User already registered"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let err = client.sign_up_with_password("ana@example.com", "secret").await.unwrap_err();
+
+        assert!(matches!(err, AuthError::Supabase { status: 422, .. }));
+        assert!(err.to_string().contains("User already registered"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_session_server_error() {
+        let base_url = spawn_mock_auth_server(
+            "500 Internal Server Error",
+            r#"{"message":"database unavailable"}"#,
+        )
+        .await;
+
+        let client = SupabaseAuthClient::new(&base_url, "anon");
+        let err = client.refresh_session("token").await.unwrap_err();
+
+        assert!(matches!(err, AuthError::Supabase { status: 500, .. }));
+        assert!(err.to_string().contains("database unavailable"));
+    }
+}
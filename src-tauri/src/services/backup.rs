@@ -0,0 +1,453 @@
+/**
+ * Backup service
+ *
+ * Packs everything a user owns into a single zip archive and restores it:
+ * - user.db (sessions, vocabulary, text library, settings)
+ * - audio/ (session recordings, wherever they are stored)
+ * - backup.json (format version and where the data came from)
+ *
+ * Files are copied as-is, so restoring needs no knowledge of the SQL schema.
+ */
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bump when the archive layout changes in a way older versions can't restore
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const METADATA_FILE: &str = "backup.json";
+const USER_DB_FILE: &str = "user.db";
+const AUDIO_DIR: &str = "audio";
+
+/// Contents of backup.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupMetadata {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: i64,
+    /// Audio directory on the machine the backup was made on
+    /// (session audio paths are absolute and get relinked on restore)
+    pub source_audio_dir: String,
+    /// Directory the user picked for recordings, if any (restored into when it exists)
+    #[serde(default)]
+    pub audio_directory: Option<String>,
+}
+
+/// Result of an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Write a backup of `app_data_dir` to `dest_path`
+///
+/// Archives the files in `<app data>/audio` and every file in `session_audio` (the
+/// sessions' `audio_path`s, which may be in a user-picked directory), all under audio/.
+/// `audio_directory` is the user's recordings directory setting, kept for the restore.
+/// The database should be checkpointed first so user.db holds every committed change.
+/// Files are streamed into the archive, audio is stored uncompressed (WAV barely shrinks).
+pub fn export_backup(
+    app_data_dir: &Path,
+    session_audio: &[PathBuf],
+    audio_directory: Option<&Path>,
+    dest_path: &Path,
+    app_version: &str,
+) -> Result<BackupInfo> {
+    println!("[export_backup] Exporting {:?} -> {:?}", app_data_dir, dest_path);
+
+    let db_path = app_data_dir.join(USER_DB_FILE);
+    if !db_path.exists() {
+        anyhow::bail!("No user database found at {}", db_path.display());
+    }
+
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create backup directory")?;
+    }
+
+    // Write to a temp name so a failed export never leaves a truncated archive behind
+    let temp_path = dest_path.with_extension("zip.partial");
+    let file = File::create(&temp_path).context("Failed to create backup file")?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let audio_dir = app_data_dir.join(AUDIO_DIR);
+    let metadata = BackupMetadata {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        source_audio_dir: audio_dir.to_string_lossy().to_string(),
+        audio_directory: audio_directory.map(|dir| dir.to_string_lossy().to_string()),
+    };
+
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(METADATA_FILE, deflated)?;
+    serde_json::to_writer_pretty(&mut zip, &metadata)?;
+
+    add_file(&mut zip, &db_path, USER_DB_FILE, deflated)?;
+
+    let mut audio_files: Vec<PathBuf> = Vec::new();
+    if audio_dir.is_dir() {
+        for entry in fs::read_dir(&audio_dir).context("Failed to read audio directory")? {
+            audio_files.push(entry?.path());
+        }
+    }
+    audio_files.extend(session_audio.iter().cloned());
+
+    // Restored into one directory, so each file name is archived once
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut archived: HashSet<String> = HashSet::new();
+    for path in audio_files {
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if archived.insert(name.to_string()) {
+            add_file(&mut zip, &path, &format!("{}/{}", AUDIO_DIR, name), stored)?;
+        }
+    }
+
+    zip.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&temp_path, dest_path).context("Failed to move backup into place")?;
+
+    let size_bytes = fs::metadata(dest_path)?.len();
+    println!("[export_backup] Wrote {} bytes", size_bytes);
+
+    Ok(BackupInfo {
+        path: dest_path.to_string_lossy().to_string(),
+        size_bytes,
+    })
+}
+
+/// Every recording a session points to, wherever it is stored
+pub async fn session_audio_paths(pool: &SqlitePool) -> Result<Vec<PathBuf>> {
+    let paths: Vec<String> = sqlx::query_scalar("SELECT audio_path FROM sessions WHERE audio_path IS NOT NULL")
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch session audio paths")?;
+    Ok(paths.into_iter().map(PathBuf::from).collect())
+}
+
+/// Where to restore a backup's recordings: the directory the user had picked if it
+/// exists on this machine (e.g. the same external drive), otherwise `default_dir`
+pub fn restore_audio_dir(metadata: &BackupMetadata, default_dir: &Path) -> PathBuf {
+    metadata
+        .audio_directory
+        .as_deref()
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute() && dir.is_dir())
+        .unwrap_or_else(|| default_dir.to_path_buf())
+}
+
+/// Stream a file from disk into the archive
+fn add_file<W: io::Write + io::Seek>(
+    zip: &mut ZipWriter<W>,
+    path: &Path,
+    name: &str,
+    options: SimpleFileOptions,
+) -> Result<()> {
+    let size = fs::metadata(path)?.len();
+    let options = options.large_file(size >= u32::MAX as u64);
+
+    zip.start_file(name, options)?;
+    let mut reader = BufReader::new(File::open(path).with_context(|| format!("Failed to open {}", path.display()))?);
+    io::copy(&mut reader, zip).with_context(|| format!("Failed to archive {}", path.display()))?;
+    Ok(())
+}
+
+/// Whether `app_data_dir` or `audio_dir` already holds user data a restore would overwrite
+pub fn has_existing_data(app_data_dir: &Path, audio_dir: &Path) -> bool {
+    let has_audio = fs::read_dir(audio_dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+
+    app_data_dir.join(USER_DB_FILE).exists() || has_audio
+}
+
+/// Read backup.json from an archive, e.g. to pick where its audio goes before restoring
+pub fn read_backup_metadata(archive_path: &Path) -> Result<BackupMetadata> {
+    let file = File::open(archive_path).context("Failed to open backup file")?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).context("Not a valid backup archive")?;
+    read_metadata(&mut archive)
+}
+
+fn read_metadata<R: io::Read + io::Seek>(archive: &mut ZipArchive<R>) -> Result<BackupMetadata> {
+    let entry = archive
+        .by_name(METADATA_FILE)
+        .context("Backup is missing backup.json")?;
+    serde_json::from_reader(entry).context("Backup metadata is corrupted")
+}
+
+/// Restore a backup archive: user.db into `app_data_dir`, recordings into `audio_dir`
+///
+/// Refuses to overwrite existing data unless `force` is set.
+/// Only user.db and files directly under audio/ are extracted.
+pub fn import_backup(app_data_dir: &Path, audio_dir: &Path, archive_path: &Path, force: bool) -> Result<BackupMetadata> {
+    println!("[import_backup] Restoring {:?} -> {:?} (audio: {:?})", archive_path, app_data_dir, audio_dir);
+
+    let file = File::open(archive_path).context("Failed to open backup file")?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).context("Not a valid backup archive")?;
+    let metadata = read_metadata(&mut archive)?;
+
+    if metadata.format_version > BACKUP_FORMAT_VERSION {
+        anyhow::bail!(
+            "Backup format version {} is newer than supported version {}. Please update the app.",
+            metadata.format_version,
+            BACKUP_FORMAT_VERSION
+        );
+    }
+
+    if archive.by_name(USER_DB_FILE).is_err() {
+        anyhow::bail!("Backup is missing user.db");
+    }
+
+    if !force && has_existing_data(app_data_dir, audio_dir) {
+        anyhow::bail!("This install already has data. Restore with force to overwrite it.");
+    }
+
+    fs::create_dir_all(audio_dir).context("Failed to create audio directory")?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.enclosed_name().and_then(|p| restore_target(&p)) else {
+            continue;
+        };
+
+        let target = match relative.strip_prefix(AUDIO_DIR) {
+            Ok(file) => audio_dir.join(file),
+            Err(_) => app_data_dir.join(&relative),
+        };
+        let temp = target.with_extension("restore");
+        {
+            let mut out = BufWriter::new(File::create(&temp)?);
+            io::copy(&mut entry, &mut out).with_context(|| format!("Failed to restore {}", relative.display()))?;
+            io::Write::flush(&mut out)?;
+        }
+        fs::rename(&temp, &target)?;
+    }
+
+    // A WAL left over from the previous database would be replayed onto the restored one
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(app_data_dir.join(format!("{}{}", USER_DB_FILE, suffix)));
+    }
+
+    println!("[import_backup] Restored backup from {}", metadata.created_at);
+    Ok(metadata)
+}
+
+/// Map an archive entry to where it belongs under app data, or None to skip it
+fn restore_target(name: &Path) -> Option<PathBuf> {
+    let components: Vec<Component> = name.components().collect();
+    match components.as_slice() {
+        [Component::Normal(file)] if *file == USER_DB_FILE => Some(PathBuf::from(USER_DB_FILE)),
+        [Component::Normal(dir), Component::Normal(file)] if *dir == AUDIO_DIR => {
+            Some(Path::new(AUDIO_DIR).join(file))
+        }
+        _ => None,
+    }
+}
+
+/// Point session audio paths at the restored recordings in `audio_dir`
+///
+/// Matched by file name, since the backup gathered recordings from several directories
+/// into one. Sessions whose recording wasn't restored keep their path.
+pub async fn relink_audio_paths(pool: &SqlitePool, audio_dir: &Path) -> Result<u64> {
+    let sessions: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, audio_path FROM sessions WHERE audio_path IS NOT NULL")
+            .fetch_all(pool)
+            .await
+            .context("Failed to fetch session audio paths")?;
+
+    let mut tx = pool.begin().await?;
+    let mut relinked = 0;
+    for (id, audio_path) in sessions {
+        let Some(name) = Path::new(&audio_path).file_name() else {
+            continue;
+        };
+        let restored = audio_dir.join(name);
+        if !restored.is_file() || restored == Path::new(&audio_path) {
+            continue;
+        }
+
+        sqlx::query("UPDATE sessions SET audio_path = ? WHERE id = ?")
+            .bind(restored.to_string_lossy().to_string())
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to relink audio paths")?;
+        relinked += 1;
+    }
+    tx.commit().await?;
+
+    Ok(relinked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_fixture(app_data_dir: &Path) {
+        fs::create_dir_all(app_data_dir.join(AUDIO_DIR)).unwrap();
+        fs::write(app_data_dir.join(USER_DB_FILE), b"sqlite database bytes").unwrap();
+        fs::write(app_data_dir.join(AUDIO_DIR).join("s1.wav"), vec![7u8; 64 * 1024]).unwrap();
+        fs::write(app_data_dir.join(AUDIO_DIR).join("s2.wav"), b"second recording").unwrap();
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        write_fixture(source.path());
+
+        // A recording saved to a user-picked directory, plus one that's missing
+        let external = tempfile::tempdir().unwrap();
+        fs::write(external.path().join("s3.wav"), b"external recording").unwrap();
+        let session_audio = vec![
+            external.path().join("s3.wav"),
+            external.path().join("gone.wav"),
+            source.path().join(AUDIO_DIR).join("s1.wav"),
+        ];
+
+        let out = tempfile::tempdir().unwrap();
+        let archive = out.path().join("backup.zip");
+        let info = export_backup(source.path(), &session_audio, Some(external.path()), &archive, "1.2.3").unwrap();
+        assert_eq!(info.size_bytes, fs::metadata(&archive).unwrap().len());
+
+        let metadata = read_backup_metadata(&archive).unwrap();
+        assert_eq!(metadata.audio_directory.as_deref(), Some(external.path().to_str().unwrap()));
+        let default_dir = Path::new("/default/audio");
+        assert_eq!(restore_audio_dir(&metadata, default_dir), external.path());
+        let elsewhere = BackupMetadata {
+            audio_directory: Some("/Volumes/Unplugged/recordings".to_string()),
+            ..metadata.clone()
+        };
+        assert_eq!(restore_audio_dir(&elsewhere, default_dir), default_dir);
+
+        let target = tempfile::tempdir().unwrap();
+        let restored_audio = target.path().join("recordings");
+        let metadata = import_backup(target.path(), &restored_audio, &archive, false).unwrap();
+
+        assert_eq!(metadata.format_version, BACKUP_FORMAT_VERSION);
+        assert_eq!(metadata.app_version, "1.2.3");
+        assert_eq!(
+            fs::read(target.path().join(USER_DB_FILE)).unwrap(),
+            fs::read(source.path().join(USER_DB_FILE)).unwrap()
+        );
+        for (file, original) in [
+            ("s1.wav", source.path().join("audio/s1.wav")),
+            ("s2.wav", source.path().join("audio/s2.wav")),
+            ("s3.wav", external.path().join("s3.wav")),
+        ] {
+            assert_eq!(
+                fs::read(restored_audio.join(file)).unwrap(),
+                fs::read(original).unwrap(),
+                "{} differs after restore",
+                file
+            );
+        }
+        assert_eq!(fs::read_dir(&restored_audio).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_import_refuses_to_overwrite_without_force() {
+        let source = tempfile::tempdir().unwrap();
+        write_fixture(source.path());
+        let archive = source.path().join("backup.zip");
+        export_backup(source.path(), &[], None, &archive, "1.0.0").unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let audio_dir = target.path().join(AUDIO_DIR);
+        fs::write(target.path().join(USER_DB_FILE), b"existing").unwrap();
+
+        assert!(import_backup(target.path(), &audio_dir, &archive, false).is_err());
+        assert_eq!(fs::read(target.path().join(USER_DB_FILE)).unwrap(), b"existing");
+
+        import_backup(target.path(), &audio_dir, &archive, true).unwrap();
+        assert_eq!(fs::read(target.path().join(USER_DB_FILE)).unwrap(), b"sqlite database bytes");
+    }
+
+    #[test]
+    fn test_import_rejects_newer_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("future.zip");
+
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file(METADATA_FILE, SimpleFileOptions::default()).unwrap();
+        serde_json::to_writer(
+            &mut zip,
+            &BackupMetadata {
+                format_version: BACKUP_FORMAT_VERSION + 1,
+                app_version: "99.0.0".to_string(),
+                created_at: 0,
+                source_audio_dir: String::new(),
+                audio_directory: None,
+            },
+        )
+        .unwrap();
+        zip.start_file(USER_DB_FILE, SimpleFileOptions::default()).unwrap();
+        zip.finish().unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        let err = import_backup(target.path(), &target.path().join(AUDIO_DIR), &archive, false).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+    }
+
+    #[test]
+    fn test_restore_target_skips_unexpected_entries() {
+        assert_eq!(restore_target(Path::new("user.db")), Some(PathBuf::from("user.db")));
+        assert_eq!(restore_target(Path::new("audio/a.wav")), Some(PathBuf::from("audio/a.wav")));
+        assert_eq!(restore_target(Path::new("backup.json")), None);
+        assert_eq!(restore_target(Path::new("models/ggml-base.bin")), None);
+        assert_eq!(restore_target(Path::new("audio/nested/a.wav")), None);
+    }
+
+    #[tokio::test]
+    async fn test_relink_audio_paths() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE sessions (id TEXT PRIMARY KEY, audio_path TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions VALUES ('s1', '/old/app/audio/s1.wav'), ('s2', '/Volumes/External/s2.wav'),
+             ('s3', NULL), ('s4', '/old/app/audio/s4.wav')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // s4's recording wasn't in the backup
+        let restored = tempfile::tempdir().unwrap();
+        fs::write(restored.path().join("s1.wav"), b"one").unwrap();
+        fs::write(restored.path().join("s2.wav"), b"two").unwrap();
+
+        let relinked = relink_audio_paths(&pool, restored.path()).await.unwrap();
+        assert_eq!(relinked, 2);
+
+        let paths: Vec<(String, Option<String>)> = sqlx::query_as("SELECT id, audio_path FROM sessions ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let restored_path = |name: &str| Some(restored.path().join(name).to_string_lossy().to_string());
+        assert_eq!(paths[0].1, restored_path("s1.wav"));
+        assert_eq!(paths[1].1, restored_path("s2.wav"));
+        assert_eq!(paths[2].1, None);
+        assert_eq!(paths[3].1.as_deref(), Some("/old/app/audio/s4.wav"));
+
+        // Already pointing at the restored files
+        assert_eq!(relink_audio_paths(&pool, restored.path()).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,239 @@
+/**
+ * Transcription benchmark service
+ *
+ * Measures how fast an installed model transcribes on this machine:
+ * - Generates a short reference clip (no audio asset needs to ship with the app)
+ * - Reports the real-time factor (audio seconds / processing seconds)
+ * - Keeps the latest result per model in benchmarks.json next to the models
+ */
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::AppHandle;
+
+use super::model_download::{get_available_models, get_model_path, get_models_dir};
+use super::transcription::{transcribe_audio_file, TranscriptionOptions};
+
+const BENCHMARKS_FILE: &str = "benchmarks.json";
+
+/// Length of the generated reference clip
+pub const REFERENCE_CLIP_SECONDS: f32 = 10.0;
+
+/// A model is only recommended if it transcribes at least this much faster than real time
+pub const MIN_REALTIME_FACTOR: f64 = 2.0;
+
+/// Measured transcription speed of one model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub model_name: String,
+    pub audio_seconds: f64,
+    /// Wall time spent transcribing (including model load)
+    pub processing_seconds: f64,
+    /// audio_seconds / processing_seconds - above 1.0 is faster than real time
+    pub real_time_factor: f64,
+    pub measured_at: i64,
+}
+
+/// Audio length divided by processing time (0 if nothing was measured)
+pub fn real_time_factor(audio_seconds: f64, processing_seconds: f64) -> f64 {
+    if processing_seconds > 0.0 {
+        audio_seconds / processing_seconds
+    } else {
+        0.0
+    }
+}
+
+/// Write a speech-like reference clip (16kHz mono 16-bit)
+///
+/// Syllable-length bursts of a voiced harmonic tone separated by short pauses, so the
+/// clip exercises the same encoder work as speech without shipping a recording.
+pub fn write_reference_clip(path: &Path, seconds: f32) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).context("Failed to create reference clip")?;
+
+    let total_samples = (seconds * spec.sample_rate as f32) as usize;
+    for i in 0..total_samples {
+        let t = i as f32 / spec.sample_rate as f32;
+
+        // ~4 syllables per second with a pause between words
+        let syllable = (t * 4.0).fract();
+        let envelope = if (t * 0.8).fract() < 0.8 {
+            (syllable * std::f32::consts::PI).sin()
+        } else {
+            0.0
+        };
+
+        // Pitch drifts around 140 Hz, with a few harmonics for vowel-like formants
+        let pitch = 140.0 + 20.0 * (t * 1.3).sin();
+        let voiced: f32 = (1..=5)
+            .map(|h| (2.0 * std::f32::consts::PI * pitch * h as f32 * t).sin() / h as f32)
+            .sum();
+
+        let sample = (voiced * envelope * 0.3).clamp(-1.0, 1.0);
+        writer.write_sample((sample * i16::MAX as f32) as i16)?;
+    }
+
+    writer.finalize().context("Failed to finalize reference clip")?;
+    Ok(())
+}
+
+/// Duration of a WAV file in seconds
+fn wav_duration_seconds(path: &Path) -> Result<f64> {
+    let reader = hound::WavReader::open(path).context("Failed to read clip")?;
+    let spec = reader.spec();
+    Ok(reader.duration() as f64 / spec.sample_rate as f64)
+}
+
+/// Time `transcribe` on `clip_path` and compute the real-time factor
+pub async fn benchmark_clip<F, Fut>(model_name: &str, clip_path: &Path, transcribe: F) -> Result<BenchmarkResult>
+where
+    F: FnOnce(PathBuf) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let audio_seconds = wav_duration_seconds(clip_path)?;
+
+    let started = Instant::now();
+    transcribe(clip_path.to_path_buf()).await?;
+    let processing_seconds = started.elapsed().as_secs_f64();
+
+    Ok(BenchmarkResult {
+        model_name: model_name.to_string(),
+        audio_seconds,
+        processing_seconds,
+        real_time_factor: real_time_factor(audio_seconds, processing_seconds),
+        measured_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Benchmark an installed model on the reference clip and store the result
+pub async fn benchmark_model(app: &AppHandle, model_name: &str) -> Result<BenchmarkResult> {
+    let model_path = get_model_path(app, model_name)?;
+    if !model_path.exists() {
+        anyhow::bail!("Model {} is not installed", model_name);
+    }
+
+    let clip_dir = tempfile::tempdir()?;
+    let clip_path = clip_dir.path().join("reference.wav");
+    write_reference_clip(&clip_path, REFERENCE_CLIP_SECONDS)?;
+
+    let result = benchmark_clip(model_name, &clip_path, |clip| async move {
+        transcribe_audio_file(&clip, &model_path, Some("en"), TranscriptionOptions::default(), None).await?;
+        Ok(())
+    })
+    .await?;
+
+    println!(
+        "[benchmark_model] {}: {:.1}x real time ({:.2}s for {:.1}s of audio)",
+        model_name, result.real_time_factor, result.processing_seconds, result.audio_seconds
+    );
+
+    save_benchmark(&get_models_dir(app)?, result.clone())?;
+    Ok(result)
+}
+
+/// Load stored benchmark results (empty if none measured yet)
+pub fn load_benchmarks(models_dir: &Path) -> Result<Vec<BenchmarkResult>> {
+    let path = models_dir.join(BENCHMARKS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&path).context("Failed to read benchmarks")?;
+    serde_json::from_str(&json).context("Failed to parse benchmarks")
+}
+
+/// Store a result, replacing any previous result for the same model
+pub fn save_benchmark(models_dir: &Path, result: BenchmarkResult) -> Result<()> {
+    let mut benchmarks = load_benchmarks(models_dir).unwrap_or_default();
+    benchmarks.retain(|b| b.model_name != result.model_name);
+    benchmarks.push(result);
+
+    let json = serde_json::to_string_pretty(&benchmarks)?;
+    fs::write(models_dir.join(BENCHMARKS_FILE), json).context("Failed to write benchmarks")
+}
+
+/// Pick the largest (most accurate) benchmarked model that is fast enough here
+/// Returns None if no measured model reaches `MIN_REALTIME_FACTOR`
+pub fn recommend_from_benchmarks(benchmarks: &[BenchmarkResult]) -> Option<String> {
+    let models = get_available_models();
+
+    benchmarks
+        .iter()
+        .filter(|b| b.real_time_factor >= MIN_REALTIME_FACTOR)
+        .filter_map(|b| {
+            models
+                .iter()
+                .find(|m| m.name == b.model_name)
+                .map(|m| (m.size_mb, m.name.clone()))
+        })
+        .max_by_key(|(size_mb, _)| *size_mb)
+        .map(|(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model_name: &str, real_time_factor: f64) -> BenchmarkResult {
+        BenchmarkResult {
+            model_name: model_name.to_string(),
+            audio_seconds: 10.0,
+            processing_seconds: 10.0 / real_time_factor,
+            real_time_factor,
+            measured_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_clip_computes_rtf() {
+        let dir = tempfile::tempdir().unwrap();
+        let clip = dir.path().join("clip.wav");
+        write_reference_clip(&clip, 0.5).unwrap();
+
+        let result = benchmark_clip("tiny", &clip, |path| async move {
+            // Stand-in for Whisper: read the clip back
+            hound::WavReader::open(&path)?;
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert!((result.audio_seconds - 0.5).abs() < 1e-3);
+        assert!(result.processing_seconds > 0.0);
+        assert!(result.real_time_factor > 0.0);
+        assert!((result.real_time_factor - result.audio_seconds / result.processing_seconds).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_save_benchmark_replaces_previous_result() {
+        let dir = tempfile::tempdir().unwrap();
+
+        save_benchmark(dir.path(), result("base", 3.0)).unwrap();
+        save_benchmark(dir.path(), result("small", 1.5)).unwrap();
+        save_benchmark(dir.path(), result("base", 4.0)).unwrap();
+
+        let stored = load_benchmarks(dir.path()).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored.iter().find(|b| b.model_name == "base").unwrap().real_time_factor, 4.0);
+    }
+
+    #[test]
+    fn test_recommend_from_benchmarks() {
+        let benchmarks = vec![result("tiny", 20.0), result("base", 8.0), result("small", 2.5), result("medium", 0.9)];
+        assert_eq!(recommend_from_benchmarks(&benchmarks), Some("small".to_string()));
+
+        // Nothing fast enough
+        assert_eq!(recommend_from_benchmarks(&[result("medium", 1.2)]), None);
+    }
+}
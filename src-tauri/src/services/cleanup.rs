@@ -1,15 +1,20 @@
 /**
  * Cleanup service for auto-deleting old sessions
  *
- * Handles deletion of sessions and their audio files based on retention policies.
+ * Handles deletion of sessions and their audio files based on retention policies
+ * (age and total audio size), and removal of audio files that no longer belong
+ * to any session.
  */
 
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
-use super::sessions::{delete_session, SessionData};
+use super::sessions::delete_session;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -23,28 +28,41 @@ pub struct CleanupStats {
 /// # Arguments
 /// * `pool` - Database connection pool
 /// * `retention_days` - Number of days to keep sessions (sessions older than this will be deleted)
+/// * `language` - Only clean up sessions in this language (all languages if None)
 ///
 /// # Returns
 /// * `CleanupStats` - Statistics about the cleanup operation
 pub async fn cleanup_old_sessions(
     pool: &SqlitePool,
     retention_days: i64,
+    language: Option<&str>,
 ) -> Result<CleanupStats> {
-    println!("[cleanup_old_sessions] Starting cleanup with retention_days: {}", retention_days);
+    println!(
+        "[cleanup_old_sessions] Starting cleanup with retention_days: {}, language: {:?}",
+        retention_days, language
+    );
 
     // Calculate cutoff timestamp (sessions older than this will be deleted)
     let cutoff_timestamp = Utc::now().timestamp() - (retention_days * 86400);
     println!("[cleanup_old_sessions] Cutoff timestamp: {} ({})", cutoff_timestamp, chrono::DateTime::from_timestamp(cutoff_timestamp, 0).unwrap());
 
     // Query sessions older than cutoff that have ended
-    // Only delete sessions that are complete (have ended_at)
-    let old_sessions = sqlx::query_as::<_, SessionData>(
-        "SELECT * FROM sessions WHERE ended_at IS NOT NULL AND ended_at < ?"
-    )
-    .bind(cutoff_timestamp)
-    .fetch_all(pool)
-    .await
-    .context("Failed to fetch old sessions")?;
+    // Only delete sessions that are complete (have ended_at) and not pinned
+    let mut sql = String::from(
+        "SELECT id FROM sessions WHERE ended_at IS NOT NULL AND ended_at < ? AND COALESCE(is_pinned, 0) = 0"
+    );
+    if language.is_some() {
+        sql.push_str(" AND language = ?");
+    }
+
+    let mut query = sqlx::query_scalar(&sql).bind(cutoff_timestamp);
+    if let Some(language) = language {
+        query = query.bind(language);
+    }
+    let old_sessions: Vec<String> = query
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch old sessions")?;
 
     let total_found = old_sessions.len();
     println!("[cleanup_old_sessions] Found {} sessions to delete", total_found);
@@ -53,15 +71,15 @@ pub async fn cleanup_old_sessions(
     let mut failed_count = 0;
 
     // Delete each session (this also deletes audio files via our fixed delete_session function)
-    for session in old_sessions {
-        match delete_session(pool, &session.id).await {
+    for session_id in old_sessions {
+        match delete_session(pool, &session_id).await {
             Ok(_) => {
                 deleted_count += 1;
-                println!("[cleanup_old_sessions] Deleted session: {}", session.id);
+                println!("[cleanup_old_sessions] Deleted session: {}", session_id);
             }
             Err(e) => {
                 failed_count += 1;
-                eprintln!("[cleanup_old_sessions] Failed to delete session {}: {}", session.id, e);
+                eprintln!("[cleanup_old_sessions] Failed to delete session {}: {}", session_id, e);
             }
         }
     }
@@ -74,6 +92,193 @@ pub async fn cleanup_old_sessions(
     })
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeCleanupStats {
+    pub deleted_count: usize,
+    pub failed_count: usize,
+    /// Total size of remaining session audio after cleanup
+    pub final_size_bytes: u64,
+}
+
+/// Size of a session's audio file on disk (0 if missing)
+fn audio_file_size(audio_path: Option<&str>) -> u64 {
+    audio_path
+        .filter(|p| !p.is_empty())
+        .and_then(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
+/// Delete the oldest sessions until their total audio size fits in `max_bytes`
+///
+/// Only completed, non-pinned sessions with audio on disk are deleted, oldest first.
+/// Pinned and in-progress sessions still count towards the total. Safe to run right
+/// after `cleanup_old_sessions` - sizes are read from whatever sessions remain.
+pub async fn cleanup_by_total_size(pool: &SqlitePool, max_bytes: u64) -> Result<SizeCleanupStats> {
+    println!("[cleanup_by_total_size] Starting cleanup with max_bytes: {}", max_bytes);
+
+    let sessions: Vec<(String, Option<String>, Option<i64>, i64)> = sqlx::query_as(
+        r#"
+        SELECT id, audio_path, ended_at, COALESCE(is_pinned, 0)
+        FROM sessions
+        ORDER BY started_at ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch sessions")?;
+
+    let mut candidates = Vec::new();
+    let mut total_size: u64 = 0;
+    for (id, audio_path, ended_at, is_pinned) in sessions {
+        let size = audio_file_size(audio_path.as_deref());
+        total_size += size;
+        if ended_at.is_some() && is_pinned == 0 && size > 0 {
+            candidates.push((id, size));
+        }
+    }
+
+    println!("[cleanup_by_total_size] Current audio size: {} bytes", total_size);
+
+    let mut deleted_count = 0;
+    let mut failed_count = 0;
+
+    for (session_id, size) in candidates {
+        if total_size <= max_bytes {
+            break;
+        }
+
+        match delete_session(pool, &session_id).await {
+            Ok(_) => {
+                deleted_count += 1;
+                total_size = total_size.saturating_sub(size);
+                println!("[cleanup_by_total_size] Deleted session: {} ({} bytes)", session_id, size);
+            }
+            Err(e) => {
+                failed_count += 1;
+                eprintln!("[cleanup_by_total_size] Failed to delete session {}: {}", session_id, e);
+            }
+        }
+    }
+
+    println!(
+        "[cleanup_by_total_size] Cleanup complete: deleted={}, failed={}, final_size={} bytes",
+        deleted_count, failed_count, total_size
+    );
+
+    Ok(SizeCleanupStats {
+        deleted_count,
+        failed_count,
+        final_size_bytes: total_size,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanCleanupStats {
+    pub deleted_count: usize,
+    pub failed_count: usize,
+    pub reclaimed_bytes: u64,
+}
+
+/// Files modified more recently than this are left alone (they may still be recording)
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+/// Delete audio files in `audio_dir` that no session references
+///
+/// A file counts as referenced if a session's `audio_path` points to it, or if it is
+/// named after an existing session (`<session_id>.wav`) whose path isn't saved yet.
+pub async fn cleanup_orphaned_audio(pool: &SqlitePool, audio_dir: &Path) -> Result<OrphanCleanupStats> {
+    remove_orphaned_files(pool, audio_dir, false, ORPHAN_GRACE_PERIOD).await
+}
+
+/// `cleanup_orphaned_audio` for a directory the user picked for recordings
+/// It may hold unrelated files too, so only `.wav` files are considered.
+pub async fn cleanup_orphaned_recordings(pool: &SqlitePool, audio_dir: &Path) -> Result<OrphanCleanupStats> {
+    remove_orphaned_files(pool, audio_dir, true, ORPHAN_GRACE_PERIOD).await
+}
+
+async fn remove_orphaned_files(
+    pool: &SqlitePool,
+    audio_dir: &Path,
+    wav_only: bool,
+    min_age: Duration,
+) -> Result<OrphanCleanupStats> {
+    let mut stats = OrphanCleanupStats {
+        deleted_count: 0,
+        failed_count: 0,
+        reclaimed_bytes: 0,
+    };
+
+    if !audio_dir.exists() {
+        return Ok(stats);
+    }
+
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as("SELECT id, audio_path FROM sessions")
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch session audio paths")?;
+
+    // Compare by file name so references survive a moved app data directory
+    let mut referenced: HashSet<String> = HashSet::new();
+    for (id, audio_path) in rows {
+        referenced.insert(format!("{}.wav", id));
+        if let Some(name) = audio_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_name())
+            .and_then(|n| n.to_str())
+        {
+            referenced.insert(name.to_string());
+        }
+    }
+
+    let entries = std::fs::read_dir(audio_dir).context("Failed to read audio directory")?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if referenced.contains(&file_name) {
+            continue;
+        }
+        if wav_only && !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav")) {
+            continue;
+        }
+
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+        if age < min_age {
+            continue;
+        }
+
+        match std::fs::remove_file(&path) {
+            Ok(_) => {
+                stats.deleted_count += 1;
+                stats.reclaimed_bytes += metadata.len();
+                println!("[cleanup_orphaned_audio] Deleted orphaned file: {}", path.display());
+            }
+            Err(e) => {
+                stats.failed_count += 1;
+                eprintln!("[cleanup_orphaned_audio] Failed to delete {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    println!(
+        "[cleanup_orphaned_audio] Cleanup complete: deleted={}, failed={}, reclaimed={} bytes",
+        stats.deleted_count, stats.failed_count, stats.reclaimed_bytes
+    );
+
+    Ok(stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,7 +303,8 @@ mod tests {
                 primary_language TEXT NOT NULL,
                 started_at INTEGER NOT NULL,
                 ended_at INTEGER,
-                audio_path TEXT
+                audio_path TEXT,
+                is_pinned INTEGER DEFAULT 0
             )
             "#,
         )
@@ -106,6 +312,11 @@ mod tests {
         .await
         .unwrap();
 
+        sqlx::query("CREATE TABLE session_words (session_id TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
         // Create test sessions
         let now = Utc::now().timestamp();
         let old_session_time = (Utc::now() - Duration::days(40)).timestamp();
@@ -137,7 +348,7 @@ mod tests {
         .unwrap();
 
         // Run cleanup with 30 day retention
-        let stats = cleanup_old_sessions(&pool, 30).await.unwrap();
+        let stats = cleanup_old_sessions(&pool, 30, None).await.unwrap();
 
         // Verify results
         assert_eq!(stats.deleted_count, 1);
@@ -152,4 +363,201 @@ mod tests {
         assert_eq!(remaining.len(), 1);
         assert_eq!(remaining[0], "recent");
     }
+
+    #[tokio::test]
+    async fn test_cleanup_skips_pinned_sessions() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        sqlx::query(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, ended_at INTEGER, audio_path TEXT, is_pinned INTEGER DEFAULT 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE session_words (session_id TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let old_session_time = (Utc::now() - Duration::days(400)).timestamp();
+        sqlx::query("INSERT INTO sessions (id, ended_at, is_pinned) VALUES ('pinned', ?, 1), ('unpinned', ?, 0)")
+            .bind(old_session_time)
+            .bind(old_session_time)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stats = cleanup_old_sessions(&pool, 30, None).await.unwrap();
+        assert_eq!(stats.deleted_count, 1);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["pinned".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_scoped_to_language() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        sqlx::query(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, language TEXT NOT NULL, ended_at INTEGER, audio_path TEXT, is_pinned INTEGER DEFAULT 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE session_words (session_id TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let old_session_time = (Utc::now() - Duration::days(100)).timestamp();
+        sqlx::query(
+            "INSERT INTO sessions (id, language, ended_at) VALUES ('es-1', 'es', ?), ('es-2', 'es', ?), ('fr-1', 'fr', ?)",
+        )
+        .bind(old_session_time)
+        .bind(old_session_time)
+        .bind(old_session_time)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let stats = cleanup_old_sessions(&pool, 30, Some("es")).await.unwrap();
+        assert_eq!(stats.deleted_count, 2);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["fr-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_orphaned_audio() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        sqlx::query("CREATE TABLE sessions (id TEXT PRIMARY KEY, audio_path TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let referenced = dir.path().join("kept.wav");
+        let pending = dir.path().join("in-progress.wav");
+        let orphan = dir.path().join("orphan.wav");
+        std::fs::write(&referenced, vec![0u8; 10]).unwrap();
+        std::fs::write(&pending, vec![0u8; 20]).unwrap();
+        std::fs::write(&orphan, vec![0u8; 30]).unwrap();
+
+        sqlx::query("INSERT INTO sessions (id, audio_path) VALUES (?, ?), (?, NULL)")
+            .bind("kept")
+            .bind(referenced.to_string_lossy().to_string())
+            .bind("in-progress")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stats = remove_orphaned_files(&pool, dir.path(), false, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.deleted_count, 1);
+        assert_eq!(stats.failed_count, 0);
+        assert_eq!(stats.reclaimed_bytes, 30);
+        assert!(referenced.exists());
+        assert!(pending.exists());
+        assert!(!orphan.exists());
+
+        // Recently written files are protected by the grace period
+        std::fs::write(&orphan, vec![0u8; 30]).unwrap();
+        let stats = cleanup_orphaned_audio(&pool, dir.path()).await.unwrap();
+        assert_eq!(stats.deleted_count, 0);
+        assert!(orphan.exists());
+
+        // A user-picked directory only loses unreferenced recordings, not other files
+        let picked = tempfile::tempdir().unwrap();
+        let recording = picked.path().join("old-session.wav");
+        let notes = picked.path().join("notes.txt");
+        std::fs::write(&recording, vec![0u8; 40]).unwrap();
+        std::fs::write(&notes, b"not ours").unwrap();
+        let stats = remove_orphaned_files(&pool, picked.path(), true, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!((stats.deleted_count, stats.reclaimed_bytes), (1, 40));
+        assert!(!recording.exists());
+        assert!(notes.exists());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_by_total_size() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        sqlx::query(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, started_at INTEGER NOT NULL, ended_at INTEGER, audio_path TEXT, is_pinned INTEGER DEFAULT 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE session_words (session_id TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        // (id, started_at, size, pinned) - oldest first
+        for (id, started_at, size, pinned) in [
+            ("pinned", 1, 50, 1),
+            ("oldest", 2, 100, 0),
+            ("middle", 3, 200, 0),
+            ("newest", 4, 300, 0),
+        ] {
+            let path = dir.path().join(format!("{}.wav", id));
+            std::fs::write(&path, vec![0u8; size]).unwrap();
+            sqlx::query("INSERT INTO sessions (id, started_at, ended_at, audio_path, is_pinned) VALUES (?, ?, ?, ?, ?)")
+                .bind(id)
+                .bind(started_at)
+                .bind(started_at)
+                .bind(path.to_string_lossy().to_string())
+                .bind(pinned)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        // 650 bytes total, budget 350: drops "oldest" (100) then "middle" (200)
+        let stats = cleanup_by_total_size(&pool, 350).await.unwrap();
+
+        assert_eq!(stats.deleted_count, 2);
+        assert_eq!(stats.failed_count, 0);
+        assert_eq!(stats.final_size_bytes, 350);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM sessions ORDER BY started_at")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["pinned".to_string(), "newest".to_string()]);
+        assert!(!dir.path().join("oldest.wav").exists());
+        assert!(dir.path().join("pinned.wav").exists());
+
+        // Already under budget: nothing else is deleted
+        let stats = cleanup_by_total_size(&pool, 1000).await.unwrap();
+        assert_eq!(stats.deleted_count, 0);
+    }
 }
@@ -0,0 +1,274 @@
+/**
+ * Dictionary service
+ *
+ * External dictionary lookups:
+ * - Listing dictionaries for a language pair
+ * - Editing a dictionary's name, URL template and type
+ * - Validating that templates contain the [WORD] placeholder
+ * - Building lookup URLs with the word safely URL-encoded
+ */
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use url::Url;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Dictionary {
+    pub id: i64,
+    pub language: String,
+    pub target_language: String,
+    pub name: String,
+    pub url_template: String,
+    pub dict_type: String,
+    pub is_active: i64,
+    pub sort_order: i64,
+    pub is_default: i64,
+    pub created_at: i64,
+}
+
+/// Target language assumed for dictionaries created before language pairs existed
+pub const DEFAULT_TARGET_LANGUAGE: &str = "en";
+
+/// Placeholder replaced by the looked-up word in a dictionary URL template
+pub const WORD_PLACEHOLDER: &str = "[WORD]";
+
+/// Get dictionaries translating from `language` into `target_language`
+///
+/// Rows without a target language are treated as translating into English.
+pub async fn get_dictionaries_for_pair(
+    pool: &SqlitePool,
+    language: &str,
+    target_language: &str,
+) -> Result<Vec<Dictionary>> {
+    sqlx::query_as::<_, Dictionary>(
+        r#"
+        SELECT id, language, COALESCE(target_language, 'en') AS target_language, name,
+               url_template, dict_type, is_active, sort_order, is_default, created_at
+        FROM dictionaries
+        WHERE language = ? AND COALESCE(target_language, 'en') = ?
+        ORDER BY sort_order ASC
+        "#,
+    )
+    .bind(language)
+    .bind(target_language)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get dictionaries")
+}
+
+/// Check that a dictionary URL template has a [WORD] placeholder and is a valid http(s) URL
+pub fn validate_url_template(url_template: &str) -> Result<()> {
+    if !url_template.contains(WORD_PLACEHOLDER) {
+        anyhow::bail!(
+            "URL template must contain the {} placeholder, e.g. https://www.wordreference.com/es/en/translation.asp?spen=[WORD]",
+            WORD_PLACEHOLDER
+        );
+    }
+
+    let sample = url_template.replace(WORD_PLACEHOLDER, "test");
+    let url = Url::parse(&sample)
+        .map_err(|e| anyhow::anyhow!("URL template is not a valid URL: {}", e))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        anyhow::bail!("URL template must start with http:// or https://");
+    }
+
+    Ok(())
+}
+
+/// Check that a dictionary type is one the frontend can display
+pub fn validate_dict_type(dict_type: &str) -> Result<()> {
+    if dict_type != "embedded" && dict_type != "popup" {
+        anyhow::bail!("dict_type must be 'embedded' or 'popup'");
+    }
+    Ok(())
+}
+
+/// Change a dictionary's name, URL template and type, keeping its place in the list
+/// Default dictionaries can only be renamed.
+pub async fn update_dictionary(
+    pool: &SqlitePool,
+    id: i64,
+    name: &str,
+    url_template: &str,
+    dict_type: &str,
+) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Dictionary name can't be empty");
+    }
+    validate_dict_type(dict_type)?;
+    validate_url_template(url_template)?;
+
+    let (is_default, current_url, current_type): (i64, String, String) = sqlx::query_as(
+        "SELECT is_default, url_template, dict_type FROM dictionaries WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to get dictionary")?
+    .ok_or_else(|| anyhow::anyhow!("Dictionary {} not found", id))?;
+
+    if is_default == 1 && (url_template != current_url || dict_type != current_type) {
+        anyhow::bail!("Default dictionaries can only be renamed");
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE dictionaries
+        SET name = ?, url_template = ?, dict_type = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(name)
+    .bind(url_template)
+    .bind(dict_type)
+    .bind(id)
+    .execute(pool)
+    .await
+    .context("Failed to update dictionary")?;
+
+    Ok(())
+}
+
+/// Substitute a URL-encoded word into a dictionary URL template
+pub fn build_dictionary_url(url_template: &str, word: &str) -> String {
+    url_template.replace(WORD_PLACEHOLDER, &urlencoding::encode(word.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE dictionaries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                language TEXT NOT NULL,
+                name TEXT NOT NULL,
+                url_template TEXT NOT NULL,
+                target_language TEXT,
+                dict_type TEXT NOT NULL,
+                is_active INTEGER NOT NULL DEFAULT 1,
+                sort_order INTEGER NOT NULL,
+                is_default INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_get_dictionaries_for_pair() {
+        let pool = setup_test_db().await;
+
+        for (language, target, name, sort) in [
+            ("es", Some("fr"), "WordReference ES-FR", 1),
+            ("es", Some("en"), "WordReference ES-EN", 1),
+            ("es", None, "Legacy ES", 2),
+            ("fr", Some("en"), "WordReference FR-EN", 1),
+        ] {
+            sqlx::query(
+                "INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, sort_order, created_at) VALUES (?, ?, ?, 'https://example.com/[WORD]', 'popup', ?, 0)",
+            )
+            .bind(language)
+            .bind(target)
+            .bind(name)
+            .bind(sort)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let es_fr = get_dictionaries_for_pair(&pool, "es", "fr").await.unwrap();
+        let names: Vec<&str> = es_fr.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["WordReference ES-FR"]);
+
+        // NULL target language counts as English
+        let es_en = get_dictionaries_for_pair(&pool, "es", "en").await.unwrap();
+        let names: Vec<&str> = es_en.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["WordReference ES-EN", "Legacy ES"]);
+        assert!(es_en.iter().all(|d| d.target_language == "en"));
+    }
+
+    #[tokio::test]
+    async fn test_update_dictionary() {
+        let pool = setup_test_db().await;
+        for (name, sort, is_default) in [("RAE", 1, 1), ("My dictionary", 2, 0), ("Other", 3, 0)] {
+            sqlx::query(
+                "INSERT INTO dictionaries (language, target_language, name, url_template, dict_type, sort_order, is_default, created_at) VALUES ('es', 'en', ?, 'https://example.com/[WORD]', 'popup', ?, ?, 0)",
+            )
+            .bind(name)
+            .bind(sort)
+            .bind(is_default)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        update_dictionary(&pool, 2, " Linguee ", "https://www.linguee.com/search?query=[WORD]", "embedded")
+            .await
+            .unwrap();
+
+        let dictionaries = get_dictionaries_for_pair(&pool, "es", "en").await.unwrap();
+        let edited = &dictionaries[1];
+        assert_eq!(edited.id, 2);
+        assert_eq!(edited.name, "Linguee");
+        assert_eq!(edited.url_template, "https://www.linguee.com/search?query=[WORD]");
+        assert_eq!(edited.dict_type, "embedded");
+        assert_eq!(edited.sort_order, 2);
+
+        // Same validation as adding
+        assert!(update_dictionary(&pool, 2, "Linguee", "https://www.linguee.com/", "popup").await.is_err());
+        assert!(update_dictionary(&pool, 2, "Linguee", "https://example.com/[WORD]", "tab").await.is_err());
+        assert!(update_dictionary(&pool, 2, "  ", "https://example.com/[WORD]", "popup").await.is_err());
+        assert!(update_dictionary(&pool, 99, "Missing", "https://example.com/[WORD]", "popup").await.is_err());
+
+        // Default dictionaries can be renamed but not repointed
+        update_dictionary(&pool, 1, "RAE (Spain)", "https://example.com/[WORD]", "popup").await.unwrap();
+        let err = update_dictionary(&pool, 1, "RAE", "https://evil.example/[WORD]", "popup").await.unwrap_err();
+        assert!(err.to_string().contains("renamed"));
+        assert_eq!(get_dictionaries_for_pair(&pool, "es", "en").await.unwrap()[0].name, "RAE (Spain)");
+    }
+
+    #[test]
+    fn test_validate_rejects_missing_placeholder() {
+        let err = validate_url_template("https://dle.rae.es/").unwrap_err();
+        assert!(err.to_string().contains("[WORD]"));
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_url() {
+        assert!(validate_url_template("not a url [WORD]").is_err());
+        assert!(validate_url_template("ftp://example.com/[WORD]").is_err());
+        assert!(validate_url_template("https://dle.rae.es/[WORD]").is_ok());
+        assert!(validate_url_template("https://example.com/search?q=[WORD]&lang=es").is_ok());
+    }
+
+    #[test]
+    fn test_build_url_encodes_word() {
+        assert_eq!(
+            build_dictionary_url("https://dle.rae.es/[WORD]", "casa"),
+            "https://dle.rae.es/casa"
+        );
+        assert_eq!(
+            build_dictionary_url("https://example.com/?q=[WORD]", "hacer caso"),
+            "https://example.com/?q=hacer%20caso"
+        );
+        assert_eq!(
+            build_dictionary_url("https://example.com/?q=[WORD]", "niño"),
+            "https://example.com/?q=ni%C3%B1o"
+        );
+        assert_eq!(
+            build_dictionary_url("https://example.com/?q=[WORD]", "a&b"),
+            "https://example.com/?q=a%26b"
+        );
+    }
+}
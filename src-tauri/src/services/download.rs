@@ -0,0 +1,1310 @@
+/**
+ * Shared HTTP download helpers
+ *
+ * Used by both Whisper model and language pack downloads:
+ * - Resumable transfers via HTTP Range requests into a `.partial` file
+ * - Progress reporting relative to the full file size
+ * - SHA-256 verification before the file is moved into place, against a pinned
+ *   digest or the one Hugging Face publishes for the file
+ * - Free disk space checks before any bytes are written
+ * - Cancellation via a shared flag checked between chunks
+ * - Retries with exponential backoff on transient network errors
+ * - Falling back to mirror hosts when the primary host fails
+ * - Throttled progress reporting with speed and ETA
+ * - Optional parallel download of large files as concurrent ranged chunks
+ */
+
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::Disks;
+use thiserror::Error;
+
+/// Extra headroom required on top of the file size (100 MB)
+const DISK_SPACE_MARGIN_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Download failures the frontend may want to distinguish from generic network errors
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error("Checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Insufficient disk space: {required_bytes} bytes required, {available_bytes} bytes available")]
+    InsufficientDiskSpace {
+        required_bytes: u64,
+        available_bytes: u64,
+    },
+
+    #[error("Download cancelled")]
+    Cancelled,
+
+    #[error("Download failed with HTTP status: {status} for URL: {url}")]
+    HttpStatus { status: u16, url: String },
+}
+
+/// Path of the in-progress download for a destination file
+/// e.g. `ggml-small.bin` -> `ggml-small.bin.partial`
+pub fn partial_path(destination: &Path) -> PathBuf {
+    let mut file_name = destination
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".partial");
+    destination.with_file_name(file_name)
+}
+
+/// Free space on the disk that holds `dir`, or `None` if it can't be determined
+pub fn available_space(dir: &Path) -> Option<u64> {
+    let target = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+
+    // Pick the most specific mount point containing the target directory
+    disks
+        .list()
+        .iter()
+        .filter(|disk| target.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Compare the bytes a download still needs (plus a safety margin) against free space
+pub fn check_disk_space(required_bytes: u64, available_bytes: u64) -> Result<(), DownloadError> {
+    let required_with_margin = required_bytes.saturating_add(DISK_SPACE_MARGIN_BYTES);
+    if required_with_margin > available_bytes {
+        return Err(DownloadError::InsufficientDiskSpace {
+            required_bytes: required_with_margin,
+            available_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Fail early if `dir` doesn't have room for `required_bytes`
+/// If free space can't be determined the download is allowed to proceed.
+pub fn ensure_disk_space(dir: &Path, required_bytes: u64) -> Result<()> {
+    match available_space(dir) {
+        Some(available) => check_disk_space(required_bytes, available).map_err(Into::into),
+        None => {
+            println!("[ensure_disk_space] Could not determine free space for {:?}, skipping check", dir);
+            Ok(())
+        }
+    }
+}
+
+/// Download `url` into `partial_path`, resuming from any bytes already on disk
+///
+/// If the partial file exists, a `Range: bytes=<len>-` request is sent and the
+/// response is appended. If the server ignores the range (200 instead of 206),
+/// the partial file is truncated and the download starts over.
+///
+/// `on_progress` receives `(downloaded_bytes, total_bytes)` after every chunk,
+/// where both values include the bytes that were already on disk.
+/// `total_bytes` is 0 if the server did not report a content length.
+///
+/// `cancel` is checked before each chunk is written. Once set, the stream is
+/// dropped, the partial file is deleted and `DownloadError::Cancelled` is returned.
+///
+/// Returns the total size of the partial file once the stream completes.
+/// The caller is responsible for renaming it to the final destination.
+pub async fn download_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<u64> {
+    // A file pre-allocated by `download_chunked` isn't a contiguous prefix, so start over
+    let chunks = chunks_path(partial_path);
+    if chunks.exists() {
+        println!("[download_resumable] Discarding chunked partial file {:?}", partial_path);
+        let _ = std::fs::remove_file(partial_path);
+        let _ = std::fs::remove_file(&chunks);
+    }
+
+    let existing_bytes = std::fs::metadata(partial_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let mut request = client.get(url);
+    if existing_bytes > 0 {
+        println!(
+            "[download_resumable] Resuming {:?} from byte {}",
+            partial_path, existing_bytes
+        );
+        request = request.header(RANGE, format!("bytes={}-", existing_bytes));
+    }
+
+    let response = request.send().await.context("Failed to start download")?;
+    let status = response.status();
+
+    // Server says the range starts past the end - the partial file is already complete
+    if existing_bytes > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+        println!("[download_resumable] Partial file already complete: {:?}", partial_path);
+        on_progress(existing_bytes, existing_bytes);
+        return Ok(existing_bytes);
+    }
+
+    if !status.is_success() {
+        return Err(DownloadError::HttpStatus {
+            status: status.as_u16(),
+            url: url.to_string(),
+        }
+        .into());
+    }
+
+    let resuming = existing_bytes > 0 && status == StatusCode::PARTIAL_CONTENT;
+    if existing_bytes > 0 && !resuming {
+        println!("[download_resumable] Server ignored range request, restarting download");
+    }
+
+    let baseline = if resuming { existing_bytes } else { 0 };
+    let total_size = response
+        .content_length()
+        .map(|len| len + baseline)
+        .unwrap_or(0);
+
+    // Make sure the rest of the file fits before writing anything
+    if let (Some(remaining), Some(dir)) = (response.content_length(), partial_path.parent()) {
+        ensure_disk_space(dir, remaining)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resuming)
+        .truncate(!resuming)
+        .open(partial_path)
+        .context("Failed to open partial download file")?;
+
+    let mut downloaded = baseline;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            println!("[download_resumable] Cancelled, removing {:?}", partial_path);
+            drop(stream);
+            drop(file);
+            let _ = std::fs::remove_file(partial_path);
+            return Err(DownloadError::Cancelled.into());
+        }
+
+        let chunk = chunk.context("Error while downloading")?;
+        file.write_all(&chunk).context("Failed to write chunk")?;
+
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total_size);
+    }
+
+    file.sync_all().context("Failed to sync file")?;
+
+    Ok(downloaded)
+}
+
+/// Minimum time between progress events, so the frontend isn't flooded
+pub const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Download speed and estimated time remaining
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferRate {
+    pub speed_mbps: f32,
+    /// None until a speed is known (or when the total size is unknown)
+    pub eta_seconds: Option<u64>,
+}
+
+/// Speed (MB/s) and ETA from bytes fetched in `elapsed` with `remaining_bytes` left
+pub fn transfer_rate(fetched_bytes: u64, elapsed: Duration, remaining_bytes: Option<u64>) -> TransferRate {
+    let elapsed_secs = elapsed.as_secs_f64();
+    let bytes_per_sec = if elapsed_secs > 0.0 {
+        fetched_bytes as f64 / elapsed_secs
+    } else {
+        0.0
+    };
+
+    let eta_seconds = match remaining_bytes {
+        Some(remaining) if bytes_per_sec > 0.0 => Some((remaining as f64 / bytes_per_sec).ceil() as u64),
+        _ => None,
+    };
+
+    TransferRate {
+        speed_mbps: (bytes_per_sec / 1_000_000.0) as f32,
+        eta_seconds,
+    }
+}
+
+/// Tracks a download's progress for throttled reporting
+///
+/// Speed is measured from the bytes fetched since the meter started, not from
+/// the size of a resumed partial file.
+pub struct ProgressMeter {
+    started: Instant,
+    baseline: Option<u64>,
+    last_emit: Option<Instant>,
+}
+
+impl ProgressMeter {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            baseline: None,
+            last_emit: None,
+        }
+    }
+
+    /// Returns the current rate if a progress event is due (always true for the
+    /// first and the final update), otherwise None
+    pub fn tick(&mut self, downloaded: u64, total: u64) -> Option<TransferRate> {
+        let baseline = *self.baseline.get_or_insert(downloaded);
+
+        let due = downloaded == total
+            || self.last_emit.is_none_or(|t| t.elapsed() >= PROGRESS_EMIT_INTERVAL);
+        if !due {
+            return None;
+        }
+        self.last_emit = Some(Instant::now());
+
+        let remaining = (total > 0).then(|| total.saturating_sub(downloaded));
+        Some(transfer_rate(
+            downloaded.saturating_sub(baseline),
+            self.started.elapsed(),
+            remaining,
+        ))
+    }
+}
+
+impl Default for ProgressMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How often and how patiently to retry a failed download
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (1-based): initial * 2^(attempt-1), capped
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether a download error is worth retrying
+///
+/// Connection drops, timeouts and server-side (5xx / 429) errors are transient.
+/// Client errors like 404, checksum failures, cancellation and full disks are not.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    if let Some(download_err) = err.downcast_ref::<DownloadError>() {
+        return match download_err {
+            DownloadError::HttpStatus { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        };
+    }
+
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .is_some_and(|e| {
+            // A connection dropped mid-stream surfaces as a body decode error
+            e.is_connect() || e.is_timeout() || e.is_request() || e.is_body() || e.is_decode()
+        })
+}
+
+/// `download_resumable` with retries on transient errors
+///
+/// Each retry resumes from whatever the previous attempt left in the partial file.
+pub async fn download_resumable_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    cancel: &AtomicBool,
+    policy: &RetryPolicy,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<u64> {
+    let mut attempt = 1;
+    loop {
+        match download_resumable(client, url, partial_path, cancel, &mut on_progress).await {
+            Ok(size) => return Ok(size),
+            Err(e) if attempt < policy.max_attempts && is_retryable(&e) && !cancel.load(Ordering::Relaxed) => {
+                let delay = policy.backoff(attempt);
+                println!(
+                    "[download_resumable_with_retry] Attempt {}/{} failed: {:#}. Retrying in {:?}",
+                    attempt, policy.max_attempts, e, delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Download from the first host that works, trying `urls` in order
+///
+/// Each host gets the full retry policy. The partial file is kept between hosts,
+/// so a mirror serving the same file picks up where the previous host stopped
+/// (the checksum in `finalize_download` catches mismatched mirrors).
+/// Returns the final size and the URL that succeeded.
+pub async fn download_from_mirrors(
+    client: &reqwest::Client,
+    urls: &[String],
+    partial_path: &Path,
+    cancel: &AtomicBool,
+    policy: &RetryPolicy,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(u64, String)> {
+    let mut last_error = None;
+
+    for url in urls {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(DownloadError::Cancelled.into());
+        }
+
+        match download_resumable_with_retry(client, url, partial_path, cancel, policy, &mut on_progress).await {
+            Ok(size) => {
+                println!("[download_from_mirrors] Downloaded from {}", url);
+                return Ok((size, url.clone()));
+            }
+            Err(e) if matches!(e.downcast_ref::<DownloadError>(), Some(DownloadError::Cancelled)) => {
+                return Err(e);
+            }
+            Err(e) => {
+                println!("[download_from_mirrors] {} failed: {:#}", url, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No download URLs provided")))
+}
+
+/// Most ranged requests `download_chunked` sends to one host at once
+pub const MAX_CHUNK_CONCURRENCY: usize = 8;
+
+/// How `download_chunked` splits a file into ranged requests
+#[derive(Debug, Clone)]
+pub struct ChunkedDownload {
+    /// Chunks downloaded at once (capped at `MAX_CHUNK_CONCURRENCY`)
+    pub concurrency: usize,
+    /// Bytes per ranged request
+    pub chunk_size: u64,
+}
+
+impl Default for ChunkedDownload {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            chunk_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// File listing the finished chunks of a chunked download, one index per line
+/// e.g. `ggml-large-v3.bin.partial` -> `ggml-large-v3.bin.partial.chunks`
+pub fn chunks_path(partial_path: &Path) -> PathBuf {
+    let mut file_name = partial_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".chunks");
+    partial_path.with_file_name(file_name)
+}
+
+/// Total size from a `Content-Range: bytes 0-0/12345` header
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Size of the file at `url` if the server answers Range requests, None if it doesn't
+async fn probe_range_support(client: &reqwest::Client, url: &str) -> Result<Option<u64>> {
+    let response = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .send()
+        .await
+        .context("Failed to probe download")?;
+
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Ok(None);
+    }
+
+    Ok(response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_content_range_total))
+}
+
+/// Chunks of a previous attempt that are already on disk
+///
+/// With a chunks file, those it lists (if the partial file has the full size).
+/// Without one, the partial file is a contiguous prefix left by a single-stream
+/// download, so every chunk it fully covers counts.
+fn finished_chunks(partial_path: &Path, total: u64, chunk_size: u64) -> BTreeSet<u64> {
+    let partial_len = match std::fs::metadata(partial_path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return BTreeSet::new(),
+    };
+    let chunk_count = total.div_ceil(chunk_size);
+
+    match std::fs::read_to_string(chunks_path(partial_path)) {
+        Ok(listed) if partial_len == total => listed
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .filter(|&index| index < chunk_count)
+            .collect(),
+        Ok(_) => BTreeSet::new(),
+        Err(_) if partial_len <= total => (0..chunk_count)
+            .take_while(|index| ((index + 1) * chunk_size).min(total) <= partial_len)
+            .collect(),
+        Err(_) => BTreeSet::new(),
+    }
+}
+
+/// Record a finished chunk so an interrupted download can skip it
+fn mark_chunk_finished(partial_path: &Path, index: u64) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(chunks_path(partial_path))
+        .context("Failed to open chunks file")?;
+    writeln!(file, "{}", index).context("Failed to record finished chunk")?;
+    Ok(())
+}
+
+/// Download bytes `start..=end` of `url` into the same offset of `partial_path`
+async fn fetch_range(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    (start, end): (u64, u64),
+    cancel: &AtomicBool,
+    mut on_bytes: impl FnMut(u64),
+) -> Result<()> {
+    let response = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await
+        .context("Failed to start chunk download")?;
+    let status = response.status();
+
+    if !status.is_success() {
+        return Err(DownloadError::HttpStatus {
+            status: status.as_u16(),
+            url: url.to_string(),
+        }
+        .into());
+    }
+    if status != StatusCode::PARTIAL_CONTENT {
+        anyhow::bail!("Server ignored range request for bytes {}-{}", start, end);
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(partial_path)
+        .context("Failed to open partial download file")?;
+    file.seek(SeekFrom::Start(start)).context("Failed to seek in partial download file")?;
+
+    let expected = end - start + 1;
+    let mut received = 0;
+    let mut stream = response.bytes_stream();
+
+    while received < expected {
+        let Some(chunk) = stream.next().await else {
+            anyhow::bail!("Chunk ended after {} of {} bytes", received, expected);
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return Err(DownloadError::Cancelled.into());
+        }
+
+        let chunk = chunk.context("Error while downloading")?;
+        // Never write past the range, even if the server sends more
+        let take = (chunk.len() as u64).min(expected - received);
+        file.write_all(&chunk[..take as usize]).context("Failed to write chunk")?;
+
+        received += take;
+        on_bytes(take);
+    }
+
+    file.sync_data().context("Failed to sync file")?;
+    Ok(())
+}
+
+/// `fetch_range` with retries; each retry continues where the previous attempt stopped
+async fn fetch_range_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    (start, end): (u64, u64),
+    cancel: &AtomicBool,
+    policy: &RetryPolicy,
+    on_bytes: impl Fn(u64),
+) -> Result<()> {
+    let mut written = 0;
+    let mut attempt = 1;
+    loop {
+        let from = start + written;
+        let result = fetch_range(client, url, partial_path, (from, end), cancel, |bytes| {
+            written += bytes;
+            on_bytes(bytes);
+        })
+        .await;
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < policy.max_attempts && is_retryable(&e) && !cancel.load(Ordering::Relaxed) => {
+                let delay = policy.backoff(attempt);
+                println!(
+                    "[download_chunked] Bytes {}-{} attempt {}/{} failed: {:#}. Retrying in {:?}",
+                    start, end, attempt, policy.max_attempts, e, delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Download `url` into `partial_path` as concurrent ranged requests
+///
+/// The partial file is pre-allocated to the full size and each chunk is written at
+/// its own offset. Finished chunks are recorded in a `.chunks` file next to it, so an
+/// interrupted download resumes with the missing chunks only (a partial file left
+/// by a single-stream download is picked up too).
+///
+/// Falls back to `download_resumable_with_retry` if the server doesn't answer
+/// Range requests. `on_progress` gets the bytes on disk across all chunks.
+/// Cancelling removes the partial file, like `download_resumable`.
+///
+/// Returns the total size once every chunk has arrived.
+pub async fn download_chunked(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    options: &ChunkedDownload,
+    cancel: &AtomicBool,
+    policy: &RetryPolicy,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<u64> {
+    let total = match probe_range_support(client, url).await {
+        Ok(Some(total)) if total > 0 => total,
+        result => {
+            if let Err(e) = result {
+                println!("[download_chunked] Range probe failed: {:#}", e);
+            }
+            println!("[download_chunked] No ranged requests for {}, using a single stream", url);
+            return download_resumable_with_retry(client, url, partial_path, cancel, policy, on_progress).await;
+        }
+    };
+
+    let chunk_size = options.chunk_size.max(1);
+    let concurrency = options.concurrency.clamp(1, MAX_CHUNK_CONCURRENCY);
+    let chunk_range = |index: u64| (index * chunk_size, ((index + 1) * chunk_size).min(total) - 1);
+
+    let finished = finished_chunks(partial_path, total, chunk_size);
+    let pending: Vec<u64> = (0..total.div_ceil(chunk_size))
+        .filter(|index| !finished.contains(index))
+        .collect();
+    let remaining: u64 = pending
+        .iter()
+        .map(|&index| {
+            let (start, end) = chunk_range(index);
+            end - start + 1
+        })
+        .sum();
+
+    println!(
+        "[download_chunked] {} bytes in {} chunks ({} already on disk), {} at a time",
+        total,
+        pending.len() + finished.len(),
+        finished.len(),
+        concurrency
+    );
+
+    if let Some(dir) = partial_path.parent() {
+        ensure_disk_space(dir, remaining)?;
+    }
+
+    // List the finished chunks before pre-allocating, since from then on the
+    // partial file is no longer a contiguous prefix
+    let listed: String = finished.iter().map(|index| format!("{}\n", index)).collect();
+    std::fs::write(chunks_path(partial_path), listed).context("Failed to write chunks file")?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(partial_path)
+        .and_then(|file| file.set_len(total))
+        .context("Failed to allocate partial download file")?;
+
+    let progress = Mutex::new(total - remaining);
+    on_progress(total - remaining, total);
+    let on_progress = Mutex::new(on_progress);
+    let on_bytes = |bytes: u64| {
+        let mut downloaded = progress.lock().unwrap();
+        *downloaded += bytes;
+        (on_progress.lock().unwrap())(*downloaded, total);
+    };
+
+    let mut chunks = futures_util::stream::iter(pending)
+        .map(|index| {
+            let on_bytes = &on_bytes;
+            async move {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(DownloadError::Cancelled.into());
+                }
+                fetch_range_with_retry(client, url, partial_path, chunk_range(index), cancel, policy, on_bytes).await?;
+                mark_chunk_finished(partial_path, index)
+            }
+        })
+        .buffer_unordered(concurrency);
+
+    // The first failure drops the chunks still in flight
+    while let Some(result) = chunks.next().await {
+        if let Err(e) = result {
+            if matches!(e.downcast_ref::<DownloadError>(), Some(DownloadError::Cancelled)) {
+                println!("[download_chunked] Cancelled, removing {:?}", partial_path);
+                drop(chunks);
+                let _ = std::fs::remove_file(partial_path);
+                let _ = std::fs::remove_file(chunks_path(partial_path));
+            }
+            return Err(e);
+        }
+    }
+    drop(chunks);
+
+    let downloaded = *progress.lock().unwrap();
+    let file_len = std::fs::metadata(partial_path)
+        .map(|m| m.len())
+        .context("Failed to read partial download file")?;
+    if downloaded != total || file_len != total {
+        anyhow::bail!(
+            "Chunked download incomplete: {} of {} bytes received, file is {} bytes",
+            downloaded,
+            total,
+            file_len
+        );
+    }
+
+    // Complete, so the file is a valid "prefix" again and needs no chunk list
+    let _ = std::fs::remove_file(chunks_path(partial_path));
+    Ok(total)
+}
+
+/// Compute the lowercase hex SHA-256 digest of a file
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)
+        .context(format!("Failed to open {:?} for hashing", path))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).context("Failed to hash file")?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Response header in which Hugging Face reports the SHA-256 of a file stored with LFS
+const LINKED_ETAG_HEADER: &str = "x-linked-etag";
+
+/// SHA-256 digest from an `X-Linked-Etag` value (`"<64 hex chars>"`)
+/// Other etags (e.g. the git hash of a small non-LFS file) give None.
+fn parse_linked_etag(value: &str) -> Option<String> {
+    let digest = value.trim().trim_start_matches("W/").trim_matches('"');
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())).then(|| digest.to_lowercase())
+}
+
+/// SHA-256 the host publishes for the file at a Hugging Face `resolve` URL, if any
+///
+/// Read from the redirect response itself: the CDN it points to doesn't repeat the header.
+pub async fn published_sha256(url: &str) -> Result<Option<String>> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .context("Failed to create HTTP client")?;
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .context("Failed to fetch published checksum")?;
+
+    Ok(response
+        .headers()
+        .get(LINKED_ETAG_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_linked_etag))
+}
+
+/// Verify a completed partial download and move it to its final destination
+///
+/// If `expected_sha256` is provided and doesn't match, the partial file is deleted
+/// (so the next attempt starts fresh) and `DownloadError::ChecksumMismatch` is returned.
+pub fn finalize_download(
+    partial_path: &Path,
+    destination: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<()> {
+    if let Some(expected) = expected_sha256 {
+        let actual = sha256_file(partial_path)?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            let _ = std::fs::remove_file(partial_path);
+            return Err(DownloadError::ChecksumMismatch {
+                file: destination
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                expected: expected.to_lowercase(),
+                actual,
+            }
+            .into());
+        }
+        println!("[finalize_download] Checksum verified for {:?}", destination);
+    }
+
+    std::fs::rename(partial_path, destination)
+        .context("Failed to move downloaded file")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server that honours `Range: bytes=N-`.
+    /// With `interrupt_first`, the first full (non-range) request is cut off halfway
+    /// to simulate a dropped connection.
+    async fn spawn_mock_server(body: Vec<u8>, interrupt_first: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut interrupted = !interrupt_first;
+            loop {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+
+                let range_start = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|r| r.trim_end_matches('-').trim().parse::<usize>().ok());
+
+                match range_start {
+                    Some(start) => {
+                        let rest = &body[start..];
+                        let header = format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                            rest.len(),
+                            start,
+                            body.len() - 1,
+                            body.len()
+                        );
+                        let _ = socket.write_all(header.as_bytes()).await;
+                        let _ = socket.write_all(rest).await;
+                    }
+                    None => {
+                        let header = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        );
+                        let _ = socket.write_all(header.as_bytes()).await;
+                        if interrupted {
+                            let _ = socket.write_all(&body).await;
+                        } else {
+                            // Send half the body, then drop the connection
+                            interrupted = true;
+                            let _ = socket.write_all(&body[..body.len() / 2]).await;
+                            let _ = socket.flush().await;
+                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                        }
+                    }
+                }
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}/file.bin", addr)
+    }
+
+    #[test]
+    fn test_partial_path_appends_suffix() {
+        let path = PathBuf::from("/tmp/models/ggml-small.bin");
+        assert_eq!(partial_path(&path), PathBuf::from("/tmp/models/ggml-small.bin.partial"));
+    }
+
+    #[test]
+    fn test_check_disk_space() {
+        let gb = 1024 * 1024 * 1024;
+
+        // Plenty of room
+        assert!(check_disk_space(gb, 10 * gb).is_ok());
+
+        // Not enough room
+        match check_disk_space(3 * gb, 2 * gb) {
+            Err(DownloadError::InsufficientDiskSpace { required_bytes, available_bytes }) => {
+                assert_eq!(required_bytes, 3 * gb + DISK_SPACE_MARGIN_BYTES);
+                assert_eq!(available_bytes, 2 * gb);
+            }
+            other => panic!("expected InsufficientDiskSpace, got {:?}", other),
+        }
+
+        // File alone fits, but not with the safety margin
+        assert!(check_disk_space(gb, gb + DISK_SPACE_MARGIN_BYTES - 1).is_err());
+        assert!(check_disk_space(gb, gb + DISK_SPACE_MARGIN_BYTES).is_ok());
+    }
+
+    #[test]
+    fn test_finalize_download_verifies_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("lemmas.db");
+        let partial = partial_path(&destination);
+        std::fs::write(&partial, b"hello world").unwrap();
+
+        // sha256("hello world")
+        let good = "B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9";
+        finalize_download(&partial, &destination, Some(good)).unwrap();
+        assert!(destination.exists());
+        assert!(!partial.exists());
+    }
+
+    #[test]
+    fn test_finalize_download_rejects_wrong_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("ggml-tiny.bin");
+        let partial = partial_path(&destination);
+        std::fs::write(&partial, b"not really a model").unwrap();
+
+        let wrong = "0000000000000000000000000000000000000000000000000000000000000000";
+        let err = finalize_download(&partial, &destination, Some(wrong)).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<DownloadError>(),
+            Some(DownloadError::ChecksumMismatch { .. })
+        ));
+        assert!(!partial.exists(), "corrupt partial file should be removed");
+        assert!(!destination.exists());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_download_resumes_to_completion() {
+        let body: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+        let url = spawn_mock_server(body.clone(), true).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        // First attempt is cut off halfway
+        let first = download_resumable(&client, &url, &partial, &cancel, |_, _| {}).await;
+        assert!(first.is_err());
+        let partial_len = std::fs::metadata(&partial).unwrap().len();
+        assert!(partial_len > 0 && partial_len < body.len() as u64);
+
+        // Second attempt resumes with a Range request and reports progress from the baseline
+        let mut first_progress = None;
+        let downloaded = download_resumable(&client, &url, &partial, &cancel, |done, total| {
+            first_progress.get_or_insert((done, total));
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, body.len() as u64);
+        let (done, total) = first_progress.unwrap();
+        assert!(done > partial_len);
+        assert_eq!(total, body.len() as u64);
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_removes_partial_file() {
+        let body: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let url = spawn_mock_server(body, false).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("ggml-medium.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        // Cancel after a few chunks have been written
+        let mut chunks = 0;
+        let result = download_resumable(&client, &url, &partial, &cancel, |_, _| {
+            chunks += 1;
+            if chunks == 3 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        })
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err.downcast_ref::<DownloadError>(), Some(DownloadError::Cancelled)));
+        assert!(!partial.exists(), "partial file should be removed on cancel");
+    }
+
+    /// Server that always answers with `status` and counts requests
+    async fn spawn_status_server(status_line: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = vec![0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status_line);
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}/file.bin", addr), requests)
+    }
+
+    fn fast_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_dropped_connection() {
+        let body: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+        let url = spawn_mock_server(body.clone(), true).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        // First attempt is cut off halfway, the retry resumes and completes
+        let downloaded = download_resumable_with_retry(
+            &client,
+            &url,
+            &partial,
+            &cancel,
+            &fast_retry_policy(),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, body.len() as u64);
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_not_found() {
+        let (url, requests) = spawn_status_server("404 Not Found").await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        let err = download_resumable_with_retry(&client, &url, &partial, &cancel, &fast_retry_policy(), |_, _| {})
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<DownloadError>(),
+            Some(DownloadError::HttpStatus { status: 404, .. })
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let (url, requests) = spawn_status_server("503 Service Unavailable").await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        let result = download_resumable_with_retry(&client, &url, &partial, &cancel, &fast_retry_policy(), |_, _| {}).await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_used_when_primary_fails() {
+        let (primary, primary_requests) = spawn_status_server("500 Internal Server Error").await;
+        let body: Vec<u8> = (0..16 * 1024).map(|i| (i % 251) as u8).collect();
+        let mirror = spawn_mock_server(body.clone(), false).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        let (downloaded, used_url) = download_from_mirrors(
+            &client,
+            &[primary, mirror.clone()],
+            &partial,
+            &cancel,
+            &fast_retry_policy(),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(used_url, mirror);
+        assert_eq!(downloaded, body.len() as u64);
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 3);
+    }
+
+    /// HTTP server that answers `Range: bytes=A-B` with 206 and counts ranged requests
+    async fn spawn_range_server(body: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let body = Arc::new(body);
+        let ranged = Arc::new(AtomicUsize::new(0));
+        let counter = ranged.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let body = body.clone();
+                let counter = counter.clone();
+                // Serve connections concurrently so chunks really overlap
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+
+                    let range = request
+                        .lines()
+                        .find_map(|line| line.strip_prefix("range: bytes="))
+                        .and_then(|r| r.trim().split_once('-'))
+                        .and_then(|(a, b)| Some((a.parse::<usize>().ok()?, b.parse::<usize>().ok()?)));
+
+                    let (header, slice) = match range {
+                        Some((start, end)) => {
+                            counter.fetch_add(1, Ordering::SeqCst);
+                            let end = end.min(body.len() - 1);
+                            let header = format!(
+                                "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                                end - start + 1,
+                                start,
+                                end,
+                                body.len()
+                            );
+                            (header, &body[start..=end])
+                        }
+                        None => (
+                            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len()),
+                            &body[..],
+                        ),
+                    };
+                    let _ = socket.write_all(header.as_bytes()).await;
+                    let _ = socket.write_all(slice).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        (format!("http://{}/file.bin", addr), ranged)
+    }
+
+    #[tokio::test]
+    async fn test_chunked_download_reassembles_file() {
+        let body: Vec<u8> = (0..100_000).map(|i| (i * 7 % 251) as u8).collect();
+        let (url, ranged) = spawn_range_server(body.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("ggml-large-v3.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+        let options = ChunkedDownload { concurrency: 4, chunk_size: 16 * 1024 };
+
+        let mut last_progress = (0, 0);
+        let downloaded = download_chunked(&client, &url, &partial, &options, &cancel, &fast_retry_policy(), |done, total| {
+            assert!(done >= last_progress.0);
+            last_progress = (done, total);
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, body.len() as u64);
+        assert_eq!(last_progress, (body.len() as u64, body.len() as u64));
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+        assert!(!chunks_path(&partial).exists());
+        // The range probe plus 7 chunks (6 full, 1 short)
+        assert_eq!(ranged.load(Ordering::SeqCst), 8);
+
+        // Resuming from a single-stream partial file only fetches the chunks it doesn't cover
+        std::fs::write(&partial, &body[..40_000]).unwrap();
+        ranged.store(0, Ordering::SeqCst);
+        download_chunked(&client, &url, &partial, &options, &cancel, &fast_retry_policy(), |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+        assert_eq!(ranged.load(Ordering::SeqCst), 1 + 5);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_download_resumes_listed_chunks() {
+        let body: Vec<u8> = (0..50_000).map(|i| (i % 241) as u8).collect();
+        let (url, ranged) = spawn_range_server(body.clone()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let options = ChunkedDownload { concurrency: 2, chunk_size: 10_000 };
+
+        // An earlier chunked run finished chunks 1 and 3; the rest of the file is zeros
+        let mut previous = vec![0u8; body.len()];
+        previous[10_000..20_000].copy_from_slice(&body[10_000..20_000]);
+        previous[30_000..40_000].copy_from_slice(&body[30_000..40_000]);
+        std::fs::write(&partial, previous).unwrap();
+        std::fs::write(chunks_path(&partial), "1\n3\n").unwrap();
+
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+        download_chunked(&client, &url, &partial, &options, &cancel, &fast_retry_policy(), |_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+        assert_eq!(ranged.load(Ordering::SeqCst), 1 + 3);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_download_falls_back_without_range_support() {
+        // This server only understands open-ended ranges, so the 0-0 probe gets a plain 200
+        let body: Vec<u8> = (0..32 * 1024).map(|i| (i % 251) as u8).collect();
+        let url = spawn_mock_server(body.clone(), false).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = partial_path(&dir.path().join("file.bin"));
+        let client = reqwest::Client::new();
+        let cancel = AtomicBool::new(false);
+
+        let downloaded = download_chunked(
+            &client,
+            &url,
+            &partial,
+            &ChunkedDownload { concurrency: 4, chunk_size: 4096 },
+            &cancel,
+            &fast_retry_policy(),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, body.len() as u64);
+        assert_eq!(std::fs::read(&partial).unwrap(), body);
+    }
+
+    #[test]
+    fn test_transfer_rate_speed_and_eta() {
+        // 10 MB in 4 s = 2.5 MB/s, 15 MB left = 6 s
+        let rate = transfer_rate(10_000_000, Duration::from_secs(4), Some(15_000_000));
+        assert!((rate.speed_mbps - 2.5).abs() < 1e-6);
+        assert_eq!(rate.eta_seconds, Some(6));
+
+        // Partial seconds round up
+        let rate = transfer_rate(1_000_000, Duration::from_secs(1), Some(1_500_000));
+        assert_eq!(rate.eta_seconds, Some(2));
+    }
+
+    #[test]
+    fn test_transfer_rate_unknown() {
+        // Nothing fetched yet: no speed, no ETA
+        let rate = transfer_rate(0, Duration::ZERO, Some(1_000));
+        assert_eq!(rate, TransferRate { speed_mbps: 0.0, eta_seconds: None });
+
+        // Unknown total size: speed but no ETA
+        let rate = transfer_rate(2_000_000, Duration::from_secs(2), None);
+        assert!((rate.speed_mbps - 1.0).abs() < 1e-6);
+        assert_eq!(rate.eta_seconds, None);
+    }
+
+    #[test]
+    fn test_progress_meter_throttles() {
+        let mut meter = ProgressMeter::new();
+
+        assert!(meter.tick(100, 1_000).is_some());
+        // Within the interval: suppressed
+        assert!(meter.tick(200, 1_000).is_none());
+        // Completion is always reported
+        assert!(meter.tick(1_000, 1_000).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_published_sha256_from_redirect() {
+        let digest = "BE6E5C6B0F1A2D3C4B5A69788796A5B4C3D2E1F0A9B8C7D6E5F4A3B2C1D0E9F8";
+        assert_eq!(parse_linked_etag(&format!("\"{}\"", digest)), Some(digest.to_lowercase()));
+        assert_eq!(parse_linked_etag(&format!("W/\"{}\"", digest)), Some(digest.to_lowercase()));
+        // Git blob hash of a non-LFS file
+        assert_eq!(parse_linked_etag("\"bd577a113a864445d4c299885e0cb97d4ba92b5f\""), None);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:1/cdn\r\nX-Linked-Etag: \"{}\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                digest
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+
+        let published = published_sha256(&format!("http://{}/model.bin", addr)).await.unwrap();
+        assert_eq!(published, Some(digest.to_lowercase()));
+    }
+}
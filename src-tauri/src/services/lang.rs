@@ -0,0 +1,85 @@
+/**
+ * Language code normalization
+ *
+ * Maps the variants users and the frontend pass around ("ES", "es-ES", "spanish",
+ * "español") to the canonical two-letter codes lemma databases and vocab use,
+ * so a language never ends up split across several vocab namespaces.
+ */
+
+use anyhow::Result;
+
+/// Canonical code, then every other spelling accepted for it (all lowercase)
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("en", &["eng", "english", "inglés", "ingles"]),
+    ("es", &["spa", "spanish", "español", "espanol", "castellano"]),
+    ("fr", &["fra", "fre", "french", "français", "francais"]),
+    ("de", &["deu", "ger", "german", "deutsch"]),
+    ("it", &["ita", "italian", "italiano"]),
+    ("pt", &["por", "portuguese", "português", "portugues"]),
+    ("nl", &["nld", "dut", "dutch", "nederlands"]),
+    ("ru", &["rus", "russian", "русский"]),
+    ("zh", &["zho", "chi", "chinese", "mandarin", "中文"]),
+    ("ja", &["jpn", "japanese", "日本語"]),
+    ("ko", &["kor", "korean", "한국어"]),
+    ("ar", &["ara", "arabic", "العربية"]),
+];
+
+/// Canonical two-letter code for a language code, locale or name
+/// Region and script subtags are dropped ("pt-BR", "es_MX" -> "pt", "es").
+/// Returns None for languages the app doesn't know.
+pub fn normalize_lang_code(input: &str) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    let primary = input.split(['-', '_']).next().unwrap_or_default();
+
+    LANGUAGES
+        .iter()
+        .find(|(code, aliases)| *code == primary || aliases.contains(&primary) || aliases.contains(&input.as_str()))
+        .map(|(code, _)| code.to_string())
+}
+
+/// Like `normalize_lang_code`, but unknown codes are an error
+pub fn require_lang_code(input: &str) -> Result<String> {
+    normalize_lang_code(input).ok_or_else(|| {
+        let known: Vec<&str> = LANGUAGES.iter().map(|(code, _)| *code).collect();
+        anyhow::anyhow!("Unknown language code: '{}'. Expected one of: {}", input, known.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_lang_code_variants() {
+        for input in ["es", "ES", " es ", "es-ES", "es_MX", "spa", "Spanish", "español"] {
+            assert_eq!(normalize_lang_code(input).as_deref(), Some("es"), "{}", input);
+        }
+        assert_eq!(normalize_lang_code("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(normalize_lang_code("zh-Hans-CN").as_deref(), Some("zh"));
+        assert_eq!(normalize_lang_code("Deutsch").as_deref(), Some("de"));
+        assert_eq!(normalize_lang_code("русский").as_deref(), Some("ru"));
+    }
+
+    #[test]
+    fn test_unknown_lang_codes_are_rejected() {
+        assert_eq!(normalize_lang_code(""), None);
+        assert_eq!(normalize_lang_code("xx"), None);
+        assert_eq!(normalize_lang_code("klingon"), None);
+
+        let err = require_lang_code("xx").unwrap_err().to_string();
+        assert!(err.contains("'xx'"));
+        assert!(err.contains("es"));
+    }
+
+    #[test]
+    fn test_mapping_table_has_unique_spellings() {
+        let mut seen = std::collections::HashSet::new();
+        for (code, aliases) in LANGUAGES {
+            assert_eq!(code.len(), 2);
+            for spelling in std::iter::once(code).chain(aliases.iter()) {
+                assert!(seen.insert(*spelling), "{} is mapped twice", spelling);
+                assert_eq!(spelling.to_lowercase(), *spelling);
+            }
+        }
+    }
+}
@@ -3,14 +3,25 @@
  *
  * Handles downloading lemma and translation databases on-demand.
  * Supports parallel downloads with progress tracking.
+ * Verifies installed databases so truncated downloads can be detected and replaced.
+ * Lists installed packs with their on-disk size for pack management.
+ * Emits `langpack_updated` after a lemma database is replaced, optionally re-lemmatizing
+ * the language's vocabulary first.
  */
 
 use anyhow::{Context, Result};
-use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
+use super::download;
+use crate::db::langpack::get_lemma_db_path;
+use crate::db::user::{get_setting, open_user_db, AUTO_RELEMMATIZE_SETTING};
+
 /// Lock file guard - automatically deletes lock file when dropped
 struct LockFileGuard {
     path: PathBuf,
@@ -33,6 +44,20 @@ pub struct DownloadProgress {
     pub total_bytes: u64,
     pub percentage: f32,
     pub speed_mbps: f32,
+    pub eta_seconds: Option<u64>,
+}
+
+/// Event emitted after a language's lemma database was downloaded
+pub const LANGPACK_UPDATED_EVENT: &str = "langpack_updated";
+
+/// Payload for the `langpack_updated` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LangpackUpdated {
+    pub language: String,
+    /// Vocabulary entries fixed by the automatic re-lemmatization
+    /// None if it's turned off (or failed); the frontend can then offer `fix_vocab_lemmas`
+    pub relemmatized: Option<i32>,
 }
 
 /// Information about a language pack
@@ -101,44 +126,101 @@ pub fn is_translation_installed(from_lang: &str, to_lang: &str, app: &AppHandle)
 
 /// Get list of installed language codes
 pub fn get_installed_languages(app: &AppHandle) -> Result<Vec<String>> {
+    Ok(get_installed_language_packs(app)?
+        .into_iter()
+        .map(|pack| pack.code)
+        .collect())
+}
+
+/// An installed lemma pack as found on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledPackInfo {
+    pub code: String,
+    pub size_bytes: u64,
+    /// Path of the pack's lemmas.db
+    pub path: String,
+    /// Shipped with the app (English) rather than downloaded; can't be deleted
+    pub bundled: bool,
+    /// At least one translation DB from this language ({code}-*.db) is installed
+    pub has_translations: bool,
+}
+
+/// Get installed language packs with their size and location, sorted by code
+pub fn get_installed_language_packs(app: &AppHandle) -> Result<Vec<InstalledPackInfo>> {
+    let bundled_en = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join("langpacks").join("en").join("lemmas.db"));
+
+    Ok(scan_installed_packs(&get_langpacks_dir(app)?, bundled_en.as_deref()))
+}
+
+/// Find lemma packs in `langpacks_dir` (plus the bundled English pack, if present)
+fn scan_installed_packs(langpacks_dir: &Path, bundled_en: Option<&Path>) -> Vec<InstalledPackInfo> {
+    let translations_dir = langpacks_dir.join("translations");
+    let pack_info = |code: &str, path: &Path, bundled: bool| InstalledPackInfo {
+        code: code.to_string(),
+        size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        path: path.to_string_lossy().to_string(),
+        bundled,
+        has_translations: has_translations_from(&translations_dir, code),
+    };
+
     let mut installed = Vec::new();
 
-    // Check if English is installed (bundled or downloaded)
-    if is_lemmas_installed("en", app)? {
-        installed.push("en".to_string());
+    // The bundled English pack takes priority over a downloaded one (see get_lemma_db_path)
+    if let Some(path) = bundled_en.filter(|path| path.exists()) {
+        installed.push(pack_info("en", path, true));
     }
 
-    let langpacks_dir = get_langpacks_dir(app)?;
-
     // Check for downloaded language directories
-    if let Ok(entries) = std::fs::read_dir(&langpacks_dir) {
+    if let Ok(entries) = std::fs::read_dir(langpacks_dir) {
         for entry in entries.flatten() {
             if entry.path().is_dir() {
                 if let Some(dir_name) = entry.file_name().to_str() {
                     // Check if lemmas.db exists in this directory
                     let lemmas_path = entry.path().join("lemmas.db");
-                    if lemmas_path.exists() && dir_name != "translations" {
-                        installed.push(dir_name.to_string());
+                    let already_listed = installed.iter().any(|pack| pack.code == dir_name);
+                    if lemmas_path.exists() && dir_name != "translations" && !already_listed {
+                        installed.push(pack_info(dir_name, &lemmas_path, false));
                     }
                 }
             }
         }
     }
 
-    installed.sort();
-    installed.dedup();
-    Ok(installed)
+    installed.sort_by(|a, b| a.code.cmp(&b.code));
+    installed
+}
+
+/// Whether `translations_dir` holds any {from}-*.db
+fn has_translations_from(translations_dir: &Path, from: &str) -> bool {
+    let prefix = format!("{}-", from);
+    std::fs::read_dir(translations_dir)
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.starts_with(&prefix) && name.ends_with(".db")
+            })
+        })
+        .unwrap_or(false)
 }
 
 /// Download a file with progress tracking
+/// `urls` holds the primary URL followed by any mirrors, tried in order
+/// Returns false if another download of the same file was already in progress.
 async fn download_file_with_progress(
-    url: &str,
+    urls: &[String],
     destination: PathBuf,
     file_type: &str,
     language_pair: &str,
+    expected_sha256: Option<&str>,
+    cancel: Arc<AtomicBool>,
     app: AppHandle,
-) -> Result<()> {
-    println!("[download_file] Starting download: {} -> {:?}", url, destination);
+) -> Result<bool> {
+    println!("[download_file] Starting download: {:?} -> {:?}", urls, destination);
 
     // Create parent directory
     if let Some(parent) = destination.parent() {
@@ -151,7 +233,7 @@ async fn download_file_with_progress(
     if lock_file.exists() {
         println!("[download_file] Download already in progress for {}, skipping", language_pair);
         // Not an error - just means another download is in progress
-        return Ok(());
+        return Ok(false);
     }
     std::fs::File::create(&lock_file)
         .context("Failed to create lock file")?;
@@ -161,46 +243,14 @@ async fn download_file_with_progress(
         path: lock_file.clone(),
     };
 
-    // Start download
+    // Download into a .partial file (resumes from a previous attempt if one exists)
     let client = reqwest::Client::new();
-    let response = client.get(url)
-        .send()
-        .await
-        .context("Failed to start download")?;
-
-    // Check if response is successful (2xx status code)
-    if !response.status().is_success() {
-        anyhow::bail!("Download failed with HTTP status: {} for URL: {}", response.status(), url);
-    }
-
-    let total_size = response.content_length().unwrap_or(0);
-    println!("[download_file] Total size: {} bytes", total_size);
-
-    // Download with progress tracking
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
-    let mut file = std::fs::File::create(&destination)
-        .context("Failed to create destination file")?;
-
-    use std::io::Write;
-    let start_time = std::time::Instant::now();
-    let mut last_progress_emit = std::time::Instant::now();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Failed to read chunk")?;
-        file.write_all(&chunk).context("Failed to write chunk")?;
-
-        downloaded += chunk.len() as u64;
+    let partial = download::partial_path(&destination);
+    let mut meter = download::ProgressMeter::new();
 
+    let (_, used_url) = download::download_from_mirrors(&client, urls, &partial, &cancel, &download::RetryPolicy::default(), |downloaded, total_size| {
         // Emit progress every 500ms
-        if last_progress_emit.elapsed().as_millis() > 500 || downloaded == total_size {
-            let elapsed_secs = start_time.elapsed().as_secs_f32();
-            let speed_mbps = if elapsed_secs > 0.0 {
-                (downloaded as f32 / 1_000_000.0) / elapsed_secs
-            } else {
-                0.0
-            };
-
+        if let Some(rate) = meter.tick(downloaded, total_size) {
             let percentage = if total_size > 0 {
                 (downloaded as f32 / total_size as f32) * 100.0
             } else {
@@ -213,49 +263,127 @@ async fn download_file_with_progress(
                 downloaded_bytes: downloaded,
                 total_bytes: total_size,
                 percentage,
-                speed_mbps,
+                speed_mbps: rate.speed_mbps,
+                eta_seconds: rate.eta_seconds,
             };
 
             // Emit progress event
             let _ = app.emit("download_progress", &progress);
-
-            last_progress_emit = std::time::Instant::now();
         }
-    }
+    })
+    .await?;
 
-    file.sync_all().context("Failed to sync file")?;
-    println!("[download_file] Download complete: {:?}", destination);
+    // Only move into place once the whole file has arrived and matches its checksum
+    download::finalize_download(&partial, &destination, expected_sha256)?;
+    println!("[download_file] Download complete: {:?} (from {})", destination, used_url);
 
-    Ok(())
+    Ok(true)
+}
+
+/// Primary URL followed by its mirrors
+fn with_mirrors(url: &str, mirrors: &[String]) -> Vec<String> {
+    std::iter::once(url.to_string())
+        .chain(mirrors.iter().cloned())
+        .collect()
 }
 
 /// Download lemma database for a language
+/// Falls back to `mirrors` in order if the primary URL fails
+///
+/// Once the new database is in place, the language's vocabulary is re-lemmatized if
+/// the auto re-lemmatize setting is on, then `langpack_updated` is emitted.
 pub async fn download_lemmas(
     lang: &str,
     url: &str,
+    mirrors: &[String],
+    sha256: Option<&str>,
+    cancel: Arc<AtomicBool>,
     app: AppHandle,
 ) -> Result<()> {
     println!("[download_lemmas] Downloading {} lemmas from {}", lang, url);
 
     let langpacks_dir = get_langpacks_dir(&app)?;
     let destination = langpacks_dir.join(lang).join("lemmas.db");
+    let urls = with_mirrors(url, mirrors);
 
-    download_file_with_progress(
-        url,
+    let download = download_file_with_progress(
+        &urls,
         destination,
         "lemmas",
         lang,
-        app,
-    ).await?;
+        sha256,
+        cancel,
+        app.clone(),
+    );
+
+    let pool = match open_user_db(&app).await {
+        Ok(pool) => Some(pool),
+        Err(e) => {
+            println!("[download_lemmas] Can't open user database, skipping re-lemmatization: {}", e);
+            None
+        }
+    };
+    let auto_relemmatize = match &pool {
+        Some(pool) => get_setting(pool, AUTO_RELEMMATIZE_SETTING).await.ok().flatten().as_deref() == Some("true"),
+        None => false,
+    };
+    let relemmatize = pool
+        .filter(|_| auto_relemmatize)
+        .map(|pool| {
+            let app = app.clone();
+            move || async move { crate::services::vocabulary::fix_vocab_lemmas(&pool, lang, &app).await }
+        });
+
+    download_lemmas_with(lang, download, relemmatize, |event| {
+        let _ = app.emit(LANGPACK_UPDATED_EVENT, event);
+    })
+    .await
+}
+
+/// `download_lemmas` with the download, re-lemmatization and event emission passed in
+///
+/// `download` resolves to whether the database was replaced; nothing happens if it wasn't.
+/// `relemmatize` is only given when automatic re-lemmatization is on. Its failure is
+/// logged rather than returned, since the download itself succeeded.
+pub async fn download_lemmas_with<D, R, RFut>(
+    lang: &str,
+    download: D,
+    relemmatize: Option<R>,
+    emit: impl FnOnce(LangpackUpdated),
+) -> Result<()>
+where
+    D: Future<Output = Result<bool>>,
+    R: FnOnce() -> RFut,
+    RFut: Future<Output = Result<i32>>,
+{
+    if !download.await? {
+        return Ok(());
+    }
 
+    let relemmatized = match relemmatize {
+        Some(relemmatize) => match relemmatize().await {
+            Ok(fixed) => Some(fixed),
+            Err(e) => {
+                println!("[download_lemmas] Re-lemmatizing {} vocabulary failed: {}", lang, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    emit(LangpackUpdated { language: lang.to_string(), relemmatized });
     Ok(())
 }
 
 /// Download translation database
+/// Falls back to `mirrors` in order if the primary URL fails
 pub async fn download_translation(
     from_lang: &str,
     to_lang: &str,
     url: &str,
+    mirrors: &[String],
+    sha256: Option<&str>,
+    cancel: Arc<AtomicBool>,
     app: AppHandle,
 ) -> Result<()> {
     let pair = format!("{}-{}", from_lang, to_lang);
@@ -266,10 +394,12 @@ pub async fn download_translation(
     let destination = translations_dir.join(format!("{}.db", pair));
 
     download_file_with_progress(
-        url,
+        &with_mirrors(url, mirrors),
         destination,
         "translations",
         &pair,
+        sha256,
+        cancel,
         app,
     ).await?;
 
@@ -294,6 +424,130 @@ pub fn delete_language_pack(lang: &str, app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Delete a downloaded translation database (e.g. to re-download a corrupted one)
+pub fn delete_translation_pack(from_lang: &str, to_lang: &str, app: &AppHandle) -> Result<()> {
+    let path = get_langpacks_dir(app)?
+        .join("translations")
+        .join(format!("{}-{}.db", from_lang, to_lang));
+
+    if path.exists() {
+        std::fs::remove_file(&path).context("Failed to delete translation pack")?;
+    }
+
+    Ok(())
+}
+
+/// Health of one language pack database file
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackHealth {
+    pub path: String,
+    pub healthy: bool,
+    /// Rows in the pack's main table (0 if it couldn't be read)
+    pub entries: i64,
+    /// Why the pack is unhealthy
+    pub error: Option<String>,
+}
+
+/// Health of a language's lemma database and the translation databases involving it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguagePackHealth {
+    pub language: String,
+    /// None if no lemma database is installed
+    pub lemmas: Option<PackHealth>,
+    pub translations: Vec<PackHealth>,
+}
+
+impl LanguagePackHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.lemmas.iter().chain(&self.translations).all(|pack| pack.healthy)
+    }
+}
+
+/// Open a pack read-only and check it: SQLite's integrity check, then a non-empty `table`
+async fn check_pack_file(path: &Path, table: &str) -> Result<i64> {
+    let pool = SqlitePool::connect(&format!("sqlite://{}?mode=ro", path.display()))
+        .await
+        .context("Failed to open database")?;
+
+    let result = async {
+        let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_one(&pool)
+            .await
+            .context("Integrity check failed")?;
+        if integrity != "ok" {
+            anyhow::bail!("Integrity check failed: {}", integrity);
+        }
+
+        let entries: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(&pool)
+            .await
+            .context(format!("Failed to read {} table", table))?;
+        if entries == 0 {
+            anyhow::bail!("The {} table is empty", table);
+        }
+
+        Ok(entries)
+    }
+    .await;
+
+    pool.close().await;
+    result
+}
+
+/// Verify a pack database file whose rows live in `table` ("lemmas" or "translations")
+pub async fn verify_pack_file(path: &Path, table: &str) -> PackHealth {
+    let (healthy, entries, error) = match check_pack_file(path, table).await {
+        Ok(entries) => (true, entries, None),
+        Err(e) => (false, 0, Some(format!("{:#}", e))),
+    };
+
+    PackHealth {
+        path: path.to_string_lossy().to_string(),
+        healthy,
+        entries,
+        error,
+    }
+}
+
+/// Verify a language's installed lemma database and every translation database
+/// to or from that language
+pub async fn verify_language_pack(lang: &str, app: &AppHandle) -> Result<LanguagePackHealth> {
+    let lemmas = match get_lemma_db_path(lang, app) {
+        Ok(path) => Some(verify_pack_file(&path, "lemmas").await),
+        Err(_) => None,
+    };
+
+    let mut translations = Vec::new();
+    let translations_dir = get_langpacks_dir(app)?.join("translations");
+    if let Ok(entries) = std::fs::read_dir(&translations_dir) {
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let pair = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                let involves_lang = pair.split('-').any(|code| code == lang);
+                path.extension().is_some_and(|ext| ext == "db") && involves_lang
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            translations.push(verify_pack_file(&path, "translations").await);
+        }
+    }
+
+    let health = LanguagePackHealth {
+        language: lang.to_string(),
+        lemmas,
+        translations,
+    };
+    println!("[verify_language_pack] {}: healthy = {}", lang, health.is_healthy());
+
+    Ok(health)
+}
+
 /// Get required packs for a language pair
 #[derive(Debug, Clone, Serialize)]
 pub struct RequiredPacks {
@@ -325,3 +579,132 @@ pub fn get_required_packs(
         translations: Vec::new() // Always empty now
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_installed_packs() {
+        let dir = tempfile::tempdir().unwrap();
+        let langpacks = dir.path().join("langpacks");
+        for (lang, size) in [("es", 300), ("fr", 120), ("en", 10)] {
+            std::fs::create_dir_all(langpacks.join(lang)).unwrap();
+            std::fs::write(langpacks.join(lang).join("lemmas.db"), vec![0u8; size]).unwrap();
+        }
+        // A leftover directory without a database isn't a pack
+        std::fs::create_dir_all(langpacks.join("de")).unwrap();
+        std::fs::create_dir_all(langpacks.join("translations")).unwrap();
+        std::fs::write(langpacks.join("translations").join("es-en.db"), b"x").unwrap();
+
+        let packs = scan_installed_packs(&langpacks, None);
+        let codes: Vec<&str> = packs.iter().map(|p| p.code.as_str()).collect();
+        assert_eq!(codes, vec!["en", "es", "fr"]);
+
+        let es = &packs[1];
+        assert_eq!(es.size_bytes, 300);
+        assert!(es.path.ends_with("lemmas.db"));
+        assert!(!es.bundled);
+        assert!(es.has_translations);
+        assert!(!packs[2].has_translations);
+
+        // The bundled English pack replaces the downloaded one
+        let resources = dir.path().join("resources");
+        std::fs::create_dir_all(&resources).unwrap();
+        let bundled = resources.join("lemmas.db");
+        std::fs::write(&bundled, vec![0u8; 50]).unwrap();
+
+        let packs = scan_installed_packs(&langpacks, Some(&bundled));
+        assert_eq!(packs.len(), 3);
+        assert!(packs[0].bundled);
+        assert_eq!(packs[0].size_bytes, 50);
+    }
+
+    #[tokio::test]
+    async fn test_lemma_download_emits_langpack_updated() {
+        type Relemmatize = fn() -> std::future::Ready<Result<i32>>;
+
+        // Auto re-lemmatization off: the frontend is told which language changed
+        let mut events = Vec::new();
+        download_lemmas_with("es", async { Ok(true) }, None::<Relemmatize>, |event| events.push(event))
+            .await
+            .unwrap();
+        assert_eq!(events, vec![LangpackUpdated { language: "es".to_string(), relemmatized: None }]);
+
+        // Auto re-lemmatization on: it runs before the event, which carries the fixed count
+        let mut events = Vec::new();
+        download_lemmas_with("fr", async { Ok(true) }, Some(|| async { Ok(7) }), |event| events.push(event))
+            .await
+            .unwrap();
+        assert_eq!(events, vec![LangpackUpdated { language: "fr".to_string(), relemmatized: Some(7) }]);
+
+        // A failed re-lemmatization doesn't fail the download
+        let mut events = Vec::new();
+        download_lemmas_with(
+            "de",
+            async { Ok(true) },
+            Some(|| async { Err(anyhow::anyhow!("lemma db locked")) }),
+            |event| events.push(event),
+        )
+        .await
+        .unwrap();
+        assert_eq!(events[0].relemmatized, None);
+
+        // Nothing is emitted when the download fails or was already running elsewhere
+        let mut events = Vec::new();
+        let result = download_lemmas_with(
+            "it",
+            async { Err(anyhow::anyhow!("network down")) },
+            None::<Relemmatize>,
+            |event| events.push(event),
+        )
+        .await;
+        assert!(result.is_err());
+        download_lemmas_with("it", async { Ok(false) }, None::<Relemmatize>, |event| events.push(event))
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_verify_pack_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Valid pack
+        let valid = dir.path().join("lemmas.db");
+        let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", valid.display()))
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE lemmas (word TEXT PRIMARY KEY, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO lemmas VALUES ('estoy', 'estar'), ('corriendo', 'correr')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let health = verify_pack_file(&valid, "lemmas").await;
+        assert!(health.healthy, "{:?}", health.error);
+        assert_eq!(health.entries, 2);
+
+        // Wrong table: a lemma file passed off as a translation pack
+        let health = verify_pack_file(&valid, "translations").await;
+        assert!(!health.healthy);
+
+        // Truncated download
+        let bytes = std::fs::read(&valid).unwrap();
+        let truncated = dir.path().join("truncated.db");
+        std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(!verify_pack_file(&truncated, "lemmas").await.healthy);
+
+        // Not a database at all (e.g. an HTML error page)
+        let malformed = dir.path().join("malformed.db");
+        std::fs::write(&malformed, b"<html>502 Bad Gateway</html>").unwrap();
+        let health = verify_pack_file(&malformed, "lemmas").await;
+        assert!(!health.healthy);
+        assert_eq!(health.entries, 0);
+        assert!(health.error.is_some());
+    }
+}
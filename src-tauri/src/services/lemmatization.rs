@@ -1,5 +1,6 @@
 use anyhow::Result;
-use sqlx::Row;
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeSet, HashMap};
 use tauri::AppHandle;
 
 use crate::db::langpack;
@@ -22,26 +23,203 @@ use crate::db::langpack;
 /// ```
 pub async fn get_lemma(word: &str, lang: &str, app: &AppHandle) -> Result<Option<String>> {
     let pool = langpack::open_lemma_db(lang, app).await?;
+    lookup_lemma(&pool, word, lang, false).await
+}
+
+/// Like `get_lemma`, but with `stem_fallback` a word missing from the lemma DB is
+/// run through a rule-based stemmer and resolved via the stem if the DB knows it
+pub async fn get_lemma_with_fallback(
+    word: &str,
+    lang: &str,
+    app: &AppHandle,
+    stem_fallback: bool,
+) -> Result<Option<String>> {
+    let pool = langpack::open_lemma_db(lang, app).await?;
+    lookup_lemma(&pool, word, lang, stem_fallback).await
+}
 
+/// Look up a word in an open lemma database
+/// Exact matches always win; stem candidates are only tried when the exact lookup misses
+pub async fn lookup_lemma(pool: &SqlitePool, word: &str, lang: &str, stem_fallback: bool) -> Result<Option<String>> {
     let word_lower = word.to_lowercase();
 
     let result = sqlx::query("SELECT lemma FROM lemmas WHERE word = ?")
         .bind(&word_lower)
-        .fetch_optional(&pool)
+        .fetch_optional(pool)
         .await?;
 
-    match result {
-        Some(row) => {
+    if let Some(row) = result {
+        let lemma: String = row.try_get("lemma")?;
+        return Ok(Some(lemma));
+    }
+
+    if !stem_fallback {
+        return Ok(None);
+    }
+
+    // A candidate counts if it's a known inflected form or itself a known lemma
+    // (base forms usually aren't stored as words). Candidates are tried in rule order.
+    for candidate in stem_candidates(&word_lower, lang) {
+        let row = sqlx::query(
+            r#"
+            SELECT lemma FROM lemmas WHERE word = ?1
+            UNION ALL
+            SELECT lemma FROM lemmas WHERE lemma = ?1
+            LIMIT 1
+            "#,
+        )
+        .bind(&candidate)
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(row) = row {
             let lemma: String = row.try_get("lemma")?;
-            Ok(Some(lemma))
+            println!("[lookup_lemma] Resolved '{}' via stem '{}' -> '{}'", word_lower, candidate, lemma);
+            return Ok(Some(lemma));
         }
-        None => Ok(None),
     }
+
+    Ok(None)
 }
 
+/// Suffix rules as (suffix, replacement), most specific first
+fn suffix_rules(lang: &str) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        "en" => &[
+            ("ies", "y"),
+            ("ied", "y"),
+            ("ing", ""),
+            ("ing", "e"),
+            ("ed", ""),
+            ("ed", "e"),
+            ("es", ""),
+            ("s", ""),
+            ("est", ""),
+            ("er", ""),
+            ("ly", ""),
+        ],
+        "es" => &[
+            ("ándo", "ar"),
+            ("iéndo", "er"),
+            ("iéndo", "ir"),
+            ("ando", "ar"),
+            ("iendo", "er"),
+            ("iendo", "ir"),
+            ("yendo", "er"),
+            ("adas", "ar"),
+            ("ados", "ar"),
+            ("ada", "ar"),
+            ("ado", "ar"),
+            ("idas", "er"),
+            ("idas", "ir"),
+            ("idos", "er"),
+            ("idos", "ir"),
+            ("ida", "er"),
+            ("ida", "ir"),
+            ("ido", "er"),
+            ("ido", "ir"),
+            ("itas", "as"),
+            ("itos", "os"),
+            ("ita", "a"),
+            ("ito", "o"),
+            ("ces", "z"),
+            ("es", ""),
+            ("s", ""),
+        ],
+        "fr" => &[
+            ("ées", "er"),
+            ("és", "er"),
+            ("ée", "er"),
+            ("é", "er"),
+            ("aux", "al"),
+            ("x", ""),
+            ("es", ""),
+            ("e", ""),
+            ("s", ""),
+        ],
+        _ => &[],
+    }
+}
+
+/// Spanish pronouns that attach to infinitives, gerunds and imperatives ("dímelo")
+const SPANISH_ENCLITICS: &[&str] = &["nos", "los", "las", "les", "lo", "la", "le", "me", "te", "se"];
+
+/// Remove acute accents (Spanish stress marks move when pronouns are attached)
+fn strip_accents(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            'á' => 'a',
+            'é' => 'e',
+            'í' => 'i',
+            'ó' => 'o',
+            'ú' => 'u',
+            other => other,
+        })
+        .collect()
+}
+
+/// Possible base forms of a word for a language, in the order they should be tried
+///
+/// Lightweight suffix stripping rather than a full stemmer: the lemma DB decides which
+/// candidate (if any) is a real word.
+pub(crate) fn stem_candidates(word: &str, lang: &str) -> Vec<String> {
+    let mut stems = vec![word.to_string()];
+
+    // Peel up to two enclitic pronouns: "dándoselo" -> "dándose" -> "dando"
+    if lang == "es" {
+        let mut current = word.to_string();
+        for _ in 0..2 {
+            match SPANISH_ENCLITICS
+                .iter()
+                .find(|p| current.len() > p.len() + 3 && current.ends_with(*p))
+            {
+                Some(pronoun) => {
+                    current = strip_accents(&current[..current.len() - pronoun.len()]);
+                    stems.push(current.clone());
+                }
+                None => break,
+            }
+        }
+    }
+
+    let mut candidates: Vec<String> = Vec::new();
+    let mut push = |candidate: String| {
+        if candidate.chars().count() >= 2 && candidate != word && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    };
+
+    for stem in &stems {
+        // A stripped pronoun alone may already leave the base form ("hacerlo" -> "hacer")
+        push(stem.clone());
+
+        for (suffix, replacement) in suffix_rules(lang) {
+            if let Some(base) = stem.strip_suffix(suffix) {
+                push(format!("{}{}", base, replacement));
+
+                // English doubles the final consonant: "running" -> "runn" -> "run"
+                let mut chars = base.chars().rev();
+                if lang == "en" && replacement.is_empty() {
+                    if let (Some(last), Some(prev)) = (chars.next(), chars.next()) {
+                        if last == prev && !"aeiou".contains(last) {
+                            push(base[..base.len() - last.len_utf8()].to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Most words looked up per query (stays under SQLite's bound-parameter limit)
+const LEMMA_BATCH_SIZE: usize = 900;
+
 /// Lemmatizes a list of words in batch
 ///
-/// More efficient than calling get_lemma repeatedly.
+/// More efficient than calling get_lemma repeatedly: the distinct words are looked up
+/// with one `IN (...)` query.
 ///
 /// # Arguments
 /// * `words` - List of words to lemmatize
@@ -60,32 +238,142 @@ pub async fn get_lemma(word: &str, lang: &str, app: &AppHandle) -> Result<Option
 /// ```
 pub async fn lemmatize_batch(words: &[String], lang: &str, app: &AppHandle) -> Result<Vec<(String, String)>> {
     let pool = langpack::open_lemma_db(lang, app).await?;
+    lemmatize_batch_in(&pool, words).await
+}
 
-    let mut results = Vec::with_capacity(words.len());
-
-    for word in words {
-        let word_lower = word.to_lowercase();
+/// Batch lemmatization against an open lemma database (see `lemmatize_batch`)
+pub async fn lemmatize_batch_in(pool: &SqlitePool, words: &[String]) -> Result<Vec<(String, String)>> {
+    let distinct: Vec<String> = words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
 
-        let result = sqlx::query("SELECT lemma FROM lemmas WHERE word = ?")
-            .bind(&word_lower)
-            .fetch_optional(&pool)
-            .await?;
+    let mut lemmas: HashMap<String, String> = HashMap::with_capacity(distinct.len());
+    for chunk in distinct.chunks(LEMMA_BATCH_SIZE) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!("SELECT word, lemma FROM lemmas WHERE word IN ({})", placeholders);
 
-        let lemma = match result {
-            Some(row) => row.try_get("lemma")?,
-            None => word_lower.clone(), // Word is already base form
-        };
+        let mut query = sqlx::query(&sql);
+        for word in chunk {
+            query = query.bind(word);
+        }
 
-        results.push((word.clone(), lemma));
+        for row in query.fetch_all(pool).await? {
+            lemmas.insert(row.try_get("word")?, row.try_get("lemma")?);
+        }
     }
 
-    Ok(results)
+    Ok(words
+        .iter()
+        .map(|word| {
+            let word_lower = word.to_lowercase();
+            // Words without a mapping are already in base form
+            let lemma = lemmas.get(&word_lower).cloned().unwrap_or(word_lower);
+            (word.clone(), lemma)
+        })
+        .collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    async fn lemma_db(rows: &[(&str, &str)]) -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE lemmas (word TEXT PRIMARY KEY, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (word, lemma) in rows {
+            sqlx::query("INSERT INTO lemmas (word, lemma) VALUES (?, ?)")
+                .bind(word)
+                .bind(lemma)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_stem_fallback_resolves_spanish_inflections() {
+        let pool = lemma_db(&[
+            ("hablo", "hablar"),
+            ("estás", "estar"),
+            ("gatas", "gato"),
+            ("comí", "comer"),
+            ("hace", "hacer"),
+        ])
+        .await;
+
+        // Gerund, plural, participle and attached pronouns the DB doesn't list
+        for (word, expected) in [
+            ("hablando", "hablar"),
+            ("gatos", "gato"),
+            ("comido", "comer"),
+            ("haciéndolo", "hacer"),
+            ("hacerlo", "hacer"),
+        ] {
+            assert_eq!(lookup_lemma(&pool, word, "es", false).await.unwrap(), None, "{}", word);
+            assert_eq!(
+                lookup_lemma(&pool, word, "es", true).await.unwrap(),
+                Some(expected.to_string()),
+                "{}",
+                word
+            );
+        }
+
+        // Exact matches take precedence, and unknown stems stay unresolved
+        assert_eq!(lookup_lemma(&pool, "Estás", "es", true).await.unwrap(), Some("estar".to_string()));
+        assert_eq!(lookup_lemma(&pool, "perros", "es", true).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_lemmatize_batch_matches_per_word_lookup() {
+        let pool = lemma_db(&[("estoy", "estar"), ("estás", "estar"), ("corriendo", "correr"), ("casas", "casa")]).await;
+
+        let words: Vec<String> = ["Estoy", "corriendo", "casa", "estoy", "perro", "casas", "Estás", "perro"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+
+        let batched = lemmatize_batch_in(&pool, &words).await.unwrap();
+
+        let mut per_word = Vec::new();
+        for word in &words {
+            let lemma = lookup_lemma(&pool, word, "es", false)
+                .await
+                .unwrap()
+                .unwrap_or_else(|| word.to_lowercase());
+            per_word.push((word.clone(), lemma));
+        }
+
+        assert_eq!(batched, per_word);
+        assert_eq!(batched[0], ("Estoy".to_string(), "estar".to_string()));
+        assert_eq!(batched[4], ("perro".to_string(), "perro".to_string()));
+        assert!(lemmatize_batch_in(&pool, &[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stem_fallback_resolves_english_inflections() {
+        let pool = lemma_db(&[("ran", "run"), ("walks", "walk"), ("studies", "study"), ("made", "make")]).await;
+
+        for (word, expected) in [("running", "run"), ("walked", "walk"), ("studied", "study"), ("making", "make")] {
+            assert_eq!(lookup_lemma(&pool, word, "en", false).await.unwrap(), None, "{}", word);
+            assert_eq!(
+                lookup_lemma(&pool, word, "en", true).await.unwrap(),
+                Some(expected.to_string()),
+                "{}",
+                word
+            );
+        }
+
+        assert_eq!(lookup_lemma(&pool, "ran", "en", true).await.unwrap(), Some("run".to_string()));
+    }
+
+
     // TODO: These tests require proper AppHandle mocking and lemma database setup.
     // They should be re-enabled with integration test infrastructure.
     //
@@ -1,13 +1,22 @@
 // Service layer - pure business logic, no UI dependencies
 
+pub mod audio_dir;
+pub mod auth;
+pub mod backup;
+pub mod benchmark;
 pub mod cleanup;
+pub mod dictionaries;
+pub mod download;
+pub mod lang;
 pub mod language_packs;
 pub mod lemmatization;
 pub mod model_download;
 pub mod oauth_server;
+pub mod pagination;
 pub mod recording;
 pub mod sessions;
 pub mod stats;
 pub mod text_library;
 pub mod transcription;
+pub mod translation;
 pub mod vocabulary;
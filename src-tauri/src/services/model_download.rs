@@ -5,12 +5,18 @@
  */
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use reqwest;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
-use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use super::download;
+use super::transcription::validate_model_file;
 
 /// Available Whisper models
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +30,8 @@ pub struct WhisperModel {
     pub description: String,
     #[serde(rename = "type")]
     pub model_type: String, // OSS version only supports "local"
+    /// Expected SHA-256 of the downloaded file (verified before install when present)
+    pub sha256: Option<String>,
 }
 
 /// Download progress information
@@ -34,6 +42,8 @@ pub struct DownloadProgress {
     pub downloaded_bytes: u64,
     pub total_bytes: u64,
     pub percentage: f64,
+    pub speed_mbps: f32,
+    pub eta_seconds: Option<u64>,
     pub is_complete: bool,
 }
 
@@ -51,6 +61,9 @@ pub fn get_models_dir(app: &AppHandle) -> Result<PathBuf> {
 }
 
 /// Get list of available Whisper models
+///
+/// `sha256` is the digest Hugging Face lists for the file. Models without a pinned
+/// digest are verified against the SHA-256 published with the download instead.
 pub fn get_available_models() -> Vec<WhisperModel> {
     vec![
         WhisperModel {
@@ -61,6 +74,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 75,
             description: "Fastest, lowest accuracy".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21".to_string()),
         },
         WhisperModel {
             name: "base".to_string(),
@@ -70,6 +84,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 142,
             description: "Good balance, recommended".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe".to_string()),
         },
         WhisperModel {
             name: "small".to_string(),
@@ -79,6 +94,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 466,
             description: "Better accuracy".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b".to_string()),
         },
         WhisperModel {
             name: "medium".to_string(),
@@ -88,6 +104,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 1500,
             description: "High accuracy".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208".to_string()),
         },
         WhisperModel {
             name: "large".to_string(),
@@ -97,6 +114,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 2900,
             description: "Highest accuracy, slower".to_string(),
             model_type: "local".to_string(),
+            sha256: None,
         },
         WhisperModel {
             name: "large-v2".to_string(),
@@ -106,6 +124,7 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 2900,
             description: "Improved large model".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("9a423fe4d40c82774b6af34115b8b935f34152246eb19e80e376071d3f999487".to_string()),
         },
         WhisperModel {
             name: "large-v3".to_string(),
@@ -115,13 +134,145 @@ pub fn get_available_models() -> Vec<WhisperModel> {
             size_mb: 2900,
             description: "Best accuracy available".to_string(),
             model_type: "local".to_string(),
+            sha256: Some("64d182b440b98d5203c4f9bd541544d84c605196c4f7b845dfa11fb23594d1e2".to_string()),
+        },
+        // Quantized variants - near full-precision accuracy at a fraction of the size/RAM
+        WhisperModel {
+            name: "tiny-q5_1".to_string(),
+            display_name: "Tiny (Q5_1)".to_string(),
+            file_name: "ggml-tiny-q5_1.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny-q5_1.bin".to_string(),
+            size_mb: 31,
+            description: "Quantized tiny, smallest download".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "base-q5_1".to_string(),
+            display_name: "Base (Q5_1)".to_string(),
+            file_name: "ggml-base-q5_1.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q5_1.bin".to_string(),
+            size_mb: 57,
+            description: "Quantized base, low memory".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "base-q8_0".to_string(),
+            display_name: "Base (Q8_0)".to_string(),
+            file_name: "ggml-base-q8_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base-q8_0.bin".to_string(),
+            size_mb: 78,
+            description: "Quantized base, near full accuracy".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "small-q5_1".to_string(),
+            display_name: "Small (Q5_1)".to_string(),
+            file_name: "ggml-small-q5_1.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q5_1.bin".to_string(),
+            size_mb: 181,
+            description: "Quantized small, low memory".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "small-q8_0".to_string(),
+            display_name: "Small (Q8_0)".to_string(),
+            file_name: "ggml-small-q8_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q8_0.bin".to_string(),
+            size_mb: 252,
+            description: "Quantized small, near full accuracy".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "medium-q5_0".to_string(),
+            display_name: "Medium (Q5_0)".to_string(),
+            file_name: "ggml-medium-q5_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin".to_string(),
+            size_mb: 514,
+            description: "Quantized medium, low memory".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "medium-q8_0".to_string(),
+            display_name: "Medium (Q8_0)".to_string(),
+            file_name: "ggml-medium-q8_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q8_0.bin".to_string(),
+            size_mb: 785,
+            description: "Quantized medium, near full accuracy".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "large-v2-q5_0".to_string(),
+            display_name: "Large-v2 (Q5_0)".to_string(),
+            file_name: "ggml-large-v2-q5_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v2-q5_0.bin".to_string(),
+            size_mb: 1080,
+            description: "Quantized large-v2, low memory".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
+        },
+        WhisperModel {
+            name: "large-v3-q5_0".to_string(),
+            display_name: "Large-v3 (Q5_0)".to_string(),
+            file_name: "ggml-large-v3-q5_0.bin".to_string(),
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-q5_0.bin".to_string(),
+            size_mb: 1080,
+            description: "Quantized large-v3, low memory".to_string(),
+            model_type: "local".to_string(),
+            sha256: None,
         },
     ]
 }
 
-/// Check if a model is installed
-pub fn is_model_installed(app: &AppHandle, model_name: &str) -> Result<bool> {
-    let models_dir = get_models_dir(app)?;
+/// Prefix used for the `name` of user-imported models (e.g. "custom:<id>")
+pub const CUSTOM_MODEL_PREFIX: &str = "custom:";
+
+/// Registry file listing user-imported models, stored next to the model files
+const CUSTOM_MODELS_FILE: &str = "custom_models.json";
+
+/// A user-imported ggml model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomModel {
+    pub id: String,
+    pub display_name: String,
+    pub file_name: String,
+    pub imported_at: i64,
+}
+
+/// Load the custom model registry (empty if none imported yet)
+fn load_custom_models(models_dir: &Path) -> Result<Vec<CustomModel>> {
+    let registry_path = models_dir.join(CUSTOM_MODELS_FILE);
+    if !registry_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&registry_path).context("Failed to read custom models registry")?;
+    serde_json::from_str(&json).context("Failed to parse custom models registry")
+}
+
+/// Persist the custom model registry
+fn save_custom_models(models_dir: &Path, models: &[CustomModel]) -> Result<()> {
+    let json = serde_json::to_string_pretty(models)?;
+    fs::write(models_dir.join(CUSTOM_MODELS_FILE), json).context("Failed to write custom models registry")
+}
+
+/// Resolve a model name (e.g. "small", "small-q5_1" or "custom:<id>") to its file inside `models_dir`
+fn resolve_model_path(models_dir: &Path, model_name: &str) -> Result<PathBuf> {
+    if let Some(id) = model_name.strip_prefix(CUSTOM_MODEL_PREFIX) {
+        let custom = load_custom_models(models_dir)?
+            .into_iter()
+            .find(|m| m.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown custom model: {}", model_name))?;
+        return Ok(models_dir.join(custom.file_name));
+    }
+
     let models = get_available_models();
 
     let model = models
@@ -129,7 +280,13 @@ pub fn is_model_installed(app: &AppHandle, model_name: &str) -> Result<bool> {
         .find(|m| m.name == model_name)
         .ok_or_else(|| anyhow::anyhow!("Unknown model: {}", model_name))?;
 
-    let model_path = models_dir.join(&model.file_name);
+    Ok(models_dir.join(&model.file_name))
+}
+
+/// Check if a model is installed
+pub fn is_model_installed(app: &AppHandle, model_name: &str) -> Result<bool> {
+    let models_dir = get_models_dir(app)?;
+    let model_path = resolve_model_path(&models_dir, model_name)?;
     Ok(model_path.exists())
 }
 
@@ -147,20 +304,59 @@ pub fn get_default_model_path(app: &AppHandle) -> Result<PathBuf> {
 /// Get path to a specific model
 pub fn get_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf> {
     let models_dir = get_models_dir(app)?;
-    let models = get_available_models();
+    resolve_model_path(&models_dir, model_name)
+}
 
-    let model = models
-        .iter()
-        .find(|m| m.name == model_name)
-        .ok_or_else(|| anyhow::anyhow!("Unknown model: {}", model_name))?;
+/// Model file names in transcription fallback order (most accurate first)
+/// Full-precision files are preferred over quantized variants of the same size.
+pub const MODEL_PRIORITY: &[&str] = &[
+    "ggml-large-v3.bin",
+    "ggml-large-v3-q5_0.bin",
+    "ggml-large-v2.bin",
+    "ggml-large-v2-q5_0.bin",
+    "ggml-large.bin",
+    "ggml-medium.bin",
+    "ggml-medium-q8_0.bin",
+    "ggml-medium-q5_0.bin",
+    "ggml-small.bin",
+    "ggml-small-q8_0.bin",
+    "ggml-small-q5_1.bin",
+    "ggml-base.bin",
+    "ggml-base-q8_0.bin",
+    "ggml-base-q5_1.bin",
+    "ggml-tiny.bin",
+    "ggml-tiny-q5_1.bin",
+];
+
+/// Pick the model file to transcribe with when the caller didn't pass one
+///
+/// Uses `selected` (the user's default model setting) if it's installed, otherwise
+/// the most accurate installed model from `MODEL_PRIORITY`, otherwise ggml-tiny.bin
+/// (which the caller reports as missing).
+pub fn select_transcription_model(models_dir: &Path, selected: Option<&str>) -> PathBuf {
+    if let Some(name) = selected {
+        match resolve_model_path(models_dir, name) {
+            Ok(path) if path.exists() => return path,
+            Ok(_) => println!("[select_transcription_model] Default model {} is not installed, falling back", name),
+            Err(e) => println!("[select_transcription_model] Ignoring default model {}: {}", name, e),
+        }
+    }
 
-    Ok(models_dir.join(&model.file_name))
+    MODEL_PRIORITY
+        .iter()
+        .map(|file_name| models_dir.join(file_name))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| models_dir.join("ggml-tiny.bin"))
 }
 
 /// Download a Whisper model with progress tracking
+/// Setting `cancel` aborts the download and removes the partial file.
+/// With `parallel`, the file is fetched as concurrent ranged chunks when the host allows it.
 pub async fn download_model(
     app: &AppHandle,
     model_name: &str,
+    parallel: bool,
+    cancel: Arc<AtomicBool>,
     progress_callback: impl Fn(DownloadProgress) + Send + 'static,
 ) -> Result<PathBuf> {
     let models = get_available_models();
@@ -177,62 +373,55 @@ pub async fn download_model(
         return Ok(output_path);
     }
 
-    // Download the model
+    // Download the model (resumes from a previous .partial file if one exists)
     println!("Downloading {} from {}", model.display_name, model.url);
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(&model.url)
-        .send()
-        .await
-        .context("Failed to start download")?;
-
-    let total_size = response
-        .content_length()
-        .ok_or_else(|| anyhow::anyhow!("Failed to get content length"))?;
-
-    // Create temporary file
-    let temp_path = output_path.with_extension("tmp");
-    let mut file = tokio::fs::File::create(&temp_path)
-        .await
-        .context("Failed to create temporary file")?;
-
-    // Download in chunks with progress
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
-    let mut last_progress_emit = std::time::Instant::now();
-
-    use futures_util::StreamExt;
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Error while downloading")?;
-        file.write_all(&chunk)
-            .await
-            .context("Failed to write to file")?;
-
-        downloaded += chunk.len() as u64;
+    let temp_path = download::partial_path(&output_path);
+
+    // Check free space up front (only the part not already downloaded is needed)
+    let already_downloaded = fs::metadata(&temp_path).map(|m| m.len()).unwrap_or(0);
+    let expected_bytes = model.size_mb * 1024 * 1024;
+    download::ensure_disk_space(&models_dir, expected_bytes.saturating_sub(already_downloaded))?;
+    let mut meter = download::ProgressMeter::new();
 
+    let on_progress = |downloaded: u64, total_size: u64| {
         // Emit progress every 500ms to avoid overwhelming the event system
-        if last_progress_emit.elapsed().as_millis() > 500 || downloaded == total_size {
-            let percentage = (downloaded as f64 / total_size as f64) * 100.0;
+        if let Some(rate) = meter.tick(downloaded, total_size) {
+            let percentage = if total_size > 0 {
+                (downloaded as f64 / total_size as f64) * 100.0
+            } else {
+                0.0
+            };
             progress_callback(DownloadProgress {
                 model_name: model.display_name.clone(),
                 downloaded_bytes: downloaded,
                 total_bytes: total_size,
                 percentage,
+                speed_mbps: rate.speed_mbps,
+                eta_seconds: rate.eta_seconds,
                 is_complete: false,
             });
-            last_progress_emit = std::time::Instant::now();
         }
-    }
-
-    // Finalize
-    file.flush().await.context("Failed to flush file")?;
-    drop(file);
+    };
 
-    // Move temp file to final location
-    tokio::fs::rename(&temp_path, &output_path)
-        .await
-        .context("Failed to move downloaded file")?;
+    let policy = download::RetryPolicy::default();
+    let total_size = if parallel {
+        let options = download::ChunkedDownload::default();
+        download::download_chunked(&client, &model.url, &temp_path, &options, &cancel, &policy, on_progress).await?
+    } else {
+        download::download_resumable_with_retry(&client, &model.url, &temp_path, &cancel, &policy, on_progress).await?
+    };
+
+    // Verify checksum (pinned, or published by the host) and move completed file to final location.
+    // Without one the model isn't installed; the partial file is kept for the next attempt
+    let expected_sha256 = match &model.sha256 {
+        Some(digest) => digest.clone(),
+        None => download::published_sha256(&model.url)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No checksum published for {}, not installing it unverified", model.file_name))?,
+    };
+    download::finalize_download(&temp_path, &output_path, Some(&expected_sha256))?;
 
     // Final progress callback
     progress_callback(DownloadProgress {
@@ -240,6 +429,8 @@ pub async fn download_model(
         downloaded_bytes: total_size,
         total_bytes: total_size,
         percentage: 100.0,
+        speed_mbps: 0.0,
+        eta_seconds: Some(0),
         is_complete: true,
     });
 
@@ -247,6 +438,94 @@ pub async fn download_model(
     Ok(output_path)
 }
 
+/// Import a ggml model from a local path (e.g. a fine-tuned model)
+///
+/// The file is validated by loading it with Whisper, copied into the models
+/// directory and recorded in `custom_models.json` under the name `custom:<id>`.
+pub fn import_custom_model(
+    app: &AppHandle,
+    source_path: &Path,
+    display_name: &str,
+) -> Result<InstalledModelInfo> {
+    println!("[import_custom_model] Importing {:?} as '{}'", source_path, display_name);
+
+    if !source_path.is_file() {
+        anyhow::bail!("Model file not found: {}", source_path.display());
+    }
+
+    let display_name = display_name.trim();
+    if display_name.is_empty() {
+        anyhow::bail!("Display name cannot be empty");
+    }
+
+    // Make sure Whisper can actually load it before copying gigabytes around
+    validate_model_file(source_path)
+        .map_err(|e| anyhow::anyhow!("Not a valid Whisper model: {}", e))?;
+
+    let models_dir = get_models_dir(app)?;
+    let id = Uuid::new_v4().to_string();
+    let file_name = format!("custom-{}.bin", id);
+    let destination = models_dir.join(&file_name);
+
+    fs::copy(source_path, &destination)
+        .context(format!("Failed to copy model into {:?}", destination))?;
+
+    let mut custom_models = load_custom_models(&models_dir)?;
+    custom_models.push(CustomModel {
+        id: id.clone(),
+        display_name: display_name.to_string(),
+        file_name,
+        imported_at: Utc::now().timestamp(),
+    });
+    if let Err(e) = save_custom_models(&models_dir, &custom_models) {
+        let _ = fs::remove_file(&destination);
+        return Err(e);
+    }
+
+    let size_bytes = fs::metadata(&destination).map(|m| m.len()).unwrap_or(0);
+    println!("[import_custom_model] Imported model as {}{}", CUSTOM_MODEL_PREFIX, id);
+
+    Ok(InstalledModelInfo {
+        name: format!("{}{}", CUSTOM_MODEL_PREFIX, id),
+        display_name: display_name.to_string(),
+        size_bytes,
+        path: destination.to_string_lossy().to_string(),
+    })
+}
+
+/// Whether an installed model file loads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelValidation {
+    pub model_name: String,
+    pub path: String,
+    pub valid: bool,
+    /// Why the model failed to load (suggests re-downloading)
+    pub error: Option<String>,
+}
+
+/// Try to load an installed model, e.g. right after a download to catch truncated files
+///
+/// Loads the whole model, so call from a blocking context.
+pub fn validate_model(app: &AppHandle, model_name: &str) -> Result<ModelValidation> {
+    let path = get_model_path(app, model_name)?;
+    Ok(check_model_file(model_name, &path))
+}
+
+fn check_model_file(model_name: &str, path: &Path) -> ModelValidation {
+    let error = validate_model_file(path).err().map(|e| e.to_string());
+    if let Some(error) = &error {
+        println!("[validate_model] {} is not usable: {}", model_name, error);
+    }
+
+    ModelValidation {
+        model_name: model_name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        valid: error.is_none(),
+        error,
+    }
+}
+
 /// Delete a downloaded model
 pub fn delete_model(app: &AppHandle, model_name: &str) -> Result<()> {
     println!("[delete_model] Attempting to delete model: {}", model_name);
@@ -263,6 +542,14 @@ pub fn delete_model(app: &AppHandle, model_name: &str) -> Result<()> {
         println!("[delete_model] Warning: File does not exist at {:?}", model_path);
     }
 
+    // Drop imported models from the registry as well
+    if let Some(id) = model_name.strip_prefix(CUSTOM_MODEL_PREFIX) {
+        let models_dir = get_models_dir(app)?;
+        let mut custom_models = load_custom_models(&models_dir)?;
+        custom_models.retain(|m| m.id != id);
+        save_custom_models(&models_dir, &custom_models)?;
+    }
+
     Ok(())
 }
 
@@ -295,5 +582,132 @@ pub fn get_installed_models(app: &AppHandle) -> Result<Vec<InstalledModelInfo>>
         }
     }
 
+    // User-imported models
+    let models_dir = get_models_dir(app)?;
+    for custom in load_custom_models(&models_dir)? {
+        let path = models_dir.join(&custom.file_name);
+        if let Ok(metadata) = fs::metadata(&path) {
+            installed.push(InstalledModelInfo {
+                name: format!("{}{}", CUSTOM_MODEL_PREFIX, custom.id),
+                display_name: custom.display_name,
+                size_bytes: metadata.len(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
     Ok(installed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_model_entries_are_unique_and_resolvable() {
+        let models = get_available_models();
+        let models_dir = PathBuf::from("/tmp/models");
+
+        let mut names = HashSet::new();
+        let mut file_names = HashSet::new();
+        for model in &models {
+            assert!(names.insert(model.name.clone()), "duplicate model name: {}", model.name);
+            assert!(file_names.insert(model.file_name.clone()), "duplicate file name: {}", model.file_name);
+
+            let path = resolve_model_path(&models_dir, &model.name).unwrap();
+            assert_eq!(path, models_dir.join(&model.file_name));
+        }
+    }
+
+    #[test]
+    fn test_every_model_can_be_verified() {
+        for model in get_available_models() {
+            match &model.sha256 {
+                Some(digest) => assert!(
+                    digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()),
+                    "bad checksum for {}: {}",
+                    model.name,
+                    digest
+                ),
+                // Unpinned models rely on the digest Hugging Face publishes
+                None => assert!(
+                    model.url.starts_with("https://huggingface.co/") && model.url.contains("/resolve/"),
+                    "{} has no checksum source",
+                    model.name
+                ),
+            }
+        }
+    }
+
+    /// Checks the pinned digests against the ones Hugging Face publishes (needs network)
+    #[tokio::test]
+    #[ignore]
+    async fn test_pinned_checksums_match_published() {
+        for model in get_available_models() {
+            if let Some(pinned) = &model.sha256 {
+                let published = download::published_sha256(&model.url).await.unwrap();
+                assert_eq!(published.as_deref(), Some(pinned.as_str()), "{}", model.file_name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_custom_model_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let custom = CustomModel {
+            id: "abc".to_string(),
+            display_name: "My fine-tune".to_string(),
+            file_name: "custom-abc.bin".to_string(),
+            imported_at: 0,
+        };
+        save_custom_models(dir.path(), &[custom]).unwrap();
+
+        let path = resolve_model_path(dir.path(), "custom:abc").unwrap();
+        assert_eq!(path, dir.path().join("custom-abc.bin"));
+        assert!(resolve_model_path(dir.path(), "custom:missing").is_err());
+    }
+
+    #[test]
+    fn test_priority_list_covers_every_model() {
+        for model in get_available_models() {
+            assert!(
+                MODEL_PRIORITY.contains(&model.file_name.as_str()),
+                "{} missing from MODEL_PRIORITY",
+                model.file_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_transcription_model() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ggml-base.bin"), b"model").unwrap();
+        fs::write(dir.path().join("ggml-small.bin"), b"model").unwrap();
+
+        // Installed selection wins over the more accurate priority pick
+        assert_eq!(select_transcription_model(dir.path(), Some("base")), dir.path().join("ggml-base.bin"));
+
+        // Uninstalled or unknown selection falls back to the priority list
+        assert_eq!(select_transcription_model(dir.path(), Some("medium")), dir.path().join("ggml-small.bin"));
+        assert_eq!(select_transcription_model(dir.path(), Some("bogus")), dir.path().join("ggml-small.bin"));
+        assert_eq!(select_transcription_model(dir.path(), None), dir.path().join("ggml-small.bin"));
+    }
+
+    #[test]
+    fn test_garbage_model_file_is_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.bin");
+        fs::write(&path, b"<html>502 Bad Gateway</html>").unwrap();
+
+        let validation = check_model_file("base", &path);
+        assert!(!validation.valid);
+        let error = validation.error.unwrap();
+        assert!(error.contains("download it again"), "{}", error);
+        assert!(error.contains(&path.display().to_string()), "{}", error);
+
+        let missing = check_model_file("base", &dir.path().join("ggml-small.bin"));
+        assert!(!missing.valid);
+        assert!(missing.error.unwrap().contains("not found"));
+    }
+}
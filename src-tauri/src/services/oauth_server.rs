@@ -4,44 +4,68 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use url::Url;
-use std::process::Command;
-
-const OAUTH_PORT: u16 = 54321; // Fixed port for OAuth callbacks
-
-/// Attempts to free the OAuth port by killing any process using it
-fn cleanup_port() {
-    #[cfg(unix)]
-    {
-        let _ = Command::new("sh")
-            .arg("-c")
-            .arg(format!("lsof -ti:{} | xargs kill -9 2>/dev/null || true", OAUTH_PORT))
-            .output();
+
+/// Ports tried in order for OAuth callbacks (these must be allowed redirect URLs in Supabase)
+/// If all are busy, the OS picks a free port instead
+const OAUTH_CANDIDATE_PORTS: &[u16] = &[54321, 54322, 54323, 54324, 54325];
+
+/// Localhost listener that receives the OAuth redirect
+pub struct OAuthCallbackServer {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl OAuthCallbackServer {
+    /// Bind to the first free candidate port, falling back to an OS-assigned port
+    pub fn bind() -> Result<Self, String> {
+        for &port in OAUTH_CANDIDATE_PORTS {
+            match TcpListener::bind(("127.0.0.1", port)) {
+                Ok(listener) => return Ok(Self { listener, port }),
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                    println!("[OAuth] Port {} is in use, trying next", port);
+                }
+                Err(e) => return Err(format!("Failed to bind to localhost:{} - {}", port, e)),
+            }
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| format!("Failed to bind OAuth callback server: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read OAuth callback port: {}", e))?
+            .port();
+        Ok(Self { listener, port })
+    }
+
+    /// Port the server is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Redirect URL to pass as `redirect_to` when building the auth URL
+    pub fn redirect_url(&self) -> String {
+        format!("http://localhost:{}/callback", self.port)
+    }
+
+    /// Wait for the OAuth callback and return its path (with tokens as query params)
+    pub fn wait_for_callback(self) -> Result<String, String> {
+        wait_for_callback(self.listener, self.port)
     }
 }
 
-/// Starts a temporary localhost server to catch OAuth callback on fixed port
+/// Starts a temporary localhost server to catch the OAuth callback
+/// `on_ready` receives the redirect URL once the server is listening (e.g. to open the browser)
 /// Returns the callback URL when received
-pub fn start_oauth_server_and_wait() -> Result<String, String> {
-    // Try to bind to fixed port, with automatic cleanup if already in use
-    let mut listener = match TcpListener::bind(format!("127.0.0.1:{}", OAUTH_PORT)) {
-        Ok(listener) => listener,
-        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
-            println!("[OAuth] Port {} is in use, attempting to clean up...", OAUTH_PORT);
-            cleanup_port();
-            // Wait a moment for the port to be freed
-            thread::sleep(Duration::from_millis(500));
-            // Try again after cleanup
-            TcpListener::bind(format!("127.0.0.1:{}", OAUTH_PORT))
-                .map_err(|e| {
-                    format!("Port {} is still in use after cleanup. Please manually run: lsof -ti:{} | xargs kill -9", OAUTH_PORT, OAUTH_PORT)
-                })?
-        },
-        Err(e) => {
-            return Err(format!("Failed to bind to localhost:{} - {}", OAUTH_PORT, e));
-        }
-    };
+pub fn start_oauth_server_and_wait(
+    on_ready: impl FnOnce(&str) -> Result<(), String>,
+) -> Result<String, String> {
+    let server = OAuthCallbackServer::bind()?;
+    on_ready(&server.redirect_url())?;
+    server.wait_for_callback()
+}
 
-    println!("[OAuth] Server listening on port {}", OAUTH_PORT);
+fn wait_for_callback(listener: TcpListener, port: u16) -> Result<String, String> {
+    println!("[OAuth] Server listening on port {}", port);
 
     let callback_url = Arc::new(Mutex::new(None::<String>));
     let callback_url_clone = callback_url.clone();
@@ -195,3 +219,27 @@ pub fn parse_oauth_callback(callback_url: &str) -> Result<(String, String), Stri
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_servers_pick_distinct_ports() {
+        let handles: Vec<_> = (0..2)
+            .map(|_| thread::spawn(OAuthCallbackServer::bind))
+            .collect();
+        let servers: Vec<OAuthCallbackServer> = handles
+            .into_iter()
+            .map(|h| h.join().unwrap().unwrap())
+            .collect();
+
+        assert_ne!(servers[0].port(), servers[1].port());
+        for server in &servers {
+            assert_eq!(
+                server.redirect_url(),
+                format!("http://localhost:{}/callback", server.port())
+            );
+        }
+    }
+}
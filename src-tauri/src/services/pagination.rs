@@ -0,0 +1,34 @@
+/**
+ * Pagination for list queries
+ *
+ * List endpoints that can grow to thousands of rows (vocabulary, sessions)
+ * offer paged variants that return one window plus the total row count.
+ */
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// SQLite treats a negative LIMIT as "no limit"
+pub const NO_LIMIT: i64 = -1;
+
+/// One page of results
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of rows across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Reject page windows that would silently return everything or nothing useful
+pub fn validate_page(limit: i64, offset: i64) -> Result<()> {
+    if limit <= 0 {
+        anyhow::bail!("Page limit must be positive, got {}", limit);
+    }
+    if offset < 0 {
+        anyhow::bail!("Page offset must not be negative, got {}", offset);
+    }
+    Ok(())
+}
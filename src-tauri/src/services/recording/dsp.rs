@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional processing applied to microphone samples before they are written
+/// Both stages are off by default
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingDsp {
+    /// Cutoff of a high-pass filter that removes hum and rumble (e.g. 100.0)
+    pub high_pass_hz: Option<f32>,
+    /// Mute callback buffers whose RMS stays below this level (0.0-1.0, e.g. 0.01)
+    pub noise_gate_threshold: Option<f32>,
+}
+
+impl RecordingDsp {
+    /// Whether any processing is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.high_pass_hz.is_some() || self.noise_gate_threshold.is_some()
+    }
+}
+
+/// How long the gate stays open after the signal drops below the threshold,
+/// so word endings and short pauses aren't chopped off
+const GATE_HOLD_SECONDS: f32 = 0.3;
+
+/// One-pole high-pass filter per channel (interleaved samples)
+struct HighPass {
+    alpha: f32,
+    prev_input: Vec<f32>,
+    prev_output: Vec<f32>,
+}
+
+impl HighPass {
+    fn new(cutoff_hz: f32, sample_rate: u32, channels: u16) -> Self {
+        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff_hz);
+        let dt = 1.0 / sample_rate as f32;
+        Self {
+            alpha: rc / (rc + dt),
+            prev_input: vec![0.0; channels as usize],
+            prev_output: vec![0.0; channels as usize],
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        let channels = self.prev_input.len();
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let ch = i % channels;
+            let output = self.alpha * (self.prev_output[ch] + *sample - self.prev_input[ch]);
+            self.prev_input[ch] = *sample;
+            self.prev_output[ch] = output;
+            *sample = output;
+        }
+    }
+}
+
+/// Block-level noise gate with a hold time
+struct NoiseGate {
+    threshold: f32,
+    hold_samples: usize,
+    /// Samples left before the gate closes
+    remaining_hold: usize,
+}
+
+impl NoiseGate {
+    fn new(threshold: f32, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            threshold,
+            hold_samples: (GATE_HOLD_SECONDS * sample_rate as f32) as usize * channels as usize,
+            remaining_hold: 0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        if mean_square.sqrt() >= self.threshold {
+            self.remaining_hold = self.hold_samples;
+        } else if self.remaining_hold > 0 {
+            self.remaining_hold = self.remaining_hold.saturating_sub(samples.len());
+        } else {
+            samples.fill(0.0);
+        }
+    }
+}
+
+/// Stateful processor for one recording stream
+///
+/// Runs in the audio callback: no allocation, O(1) work per sample.
+pub struct DspProcessor {
+    high_pass: Option<HighPass>,
+    gate: Option<NoiseGate>,
+}
+
+impl DspProcessor {
+    pub fn new(options: RecordingDsp, sample_rate: u32, channels: u16) -> Self {
+        let channels = channels.max(1);
+        Self {
+            high_pass: options
+                .high_pass_hz
+                .filter(|hz| *hz > 0.0)
+                .map(|hz| HighPass::new(hz, sample_rate, channels)),
+            gate: options
+                .noise_gate_threshold
+                .map(|threshold| NoiseGate::new(threshold, sample_rate, channels)),
+        }
+    }
+
+    /// Process interleaved samples in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if let Some(high_pass) = &mut self.high_pass {
+            high_pass.process(samples);
+        }
+        if let Some(gate) = &mut self.gate {
+            gate.process(samples);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, seconds: f32) -> Vec<f32> {
+        (0..(seconds * RATE as f32) as usize)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn high_pass(cutoff_hz: f32) -> DspProcessor {
+        DspProcessor::new(
+            RecordingDsp {
+                high_pass_hz: Some(cutoff_hz),
+                noise_gate_threshold: None,
+            },
+            RATE,
+            1,
+        )
+    }
+
+    #[test]
+    fn test_high_pass_attenuates_hum_and_keeps_voice() {
+        // Skip the first 100ms while the filter settles
+        let settled = (RATE / 10) as usize;
+
+        let mut hum = sine(50.0, 1.0);
+        let hum_rms = rms(&hum[settled..]);
+        high_pass(150.0).process(&mut hum);
+        assert!(rms(&hum[settled..]) < hum_rms * 0.35, "50 Hz hum not attenuated");
+
+        let mut voice = sine(1000.0, 1.0);
+        let voice_rms = rms(&voice[settled..]);
+        high_pass(150.0).process(&mut voice);
+        assert!(rms(&voice[settled..]) > voice_rms * 0.95, "1 kHz tone attenuated");
+    }
+
+    #[test]
+    fn test_high_pass_impulse_response_decays() {
+        let mut impulse = vec![0.0f32; RATE as usize / 2];
+        impulse[0] = 1.0;
+        high_pass(150.0).process(&mut impulse);
+
+        // The impulse passes, then the response dies away with no DC left over
+        assert!(impulse[0] > 0.9);
+        assert!(impulse[RATE as usize / 10..].iter().all(|s| s.abs() < 1e-3));
+        assert!(impulse.iter().sum::<f32>().abs() < 0.05);
+    }
+
+    #[test]
+    fn test_noise_gate_mutes_quiet_buffers() {
+        let mut gate = DspProcessor::new(
+            RecordingDsp {
+                high_pass_hz: None,
+                noise_gate_threshold: Some(0.05),
+            },
+            RATE,
+            1,
+        );
+
+        let mut noise = vec![0.01f32; 512];
+        gate.process(&mut noise);
+        assert!(noise.iter().all(|s| *s == 0.0));
+
+        let mut speech = sine(300.0, 0.032);
+        let original = speech.clone();
+        gate.process(&mut speech);
+        assert_eq!(speech, original);
+
+        // Quiet right after speech is kept during the hold time
+        let mut tail = vec![0.01f32; 512];
+        gate.process(&mut tail);
+        assert!(tail.iter().all(|s| *s == 0.01));
+    }
+}
@@ -1,4 +1,17 @@
+mod dsp;
+mod pre_roll;
 mod recorder;
+mod recovery;
+mod resample;
 mod wav_writer;
 
-pub use recorder::{DeviceInfo, RecorderState, RecordingResult, Result};
+pub use dsp::RecordingDsp;
+pub use pre_roll::MAX_PRE_ROLL_SECONDS;
+pub use recorder::{
+    preferred_or_default, DetailedDeviceInfo, DeviceConfig, DeviceInfo, RecorderState, RecordingResult, Result,
+};
+pub use resample::{sinc_resampler, MonoResampler, WHISPER_SAMPLE_RATE};
+pub use recovery::{find_markers, remove_marker, write_marker, RecordingMarker};
+pub use wav_writer::{
+    check_recording_audible, check_wav_input, copy_partial_wav, repair_wav, trim_wav, DEFAULT_AUDIBLE_THRESHOLD,
+};
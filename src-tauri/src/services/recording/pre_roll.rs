@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+/// Longest pre-roll the recorder keeps, to bound memory while idle
+pub const MAX_PRE_ROLL_SECONDS: f32 = 5.0;
+
+/// Ring buffer holding the most recent samples captured before recording starts
+///
+/// Samples are interleaved; the capacity is a whole number of frames so a
+/// drained buffer always starts on the first channel.
+pub struct PreRollBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl PreRollBuffer {
+    /// Buffer holding `seconds` of audio (clamped to `MAX_PRE_ROLL_SECONDS`)
+    pub fn new(seconds: f32, sample_rate: u32, channels: u16) -> Self {
+        let seconds = seconds.clamp(0.0, MAX_PRE_ROLL_SECONDS);
+        let frames = (seconds * sample_rate as f32).round() as usize;
+        let capacity = frames * channels as usize;
+
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append samples, dropping the oldest ones beyond capacity
+    pub fn push(&mut self, data: &[f32]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        // Only the tail of a large callback buffer can survive
+        let data = &data[data.len().saturating_sub(self.capacity)..];
+        let overflow = (self.samples.len() + data.len()).saturating_sub(self.capacity);
+        self.samples.drain(..overflow);
+        self.samples.extend(data.iter().copied());
+    }
+
+    /// Take everything buffered, oldest first, leaving the buffer empty
+    pub fn drain(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+
+    /// Number of buffered samples
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pre_roll_keeps_most_recent_samples() {
+        // 1 second of 4Hz stereo = 8 samples
+        let mut buffer = PreRollBuffer::new(1.0, 4, 2);
+        assert!(buffer.is_empty());
+
+        buffer.push(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(buffer.len(), 4);
+
+        buffer.push(&[5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+        assert_eq!(buffer.drain(), vec![3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+        assert!(buffer.is_empty());
+
+        // A single callback larger than the buffer
+        let big: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        buffer.push(&big);
+        assert_eq!(buffer.drain(), (12..20).map(|i| i as f32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_pre_roll_disabled_and_clamped() {
+        let mut disabled = PreRollBuffer::new(0.0, 16000, 1);
+        disabled.push(&[0.5; 100]);
+        assert!(disabled.is_empty());
+
+        let mut clamped = PreRollBuffer::new(60.0, 10, 1);
+        clamped.push(&[0.5; 100]);
+        assert_eq!(clamped.len(), (MAX_PRE_ROLL_SECONDS * 10.0) as usize);
+    }
+}
@@ -1,8 +1,11 @@
-use super::wav_writer::WavWriter;
+use super::dsp::{DspProcessor, RecordingDsp};
+use super::pre_roll::PreRollBuffer;
+use super::resample::{MonoResampler, WHISPER_SAMPLE_RATE};
+use super::wav_writer::{measure_wav_levels, normalize_wav, AudioLevels, WavWriter, NORMALIZE_TARGET_PEAK};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, SampleFormat, Stream};
+use cpal::{Device, SampleFormat, Stream, SupportedStreamConfig, SupportedStreamConfigRange};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
@@ -17,6 +20,10 @@ pub struct RecordingResult {
     pub sample_rate: u32,
     pub channels: u16,
     pub duration_seconds: f32,
+    /// Loudest sample as captured (before normalization), 0.0-1.0
+    pub peak_amplitude: f32,
+    /// Average (RMS) level as captured - very low values mean the mic was too quiet
+    pub rms_amplitude: f32,
 }
 
 /// Device information for frontend
@@ -27,6 +34,102 @@ pub struct DeviceInfo {
     pub is_default: bool,
 }
 
+/// One supported input format range of a device
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedConfigInfo {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    /// e.g. "f32", "i16"
+    pub sample_format: String,
+}
+
+/// Device information including every supported input format
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetailedDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub configs: Vec<SupportedConfigInfo>,
+    /// Whether the device can record at 16kHz in a format the recorder handles
+    pub supports_preferred_rate: bool,
+}
+
+/// Stream format a device would record with - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConfig {
+    pub device_name: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// e.g. "f32", "i16"
+    pub sample_format: String,
+}
+
+/// Where captured samples go while recording: DSP, optional conversion to
+/// 16kHz mono, then the WAV file
+struct RecordingSink {
+    writer: Arc<Mutex<WavWriter>>,
+    processor: DspProcessor,
+    resampler: Option<MonoResampler>,
+}
+
+impl RecordingSink {
+    /// Sink for input at `sample_rate`/`channels`; returns it with the format written to the file
+    fn create(
+        output_path: PathBuf,
+        sample_rate: u32,
+        channels: u16,
+        dsp: RecordingDsp,
+        force_16k_mono: bool,
+    ) -> Result<(Self, u32, u16)> {
+        let convert = force_16k_mono && (sample_rate != WHISPER_SAMPLE_RATE || channels != 1);
+        let (file_rate, file_channels) = if convert { (WHISPER_SAMPLE_RATE, 1) } else { (sample_rate, channels) };
+
+        let writer = WavWriter::new(output_path, file_rate, file_channels)
+            .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+        let resampler = if convert {
+            println!("[start_recording] Converting {}Hz x{} to 16kHz mono while recording", sample_rate, channels);
+            Some(MonoResampler::new(sample_rate, channels)?)
+        } else {
+            None
+        };
+
+        let sink = Self {
+            writer: Arc::new(Mutex::new(writer)),
+            processor: DspProcessor::new(dsp, sample_rate, channels),
+            resampler,
+        };
+        Ok((sink, file_rate, file_channels))
+    }
+
+    fn write(&mut self, mut samples: Vec<f32>) {
+        self.processor.process(&mut samples);
+        let samples = match self.resampler.as_mut() {
+            Some(resampler) => resampler.process(&samples),
+            None => samples,
+        };
+        if let Ok(mut w) = self.writer.lock() {
+            let _ = w.write_samples(&samples);
+        }
+    }
+}
+
+/// Recording an armed pre-roll stream feeds while recording
+type PreRollSink = Arc<Mutex<Option<RecordingSink>>>;
+
+/// An input stream kept open between recordings so the moment before
+/// `start_recording` can be prepended to the file
+struct PreRoll {
+    _stream: Stream,
+    device_name: Option<String>,
+    sample_rate: u32,
+    channels: u16,
+    buffer: Arc<Mutex<PreRollBuffer>>,
+    sink: PreRollSink,
+}
+
 /// Simplified recorder state
 pub struct RecorderState {
     stream: Option<Stream>,
@@ -35,6 +138,7 @@ pub struct RecorderState {
     file_path: Option<PathBuf>,
     sample_rate: u32,
     channels: u16,
+    pre_roll: Option<PreRoll>,
 }
 
 impl RecorderState {
@@ -46,7 +150,87 @@ impl RecorderState {
             file_path: None,
             sample_rate: 0,
             channels: 0,
+            pre_roll: None,
+        }
+    }
+
+    /// Keep `seconds` of audio from `device_name` buffered so recordings start
+    /// slightly before `start_recording` is called; 0 turns the pre-roll off
+    ///
+    /// This keeps the microphone open while idle.
+    pub fn set_pre_roll(&mut self, device_name: Option<String>, seconds: f32) -> Result<()> {
+        if self.is_recording.load(Ordering::Relaxed) {
+            return Err("Can't change the pre-roll while recording".to_string());
         }
+
+        // Drop any existing stream before opening the device again
+        self.pre_roll = None;
+        if seconds <= 0.0 {
+            return Ok(());
+        }
+
+        let device = select_device(device_name.clone())?;
+        let config = get_optimal_config(&device)?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+
+        let buffer = Arc::new(Mutex::new(PreRollBuffer::new(seconds, sample_rate, channels)));
+        let sink: PreRollSink = Arc::new(Mutex::new(None));
+
+        let buffer_clone = buffer.clone();
+        let sink_clone = sink.clone();
+        let feed = move |samples: Vec<f32>| {
+            let Ok(mut sink) = sink_clone.lock() else { return };
+            match sink.as_mut() {
+                Some(recording) => recording.write(samples),
+                None => {
+                    if let Ok(mut b) = buffer_clone.lock() {
+                        b.push(&samples);
+                    }
+                }
+            }
+        };
+
+        let stream = build_input_stream(&device, &config, feed)?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start stream: {}", e))?;
+
+        println!("[set_pre_roll] Buffering {:.1}s from {:?}", seconds, device.name().unwrap_or_default());
+        self.pre_roll = Some(PreRoll {
+            _stream: stream,
+            device_name,
+            sample_rate,
+            channels,
+            buffer,
+            sink,
+        });
+
+        Ok(())
+    }
+
+    /// Start recording through the armed pre-roll stream, writing its buffer first
+    fn start_pre_roll_recording(&mut self, output_path: PathBuf, dsp: RecordingDsp, force_16k_mono: bool) -> Result<()> {
+        let pre_roll = self.pre_roll.as_ref().ok_or("Pre-roll is not active")?;
+
+        let (mut recording, sample_rate, channels) =
+            RecordingSink::create(output_path.clone(), pre_roll.sample_rate, pre_roll.channels, dsp, force_16k_mono)?;
+        let writer = recording.writer.clone();
+
+        // Hold the sink while draining so the callback can't slip samples in between
+        let mut sink = pre_roll.sink.lock().map_err(|e| e.to_string())?;
+        let buffered = pre_roll.buffer.lock().map_err(|e| e.to_string())?.drain();
+        recording.write(buffered);
+        *sink = Some(recording);
+        drop(sink);
+
+        self.file_path = Some(output_path);
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.writer = Some(writer);
+        self.is_recording.store(true, Ordering::Relaxed);
+
+        Ok(())
     }
 
     /// List available recording devices
@@ -70,36 +254,95 @@ impl RecorderState {
         Ok(devices)
     }
 
+    /// List available recording devices with their supported formats
+    /// Devices whose formats can't be queried are skipped
+    pub fn enumerate_devices_detailed(&self) -> Result<Vec<DetailedDeviceInfo>> {
+        let host = cpal::default_host();
+        let default_device = host
+            .default_input_device()
+            .and_then(|d| d.name().ok());
+
+        let devices = host
+            .input_devices()
+            .map_err(|e| format!("Failed to get input devices: {}", e))?
+            .filter_map(|device| {
+                let name = device.name().ok()?;
+                let ranges: Vec<SupportedStreamConfigRange> = match device.supported_input_configs() {
+                    Ok(configs) => configs.collect(),
+                    Err(e) => {
+                        eprintln!("[enumerate_devices_detailed] Skipping '{}': {}", name, e);
+                        return None;
+                    }
+                };
+
+                Some(DetailedDeviceInfo {
+                    is_default: default_device.as_ref() == Some(&name),
+                    name,
+                    configs: describe_configs(&ranges),
+                    supports_preferred_rate: ranges.iter().any(supports_preferred_rate),
+                })
+            })
+            .collect();
+
+        Ok(devices)
+    }
+
+    /// Report the format `start_recording` would use for a device, without opening a stream
+    pub fn probe_device(&self, device_name: Option<String>) -> Result<DeviceConfig> {
+        let device = select_device(device_name)?;
+        let config = get_optimal_config(&device)?;
+
+        Ok(DeviceConfig {
+            device_name: device.name().unwrap_or_default(),
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+            sample_format: config.sample_format().to_string(),
+        })
+    }
+
     /// Start recording audio
+    /// `dsp` filters the samples before they are written (no processing by default)
+    /// `force_16k_mono` converts the input to 16kHz mono as it's captured when the
+    /// device can't record that natively, so the file needs no resampling later
     pub fn start_recording(
         &mut self,
         device_name: Option<String>,
         output_path: PathBuf,
+        dsp: RecordingDsp,
+        force_16k_mono: bool,
     ) -> Result<()> {
         // Ensure we're not already recording
         if self.is_recording.load(Ordering::Relaxed) {
             return Err("Recording already in progress".to_string());
         }
 
+        // Record through the pre-roll stream when it's listening to this device
+        match &self.pre_roll {
+            Some(pre_roll) if pre_roll.device_name == device_name => {
+                return self.start_pre_roll_recording(output_path, dsp, force_16k_mono);
+            }
+            Some(_) => {
+                println!("[start_recording] Different device requested, turning pre-roll off");
+                self.pre_roll = None;
+            }
+            None => {}
+        }
+
         // Find the device
-        let host = cpal::default_host();
-        let device = if let Some(name) = device_name {
-            find_device(&host, &name)?
-        } else {
-            host.default_input_device()
-                .ok_or("No default input device available")?
-        };
+        let device = select_device(device_name)?;
 
         // Get optimal config for voice recording
         let config = get_optimal_config(&device)?;
-        let sample_format = config.sample_format();
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
 
         // Create WAV writer
-        let writer = WavWriter::new(output_path.clone(), sample_rate, channels)
-            .map_err(|e| format!("Failed to create WAV file: {}", e))?;
-        let writer = Arc::new(Mutex::new(writer));
+        let (mut recording, sample_rate, channels) = RecordingSink::create(
+            output_path.clone(),
+            config.sample_rate().0,
+            config.channels(),
+            dsp,
+            force_16k_mono,
+        )?;
+        let writer = recording.writer.clone();
 
         // Store recording metadata
         self.file_path = Some(output_path);
@@ -107,71 +350,12 @@ impl RecorderState {
         self.channels = channels;
         self.is_recording.store(true, Ordering::Relaxed);
 
-        // Create stream config
-        let stream_config = cpal::StreamConfig {
-            channels,
-            sample_rate: cpal::SampleRate(sample_rate),
-            buffer_size: cpal::BufferSize::Default,
-        };
-
-        // Clone for move into closure
-        let writer_clone = writer.clone();
         let is_recording = self.is_recording.clone();
-
-        // Create the audio stream based on sample format
-        let stream = match sample_format {
-            SampleFormat::F32 => device.build_input_stream(
-                &stream_config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer_clone.lock() {
-                            let _ = w.write_samples(data);
-                        }
-                    }
-                },
-                |err| eprintln!("Stream error: {}", err),
-                None,
-            ),
-            SampleFormat::I16 => device.build_input_stream(
-                &stream_config,
-                move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        // Convert i16 to f32
-                        let samples: Vec<f32> =
-                            data.iter().map(|&s| s as f32 / 32768.0).collect();
-                        if let Ok(mut w) = writer_clone.lock() {
-                            let _ = w.write_samples(&samples);
-                        }
-                    }
-                },
-                |err| eprintln!("Stream error: {}", err),
-                None,
-            ),
-            SampleFormat::U16 => device.build_input_stream(
-                &stream_config,
-                move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        // Convert u16 to f32
-                        let samples: Vec<f32> = data
-                            .iter()
-                            .map(|&s| (s as f32 - 32768.0) / 32768.0)
-                            .collect();
-                        if let Ok(mut w) = writer_clone.lock() {
-                            let _ = w.write_samples(&samples);
-                        }
-                    }
-                },
-                |err| eprintln!("Stream error: {}", err),
-                None,
-            ),
-            _ => {
-                return Err(format!(
-                    "Unsupported sample format: {:?}",
-                    sample_format
-                ))
+        let stream = build_input_stream(&device, &config, move |samples| {
+            if is_recording.load(Ordering::Relaxed) {
+                recording.write(samples);
             }
-        }
-        .map_err(|e| format!("Failed to build input stream: {}", e))?;
+        })?;
 
         // Start the stream
         stream
@@ -185,7 +369,8 @@ impl RecorderState {
     }
 
     /// Stop recording and return metadata
-    pub fn stop_recording(&mut self) -> Result<RecordingResult> {
+    /// With `normalize`, the finished file is rescaled to a consistent peak level
+    pub fn stop_recording(&mut self, normalize: bool) -> Result<RecordingResult> {
         // Ensure we're actually recording
         if !self.is_recording.load(Ordering::Relaxed) {
             return Err("No recording in progress".to_string());
@@ -199,8 +384,13 @@ impl RecorderState {
             drop(stream);
         }
 
+        // A pre-roll stream keeps running and goes back to buffering
+        if let Some(pre_roll) = &self.pre_roll {
+            pre_roll.sink.lock().map_err(|e| e.to_string())?.take();
+        }
+
         // Get metadata and finalize the WAV file
-        let (duration, file_path) = if let Some(writer_arc) = self.writer.take() {
+        let (duration, file_path, levels) = if let Some(writer_arc) = self.writer.take() {
             let duration = {
                 let w = writer_arc.lock().unwrap();
                 w.duration_seconds()
@@ -213,10 +403,28 @@ impl RecorderState {
                 .to_string_lossy()
                 .to_string();
 
-            // WAV file will be finalized automatically when writer is dropped
-            drop(writer_arc);
+            // The stream (and its clone of the writer) is gone, so we hold the last reference
+            match Arc::try_unwrap(writer_arc) {
+                Ok(writer) => writer
+                    .into_inner()
+                    .map_err(|e| e.to_string())?
+                    .finalize()?,
+                // Finalized when the remaining reference is dropped
+                Err(writer_arc) => drop(writer_arc),
+            }
+
+            // Measure what the microphone delivered, before any normalization
+            let levels = measure_wav_levels(Path::new(&file_path)).unwrap_or_else(|e| {
+                println!("[stop_recording] Could not measure levels: {}", e);
+                AudioLevels { peak: 0.0, rms: 0.0 }
+            });
+
+            if normalize {
+                let gain = normalize_wav(Path::new(&file_path), NORMALIZE_TARGET_PEAK)?;
+                println!("[stop_recording] Normalized with gain {:.2}", gain);
+            }
 
-            (duration, file_path)
+            (duration, file_path, levels)
         } else {
             return Err("No writer available".to_string());
         };
@@ -226,15 +434,84 @@ impl RecorderState {
             sample_rate: self.sample_rate,
             channels: self.channels,
             duration_seconds: duration,
+            peak_amplitude: levels.peak,
+            rms_amplitude: levels.rms,
         })
     }
 
+    /// Flush the in-progress recording so its file can be read while recording continues
+    /// Returns the file path and the number of samples readable from it
+    pub fn snapshot_recording(&self) -> Result<(PathBuf, u64)> {
+        let writer = self.writer.as_ref().ok_or("No recording in progress")?;
+        let file_path = self.file_path.clone().ok_or("No file path recorded")?;
+
+        let mut w = writer.lock().map_err(|e| e.to_string())?;
+        w.flush()?;
+        Ok((file_path, w.samples_written()))
+    }
+
     /// Check if currently recording
     pub fn is_recording(&self) -> bool {
         self.is_recording.load(Ordering::Relaxed)
     }
 }
 
+/// Build an input stream that hands every callback buffer to `feed` as f32 samples
+fn build_input_stream<F>(device: &Device, config: &SupportedStreamConfig, mut feed: F) -> Result<Stream>
+where
+    F: FnMut(Vec<f32>) + Send + 'static,
+{
+    let stream_config = cpal::StreamConfig {
+        channels: config.channels(),
+        sample_rate: config.sample_rate(),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    match config.sample_format() {
+        SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| feed(data.to_vec()),
+            |err| eprintln!("Stream error: {}", err),
+            None,
+        ),
+        SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                feed(data.iter().map(|&s| s as f32 / 32768.0).collect())
+            },
+            |err| eprintln!("Stream error: {}", err),
+            None,
+        ),
+        SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                feed(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0).collect())
+            },
+            |err| eprintln!("Stream error: {}", err),
+            None,
+        ),
+        sample_format => return Err(format!("Unsupported sample format: {:?}", sample_format)),
+    }
+    .map_err(|e| format!("Failed to build input stream: {}", e))
+}
+
+/// The saved preferred device if it's among `devices`, otherwise None (the
+/// system default), e.g. after the preferred microphone was unplugged
+pub fn preferred_or_default(preferred: Option<String>, devices: &[DeviceInfo]) -> Option<String> {
+    preferred.filter(|name| devices.iter().any(|device| &device.name == name))
+}
+
+/// The named input device, or the default one
+fn select_device(device_name: Option<String>) -> Result<Device> {
+    let host = cpal::default_host();
+    match device_name {
+        Some(name) => find_device(&host, &name),
+        None => host
+            .default_input_device()
+            .ok_or_else(|| "No default input device available".to_string()),
+    }
+}
+
 /// Find a device by name
 fn find_device(host: &cpal::Host, name: &str) -> Result<Device> {
     host.input_devices()
@@ -243,24 +520,170 @@ fn find_device(host: &cpal::Host, name: &str) -> Result<Device> {
         .ok_or_else(|| format!("Device '{}' not found", name))
 }
 
-/// Get optimal config for voice recording (prefer 16kHz mono, fallback to 48kHz)
-fn get_optimal_config(device: &Device) -> Result<cpal::SupportedStreamConfig> {
+/// Sample rate Whisper works with, so recording at it avoids resampling
+const PREFERRED_SAMPLE_RATE: u32 = WHISPER_SAMPLE_RATE;
+
+/// Get optimal config for voice recording (see `choose_config`)
+fn get_optimal_config(device: &Device) -> Result<SupportedStreamConfig> {
     let supported_configs = device
         .supported_input_configs()
         .map_err(|e| format!("Failed to get supported configs: {}", e))?;
 
-    // Try to find 16kHz mono config (ideal for voice/Whisper)
-    for config in supported_configs {
-        if config.channels() == 1 {
-            let sample_rate = config.min_sample_rate().0;
-            if sample_rate == 16000 {
-                return Ok(config.with_sample_rate(cpal::SampleRate(16000)));
-            }
+    choose_config(supported_configs, || {
+        device
+            .default_input_config()
+            .map_err(|e| format!("Failed to get default config: {}", e))
+    })
+}
+
+/// Pick a recording config from a device's supported ranges
+///
+/// Preference order: 16kHz mono, then 16kHz with any channel count, then the
+/// device's default config. Only sample formats the recorder can convert are considered.
+fn choose_config(
+    supported: impl IntoIterator<Item = SupportedStreamConfigRange>,
+    default_config: impl FnOnce() -> Result<SupportedStreamConfig>,
+) -> Result<SupportedStreamConfig> {
+    let candidates: Vec<SupportedStreamConfigRange> =
+        supported.into_iter().filter(supports_preferred_rate).collect();
+    let chosen = candidates
+        .iter()
+        .find(|c| c.channels() == 1)
+        .or_else(|| candidates.iter().min_by_key(|c| c.channels()))
+        .copied();
+
+    match chosen {
+        Some(config) => Ok(config.with_sample_rate(cpal::SampleRate(PREFERRED_SAMPLE_RATE))),
+        None => default_config(),
+    }
+}
+
+/// Whether a range covers 16kHz in a sample format the recorder can convert
+fn supports_preferred_rate(config: &SupportedStreamConfigRange) -> bool {
+    matches!(config.sample_format(), SampleFormat::F32 | SampleFormat::I16 | SampleFormat::U16)
+        && config.min_sample_rate().0 <= PREFERRED_SAMPLE_RATE
+        && config.max_sample_rate().0 >= PREFERRED_SAMPLE_RATE
+}
+
+/// Map cpal's config ranges to the frontend shape, dropping duplicates
+fn describe_configs(ranges: &[SupportedStreamConfigRange]) -> Vec<SupportedConfigInfo> {
+    let mut configs: Vec<SupportedConfigInfo> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        let info = SupportedConfigInfo {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            sample_format: range.sample_format().to_string(),
+        };
+        if !configs.contains(&info) {
+            configs.push(info);
         }
     }
+    configs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpal::{SampleRate, SupportedBufferSize};
+
+    fn range(channels: u16, min: u32, max: u32, format: SampleFormat) -> SupportedStreamConfigRange {
+        SupportedStreamConfigRange::new(
+            channels,
+            SampleRate(min),
+            SampleRate(max),
+            SupportedBufferSize::Unknown,
+            format,
+        )
+    }
 
-    // Fallback to default config
-    device
-        .default_input_config()
-        .map_err(|e| format!("Failed to get default config: {}", e))
+    fn default_48k() -> Result<SupportedStreamConfig> {
+        Ok(SupportedStreamConfig::new(
+            2,
+            SampleRate(48000),
+            SupportedBufferSize::Unknown,
+            SampleFormat::F32,
+        ))
+    }
+
+    #[test]
+    fn test_describe_configs() {
+        let ranges = vec![
+            range(1, 8000, 48000, SampleFormat::I16),
+            range(2, 44100, 44100, SampleFormat::F32),
+            range(1, 8000, 48000, SampleFormat::I16),
+        ];
+
+        let configs = describe_configs(&ranges);
+        assert_eq!(
+            configs,
+            vec![
+                SupportedConfigInfo {
+                    channels: 1,
+                    min_sample_rate: 8000,
+                    max_sample_rate: 48000,
+                    sample_format: "i16".to_string(),
+                },
+                SupportedConfigInfo {
+                    channels: 2,
+                    min_sample_rate: 44100,
+                    max_sample_rate: 44100,
+                    sample_format: "f32".to_string(),
+                },
+            ]
+        );
+
+        assert!(supports_preferred_rate(&ranges[0]));
+        assert!(!supports_preferred_rate(&ranges[1]));
+    }
+
+    #[test]
+    fn test_choose_config_prefers_16k_mono() {
+        let config = choose_config(
+            vec![
+                range(2, 8000, 48000, SampleFormat::F32),
+                range(1, 8000, 48000, SampleFormat::I16),
+            ],
+            default_48k,
+        )
+        .unwrap();
+
+        assert_eq!(config.sample_rate().0, 16000);
+        assert_eq!(config.channels(), 1);
+        assert_eq!(config.sample_format(), SampleFormat::I16);
+    }
+
+    #[test]
+    fn test_choose_config_falls_back_to_16k_stereo_then_default() {
+        let config = choose_config(vec![range(2, 8000, 48000, SampleFormat::F32)], default_48k).unwrap();
+        assert_eq!((config.sample_rate().0, config.channels()), (16000, 2));
+
+        // No 16kHz support (or only unsupported sample formats): use the device default
+        let config = choose_config(
+            vec![
+                range(1, 44100, 48000, SampleFormat::F32),
+                range(1, 8000, 48000, SampleFormat::I32),
+            ],
+            default_48k,
+        )
+        .unwrap();
+        assert_eq!((config.sample_rate().0, config.channels()), (48000, 2));
+    }
+
+    #[test]
+    fn test_preferred_device_falls_back_when_missing() {
+        let devices = vec![
+            DeviceInfo { name: "Built-in Microphone".to_string(), is_default: true },
+            DeviceInfo { name: "USB Headset".to_string(), is_default: false },
+        ];
+
+        assert_eq!(
+            preferred_or_default(Some("USB Headset".to_string()), &devices),
+            Some("USB Headset".to_string())
+        );
+        // Unplugged (or renamed) device: record from the system default instead
+        assert_eq!(preferred_or_default(Some("Blue Yeti".to_string()), &devices), None);
+        assert_eq!(preferred_or_default(Some("Blue Yeti".to_string()), &[]), None);
+        assert_eq!(preferred_or_default(None, &devices), None);
+    }
 }
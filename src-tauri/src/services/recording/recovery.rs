@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Extension of the marker written next to a recording while it's in progress
+const MARKER_EXTENSION: &str = "recording";
+
+/// A recording that was in progress, as left behind in its marker file
+/// A marker that still exists at startup means the app quit without stopping it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingMarker {
+    pub session_id: String,
+    pub audio_path: String,
+    pub started_at: i64,
+}
+
+/// Marker path for a recording ("<id>.wav" -> "<id>.recording")
+fn marker_path(audio_path: &Path) -> PathBuf {
+    audio_path.with_extension(MARKER_EXTENSION)
+}
+
+/// Note that `audio_path` is being recorded for `session_id`
+pub fn write_marker(session_id: &str, audio_path: &Path) -> Result<(), String> {
+    let marker = RecordingMarker {
+        session_id: session_id.to_string(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    let json = serde_json::to_string(&marker).map_err(|e| e.to_string())?;
+
+    std::fs::write(marker_path(audio_path), json).map_err(|e| format!("Failed to write recording marker: {}", e))
+}
+
+/// Remove the marker once the recording has been stopped or recovered
+pub fn remove_marker(audio_path: &Path) {
+    let _ = std::fs::remove_file(marker_path(audio_path));
+}
+
+/// Recordings in `audio_dir` whose marker was never removed
+/// Markers that can't be read are skipped.
+pub fn find_markers(audio_dir: &Path) -> Vec<RecordingMarker> {
+    let Ok(entries) = std::fs::read_dir(audio_dir) else {
+        return Vec::new();
+    };
+
+    let mut markers: Vec<RecordingMarker> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == MARKER_EXTENSION))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+
+    markers.sort_by_key(|marker| marker.started_at);
+    markers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers_track_unfinished_recordings() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.wav");
+        let second = dir.path().join("second.wav");
+
+        write_marker("first", &first).unwrap();
+        write_marker("second", &second).unwrap();
+        std::fs::write(dir.path().join("broken.recording"), "{").unwrap();
+
+        let ids: Vec<String> = find_markers(dir.path()).into_iter().map(|m| m.session_id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"first".to_string()));
+
+        remove_marker(&first);
+        let markers = find_markers(dir.path());
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].session_id, "second");
+        assert_eq!(Path::new(&markers[0].audio_path), second);
+
+        assert!(find_markers(&dir.path().join("missing")).is_empty());
+    }
+}
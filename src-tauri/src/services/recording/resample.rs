@@ -0,0 +1,114 @@
+use rubato::{Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction};
+
+/// Sample rate Whisper expects
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Input frames the streaming resampler works on at a time
+const CHUNK_FRAMES: usize = 1024;
+
+/// Mono sinc resampler from `input_rate` to `output_rate`, taking `chunk_size` frames per call
+/// Shared by recording and the conversion before transcription.
+pub fn sinc_resampler(input_rate: u32, output_rate: u32, chunk_size: usize) -> Result<SincFixedIn<f32>, String> {
+    let params = SincInterpolationParameters {
+        sinc_len: 64,
+        f_cutoff: 0.95,
+        interpolation: SincInterpolationType::Linear,
+        oversampling_factor: 128,
+        window: WindowFunction::BlackmanHarris2,
+    };
+
+    SincFixedIn::<f32>::new(
+        output_rate as f64 / input_rate as f64,
+        8.0,
+        params,
+        chunk_size,
+        1, // mono
+    )
+    .map_err(|e| format!("Failed to create resampler: {}", e))
+}
+
+/// Converts interleaved input of any rate and channel count to 16kHz mono as it arrives
+///
+/// Input is buffered until a whole chunk can be resampled, so up to one chunk
+/// (~20ms at 48kHz) is held back at any time.
+pub struct MonoResampler {
+    channels: usize,
+    /// None when the input is already at 16kHz
+    resampler: Option<SincFixedIn<f32>>,
+    pending: Vec<f32>,
+}
+
+impl MonoResampler {
+    pub fn new(input_rate: u32, channels: u16) -> Result<Self, String> {
+        let resampler = if input_rate == WHISPER_SAMPLE_RATE {
+            None
+        } else {
+            Some(sinc_resampler(input_rate, WHISPER_SAMPLE_RATE, CHUNK_FRAMES)?)
+        };
+
+        Ok(Self {
+            channels: channels.max(1) as usize,
+            resampler,
+            pending: Vec::with_capacity(CHUNK_FRAMES * 2),
+        })
+    }
+
+    /// Downmix and resample a callback buffer, returning the 16kHz mono samples now ready
+    pub fn process(&mut self, interleaved: &[f32]) -> Vec<f32> {
+        let channels = self.channels;
+        self.pending.extend(
+            interleaved
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+
+        let Some(resampler) = self.resampler.as_mut() else {
+            return std::mem::take(&mut self.pending);
+        };
+
+        let mut output = Vec::new();
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= CHUNK_FRAMES {
+            let chunk = vec![self.pending[consumed..consumed + CHUNK_FRAMES].to_vec()];
+            match resampler.process(&chunk, None) {
+                Ok(waves_out) => output.extend_from_slice(&waves_out[0]),
+                Err(e) => eprintln!("[MonoResampler] Resampling failed: {}", e),
+            }
+            consumed += CHUNK_FRAMES;
+        }
+        self.pending.drain(..consumed);
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_48k_stereo_becomes_16k_mono() {
+        let mut resampler = MonoResampler::new(48000, 2).unwrap();
+
+        // 1 second of 48kHz stereo in 10ms callbacks; left 0.5, right 0.3
+        let callback: Vec<f32> = [0.5, 0.3].repeat(480);
+        let mut output = Vec::new();
+        for _ in 0..100 {
+            output.extend(resampler.process(&callback));
+        }
+
+        // All but the held-back partial chunk (at most 1024 frames / 3)
+        assert!(output.len() <= 16000 && output.len() >= 16000 - 342, "{}", output.len());
+        // Channels are averaged; the sinc filter has settled by the middle
+        assert!((output[8000] - 0.4).abs() < 0.01, "{}", output[8000]);
+    }
+
+    #[test]
+    fn test_16k_mono_passes_through() {
+        let mut resampler = MonoResampler::new(16000, 1).unwrap();
+        assert_eq!(resampler.process(&[0.1, 0.2, 0.3]), vec![0.1, 0.2, 0.3]);
+
+        let mut stereo = MonoResampler::new(16000, 2).unwrap();
+        assert_eq!(stereo.process(&[0.2, 0.4, 1.0, 0.0]), vec![0.3, 0.5]);
+    }
+}
@@ -1,5 +1,5 @@
-use hound::{WavSpec, WavWriter as HoundWriter};
-use std::path::PathBuf;
+use hound::{WavReader, WavSpec, WavWriter as HoundWriter};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Thread-safe WAV file writer for real-time audio recording
@@ -12,7 +12,15 @@ pub struct WavWriter {
 
 impl WavWriter {
     /// Create a new WAV file writer
+    /// A zero sample rate or channel count is rejected before the file is created
     pub fn new(path: PathBuf, sample_rate: u32, channels: u16) -> Result<Self, String> {
+        if sample_rate == 0 {
+            return Err("Invalid WAV format: sample rate must be greater than 0 Hz".to_string());
+        }
+        if channels == 0 {
+            return Err("Invalid WAV format: channel count must be greater than 0".to_string());
+        }
+
         let spec = WavSpec {
             channels,
             sample_rate,
@@ -52,11 +60,516 @@ impl WavWriter {
         self.samples_written.load(Ordering::Relaxed)
     }
 
-    /// Get the duration in seconds
+    /// Get the duration in seconds (0 for a format without samples per second)
     pub fn duration_seconds(&self) -> f32 {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return 0.0;
+        }
+
         let total_samples = self.samples_written();
         let frames = total_samples / self.channels as u64;
         frames as f32 / self.sample_rate as f32
     }
 
+    /// Update the WAV header for the samples written so far and flush them to disk
+    /// The file stays open for writing; readers then see a valid WAV of the current length
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush WAV file: {}", e))
+    }
+
+    /// Write the WAV header and flush the file
+    pub fn finalize(self) -> Result<(), String> {
+        self.writer
+            .finalize()
+            .map_err(|e| format!("Failed to finalize WAV file: {}", e))
+    }
+}
+
+/// Copy the first `max_samples` samples (all if None) of a WAV file into a new file
+///
+/// Works on a recording that is still being written as long as its writer was flushed:
+/// only the samples the header accounts for are read. The copy is cut to whole frames.
+/// Returns the number of samples copied.
+pub fn copy_partial_wav(source: &Path, dest: &Path, max_samples: Option<u64>) -> Result<u64, String> {
+    let mut reader = WavReader::open(source).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err("Only 16-bit PCM recordings can be copied".to_string());
+    }
+
+    let available = reader.len() as u64;
+    let limit = max_samples.map_or(available, |max| max.min(available));
+    let limit = limit - limit % spec.channels as u64;
+
+    let mut writer = HoundWriter::create(dest, spec).map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    for sample in reader.samples::<i16>().take(limit as usize) {
+        let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))?;
+        writer
+            .write_sample(sample)
+            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+
+    Ok(limit)
+}
+
+/// Copy the part of a 16-bit WAV file between `start_seconds` and `end_seconds`
+/// (the end of the file if None) into a new file
+/// Returns the number of samples copied.
+pub fn trim_wav(source: &Path, dest: &Path, start_seconds: f32, end_seconds: Option<f32>) -> Result<u64, String> {
+    let mut reader = WavReader::open(source).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err("Only 16-bit PCM recordings can be trimmed".to_string());
+    }
+
+    let total_frames = reader.duration() as u64;
+    let to_frame = |seconds: f32| ((seconds.max(0.0) * spec.sample_rate as f32).round() as u64).min(total_frames);
+    let start_frame = to_frame(start_seconds);
+    let end_frame = end_seconds.map_or(total_frames, to_frame).max(start_frame);
+
+    reader
+        .seek(start_frame as u32)
+        .map_err(|e| format!("Failed to seek in WAV file: {}", e))?;
+
+    let count = (end_frame - start_frame) * spec.channels as u64;
+    let mut writer = HoundWriter::create(dest, spec).map_err(|e| format!("Failed to create WAV file: {}", e))?;
+    for sample in reader.samples::<i16>().take(count as usize) {
+        let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))?;
+        writer
+            .write_sample(sample)
+            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+
+    Ok(count)
+}
+
+/// Fix the RIFF and data chunk sizes of a WAV file that was never finalized
+/// (e.g. the app crashed mid-recording), so it can be read again
+/// The data chunk is taken to run to the end of the file; a trailing partial frame
+/// is cut off. Returns the recording's duration in seconds.
+pub fn repair_wav(path: &Path) -> Result<f32, String> {
+    let mut bytes = std::fs::read(path).map_err(|e| format!("Failed to read WAV file: {}", e))?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(format!("Not a WAV file: {}", path.display()));
+    }
+
+    let read_u32 = |bytes: &[u8], at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+
+    // Walk the chunks up to "data", remembering the frame size from "fmt "
+    let mut block_align = None;
+    let mut pos = 12;
+    let data_start = loop {
+        if pos + 8 > bytes.len() {
+            return Err("WAV file has no data chunk".to_string());
+        }
+        let id = &bytes[pos..pos + 4];
+        let size = read_u32(&bytes, pos + 4) as usize;
+        if id == b"data" {
+            break pos + 8;
+        }
+        if id == b"fmt " && pos + 22 <= bytes.len() {
+            block_align = Some(u16::from_le_bytes([bytes[pos + 20], bytes[pos + 21]]) as usize);
+        }
+        pos += 8 + size + (size & 1);
+    };
+    let block_align = block_align
+        .filter(|&align| align > 0)
+        .ok_or_else(|| "WAV file has no valid format chunk".to_string())?;
+
+    let available = bytes.len() - data_start;
+    let data_len = available - available % block_align;
+    bytes.truncate(data_start + data_len);
+    bytes[data_start - 4..data_start].copy_from_slice(&(data_len as u32).to_le_bytes());
+    let riff_len = (bytes.len() - 8) as u32;
+    bytes[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+    std::fs::write(path, &bytes).map_err(|e| format!("Failed to write WAV file: {}", e))?;
+
+    let reader = WavReader::open(path).map_err(|e| format!("WAV file is still unreadable: {}", e))?;
+    Ok(reader.duration() as f32 / reader.spec().sample_rate as f32)
+}
+
+/// Check that `path` is an absolute path to a readable WAV file before transcribing it
+/// Other formats (mp3, m4a, ...) are rejected since only WAV can be decoded.
+pub fn check_wav_input(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("Audio path must be absolute: {}", path.display()));
+    }
+    if !path.is_file() {
+        return Err(format!("Audio file not found: {}", path.display()));
+    }
+
+    WavReader::open(path)
+        .map(|_| ())
+        .map_err(|e| format!("Unsupported audio file {} (only WAV can be transcribed): {}", path.display(), e))
+}
+
+/// Input level of a recording, both 0.0-1.0 of full scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioLevels {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+/// Read a 16-bit WAV file back and measure its peak and RMS level
+pub fn measure_wav_levels(path: &Path) -> Result<AudioLevels, String> {
+    let mut reader = WavReader::open(path).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err("Only 16-bit PCM recordings can be measured".to_string());
+    }
+
+    let mut peak: i32 = 0;
+    let mut sum_squares: f64 = 0.0;
+    let mut count: u64 = 0;
+    for sample in reader.samples::<i16>() {
+        let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))? as i32;
+        peak = peak.max(sample.abs());
+        sum_squares += (sample as f64 / 32768.0).powi(2);
+        count += 1;
+    }
+
+    if count == 0 {
+        return Ok(AudioLevels { peak: 0.0, rms: 0.0 });
+    }
+
+    Ok(AudioLevels {
+        peak: (peak as f32 / 32768.0).min(1.0),
+        rms: (sum_squares / count as f64).sqrt() as f32,
+    })
+}
+
+/// Default level (0.0-1.0 of full scale, about -40 dBFS) a sample must exceed to count as audio
+pub const DEFAULT_AUDIBLE_THRESHOLD: f32 = 0.01;
+
+/// Share of samples that must exceed the threshold, so a click or pop in an
+/// otherwise silent (e.g. muted) recording doesn't count as speech
+const AUDIBLE_MIN_FRACTION: f64 = 0.005;
+
+/// Whether a WAV file contains meaningful audio: at least `AUDIBLE_MIN_FRACTION` of
+/// its samples above `threshold` (0.0-1.0 of full scale)
+/// Empty and all-zero recordings (e.g. from a muted microphone) aren't audible.
+pub fn check_recording_audible(path: &Path, threshold: f32) -> Result<bool, String> {
+    let mut reader = WavReader::open(path).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let spec = reader.spec();
+
+    let mut loud: u64 = 0;
+    let mut count: u64 = 0;
+    let mut tally = |level: f32| {
+        if level > threshold {
+            loud += 1;
+        }
+        count += 1;
+    };
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for sample in reader.samples::<f32>() {
+                tally(sample.map_err(|e| format!("Failed to read sample: {}", e))?.abs());
+            }
+        }
+        hound::SampleFormat::Int => {
+            let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            for sample in reader.samples::<i32>() {
+                let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))?;
+                tally(sample.unsigned_abs() as f32 / full_scale);
+            }
+        }
+    }
+
+    Ok(count > 0 && loud as f64 / count as f64 >= AUDIBLE_MIN_FRACTION)
+}
+
+/// Peak level `normalize_wav` scales recordings to (about -1 dBFS)
+pub const NORMALIZE_TARGET_PEAK: f32 = 0.9;
+
+/// Largest gain `normalize_wav` applies, so near-silent recordings don't turn into loud noise
+const NORMALIZE_MAX_GAIN: f32 = 10.0;
+
+/// Scale a 16-bit WAV file so its peak reaches `target_peak` (0.0-1.0)
+///
+/// Streams the file twice (find the peak, then rewrite through a temp file) so long
+/// recordings aren't loaded into memory. Returns the gain that was applied.
+pub fn normalize_wav(path: &Path, target_peak: f32) -> Result<f32, String> {
+    let mut reader = WavReader::open(path).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err("Only 16-bit PCM recordings can be normalized".to_string());
+    }
+
+    let mut peak: i32 = 0;
+    for sample in reader.samples::<i16>() {
+        let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))?;
+        peak = peak.max((sample as i32).abs());
+    }
+
+    if peak == 0 {
+        return Ok(1.0);
+    }
+
+    let gain = (target_peak.clamp(0.0, 1.0) * 32767.0 / peak as f32).min(NORMALIZE_MAX_GAIN);
+    if (gain - 1.0).abs() < 0.01 {
+        return Ok(1.0);
+    }
+
+    let temp_path = path.with_extension("wav.normalizing");
+    let mut reader = WavReader::open(path).map_err(|e| format!("Failed to open WAV file: {}", e))?;
+    let mut writer = HoundWriter::create(&temp_path, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+
+    for sample in reader.samples::<i16>() {
+        let sample = sample.map_err(|e| format!("Failed to read sample: {}", e))?;
+        let scaled = (sample as f32 * gain).round().clamp(-32768.0, 32767.0) as i16;
+        writer
+            .write_sample(scaled)
+            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    }
+
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace WAV file: {}", e))?;
+
+    Ok(gain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(path: &Path) -> i16 {
+        WavReader::open(path)
+            .unwrap()
+            .samples::<i16>()
+            .map(|s| s.unwrap().saturating_abs())
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wav_writer_rejects_empty_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid.wav");
+
+        let err = WavWriter::new(path.clone(), 0, 1).err().unwrap();
+        assert!(err.contains("sample rate"), "{}", err);
+        let err = WavWriter::new(path.clone(), 16000, 0).err().unwrap();
+        assert!(err.contains("channel count"), "{}", err);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_wav_writer_duration() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // 1.5s of 48kHz stereo
+        let mut writer = WavWriter::new(dir.path().join("stereo.wav"), 48000, 2).unwrap();
+        assert_eq!(writer.duration_seconds(), 0.0);
+        writer.write_samples(&vec![0.0; 48000 * 2 * 3 / 2]).unwrap();
+        assert_eq!(writer.samples_written(), 144000);
+        assert_eq!(writer.duration_seconds(), 1.5);
+    }
+
+    #[test]
+    fn test_normalize_wav_scales_quiet_recording_to_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quiet.wav");
+
+        // Half a second of a 440 Hz tone peaking at 0.2
+        let mut writer = WavWriter::new(path.clone(), 16000, 1).unwrap();
+        let samples: Vec<f32> = (0..8000)
+            .map(|i| 0.2 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect();
+        writer.write_samples(&samples).unwrap();
+        writer.finalize().unwrap();
+
+        let gain = normalize_wav(&path, NORMALIZE_TARGET_PEAK).unwrap();
+        assert!((gain - 4.5).abs() < 0.05, "unexpected gain {}", gain);
+
+        let target = (NORMALIZE_TARGET_PEAK * 32767.0) as i16;
+        let normalized = peak(&path);
+        assert!((normalized - target).abs() <= 1, "peak {} != {}", normalized, target);
+        assert!(normalized < i16::MAX, "normalized audio clipped");
+
+        // Length and format are unchanged
+        let reader = WavReader::open(&path).unwrap();
+        assert_eq!(reader.duration(), 8000);
+        assert_eq!(reader.spec().sample_rate, 16000);
+    }
+
+    #[test]
+    fn test_copy_partial_wav_from_open_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.wav");
+        let partial = dir.path().join("partial.wav");
+
+        let mut writer = WavWriter::new(path.clone(), 16000, 2).unwrap();
+        let samples: Vec<f32> = (0..3200).map(|i| (i % 100) as f32 / 200.0).collect();
+        writer.write_samples(&samples).unwrap();
+        writer.flush().unwrap();
+
+        // Still recording: the copy holds everything flushed so far
+        writer.write_samples(&[0.5; 4]).unwrap();
+        let copied = copy_partial_wav(&path, &partial, Some(writer.samples_written())).unwrap();
+        assert_eq!(copied, 3200);
+
+        let mut reader = WavReader::open(&partial).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        let read: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(read.len(), 3200);
+        assert_eq!(read[99], (99.0 / 200.0 * 32767.0) as i16);
+
+        // Limits are rounded down to whole frames
+        assert_eq!(copy_partial_wav(&path, &partial, Some(1001)).unwrap(), 1000);
+        assert_eq!(WavReader::open(&partial).unwrap().len(), 1000);
+
+        writer.finalize().unwrap();
+        assert_eq!(copy_partial_wav(&path, &partial, None).unwrap(), 3204);
+    }
+
+    fn write_tone(path: &Path, amplitude: f32) {
+        let mut writer = WavWriter::new(path.to_path_buf(), 16000, 1).unwrap();
+        let samples: Vec<f32> = (0..16000)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16000.0).sin())
+            .collect();
+        writer.write_samples(&samples).unwrap();
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_measure_wav_levels_quiet_vs_loud() {
+        let dir = tempfile::tempdir().unwrap();
+        let quiet = dir.path().join("quiet.wav");
+        let loud = dir.path().join("loud.wav");
+        write_tone(&quiet, 0.02);
+        write_tone(&loud, 0.8);
+
+        // A sine wave's RMS is its amplitude / sqrt(2)
+        let quiet_levels = measure_wav_levels(&quiet).unwrap();
+        assert!((quiet_levels.peak - 0.02).abs() < 0.001, "{:?}", quiet_levels);
+        assert!((quiet_levels.rms - 0.02 / 2f32.sqrt()).abs() < 0.001, "{:?}", quiet_levels);
+
+        let loud_levels = measure_wav_levels(&loud).unwrap();
+        assert!((loud_levels.peak - 0.8).abs() < 0.001, "{:?}", loud_levels);
+        assert!((loud_levels.rms - 0.8 / 2f32.sqrt()).abs() < 0.001, "{:?}", loud_levels);
+    }
+
+    #[test]
+    fn test_check_recording_audible_silent_vs_spoken() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let muted = dir.path().join("muted.wav");
+        let mut writer = WavWriter::new(muted.clone(), 16000, 1).unwrap();
+        writer.write_samples(&[0.0; 16000]).unwrap();
+        writer.finalize().unwrap();
+        assert!(!check_recording_audible(&muted, DEFAULT_AUDIBLE_THRESHOLD).unwrap());
+
+        // Silence with a single click is still silent
+        let click = dir.path().join("click.wav");
+        let mut samples = vec![0.0; 16000];
+        samples[8000] = 0.9;
+        let mut writer = WavWriter::new(click.clone(), 16000, 1).unwrap();
+        writer.write_samples(&samples).unwrap();
+        writer.finalize().unwrap();
+        assert!(!check_recording_audible(&click, DEFAULT_AUDIBLE_THRESHOLD).unwrap());
+
+        let empty = dir.path().join("empty.wav");
+        WavWriter::new(empty.clone(), 16000, 1).unwrap().finalize().unwrap();
+        assert!(!check_recording_audible(&empty, DEFAULT_AUDIBLE_THRESHOLD).unwrap());
+
+        let spoken = dir.path().join("spoken.wav");
+        write_tone(&spoken, 0.3);
+        assert!(check_recording_audible(&spoken, DEFAULT_AUDIBLE_THRESHOLD).unwrap());
+        // Too quiet for a stricter threshold
+        assert!(!check_recording_audible(&spoken, 0.5).unwrap());
+    }
+
+    #[test]
+    fn test_normalize_wav_leaves_silence_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("silent.wav");
+
+        let mut writer = WavWriter::new(path.clone(), 16000, 1).unwrap();
+        writer.write_samples(&[0.0; 1600]).unwrap();
+        writer.finalize().unwrap();
+
+        assert_eq!(normalize_wav(&path, NORMALIZE_TARGET_PEAK).unwrap(), 1.0);
+        assert_eq!(peak(&path), 0);
+    }
+
+    #[test]
+    fn test_trim_wav_copies_time_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.wav");
+        let trimmed = dir.path().join("trimmed.wav");
+
+        // 1 second of stereo at 100 Hz; both channels hold the frame number
+        let mut writer = WavWriter::new(path.clone(), 100, 2).unwrap();
+        let samples: Vec<f32> = (0..100).flat_map(|i| [i as f32 / 1000.0; 2]).collect();
+        writer.write_samples(&samples).unwrap();
+        writer.finalize().unwrap();
+
+        assert_eq!(trim_wav(&path, &trimmed, 0.25, Some(0.5)).unwrap(), 50);
+        let read: Vec<i16> = WavReader::open(&trimmed).unwrap().samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(read.len(), 50);
+        assert_eq!(read[0], (25.0 / 1000.0 * 32767.0) as i16);
+        assert_eq!(read[0], read[1]);
+
+        // Open-ended, and clamped to the file's length
+        assert_eq!(trim_wav(&path, &trimmed, 0.9, None).unwrap(), 20);
+        assert_eq!(trim_wav(&path, &trimmed, 0.9, Some(5.0)).unwrap(), 20);
+    }
+
+    #[test]
+    fn test_repair_wav_fixes_unfinalized_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("crashed.wav");
+
+        let mut writer = WavWriter::new(path.clone(), 100, 2).unwrap();
+        writer.write_samples(&[0.25; 200]).unwrap();
+        writer.finalize().unwrap();
+
+        // Simulate a crash: header sizes never written, half a frame at the end
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+        let data_size_at = bytes.windows(4).position(|w| w == b"data").unwrap() + 4;
+        bytes[data_size_at..data_size_at + 4].copy_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2]);
+        std::fs::write(&path, &bytes).unwrap();
+        let unreadable = WavReader::open(&path).map(|r| r.len()).unwrap_or(0);
+        assert_eq!(unreadable, 0);
+
+        let duration = repair_wav(&path).unwrap();
+        assert!((duration - 1.0).abs() < 1e-6);
+        let samples: Vec<i16> = WavReader::open(&path).unwrap().samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(samples.len(), 200);
+        assert_eq!(samples[0], (0.25 * 32767.0) as i16);
+
+        std::fs::write(&path, b"not a wav").unwrap();
+        assert!(repair_wav(&path).is_err());
+    }
+
+    #[test]
+    fn test_check_wav_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let wav = dir.path().join("clip.wav");
+        let mut writer = WavWriter::new(wav.clone(), 16000, 1).unwrap();
+        writer.write_samples(&[0.1; 160]).unwrap();
+        writer.finalize().unwrap();
+        assert!(check_wav_input(&wav).is_ok());
+
+        let mp3 = dir.path().join("clip.mp3");
+        std::fs::write(&mp3, b"ID3\x03\x00not really audio").unwrap();
+        assert!(check_wav_input(&mp3).unwrap_err().contains("only WAV"));
+
+        assert!(check_wav_input(&dir.path().join("missing.wav")).unwrap_err().contains("not found"));
+        assert!(check_wav_input(Path::new("clip.wav")).unwrap_err().contains("absolute"));
+    }
 }
@@ -4,19 +4,32 @@
  * Handles creation and management of recording sessions, including:
  * - Creating new sessions
  * - Processing transcripts and extracting words
- * - Calculating session stats (WPM, word count, etc.)
+ * - Calculating session stats (WPM, articulation rate, word count, etc.)
  * - Linking sessions to vocabulary
+ * - Recomputing stats of past sessions after tokenizer/lemmatizer changes
+ * - Correcting which of a session's words were new
+ * - Splitting a session into several at long pauses
+ * - Exporting a session (audio, transcript, subtitles, metadata) as a zip to share
+ * - Annotating a transcript's words as new, known or mastered
  */
 
 use anyhow::{Context, Result};
+use crate::db::user::{get_setting, LEMMA_STEMMING_SETTING, VOCAB_NUMBERS_SETTING};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::{SqlitePool, Row};
+use sqlx::{SqliteConnection, SqlitePool, Row};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::Path;
 use uuid::Uuid;
 use tauri::Emitter;
 
-use super::lemmatization::get_lemma;
-use super::vocabulary::record_word;
+use super::lang::require_lang_code;
+use super::lemmatization::{get_lemma, get_lemma_with_fallback};
+use super::pagination::{validate_page, Page, NO_LIMIT};
+use super::recording::trim_wav;
+use super::transcription::{segments_to_srt, segments_to_vtt, TranscriptSegment};
+use super::vocabulary::{record_word, record_word_in};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -45,6 +58,19 @@ pub struct SessionData {
     pub session_type: Option<String>,
     pub text_library_id: Option<String>,
     pub source_text: Option<String>,
+    /// Pinned sessions are never removed by retention cleanup
+    pub is_pinned: bool,
+    /// Marked by the user as one of their best sessions (unrelated to pinning)
+    pub is_favorite: bool,
+    /// Read-aloud accuracy against the source text (0-100)
+    pub accuracy_score: Option<f64>,
+    pub filler_count: Option<i64>,
+    /// Words per minute of actual speaking time (pauses excluded)
+    pub articulation_wpm: Option<f64>,
+    /// Transcript segments as stored (JSON); only loaded by `get_session`,
+    /// see `get_session_segments` for them parsed
+    #[sqlx(default)]
+    pub segments: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,7 +79,27 @@ pub struct SessionStats {
     pub word_count: i64,
     pub unique_word_count: i64,
     pub wpm: f64,
+    /// Words per minute over the transcribed segments only, so pauses don't count
+    /// None when the transcript has no segment timings
+    pub articulation_wpm: Option<f64>,
     pub new_word_count: i64,
+    pub filler_count: i64,
+    /// Filler words per minute
+    pub filler_rate: f64,
+    /// Words that couldn't be saved to the vocabulary (see `retry_failed_words`)
+    pub failed_words: Vec<FailedWord>,
+}
+
+/// A spoken word whose vocabulary write failed while its session was processed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedWord {
+    pub word: String,
+    pub lemma: String,
+    pub error: String,
+    /// The word is in the vocabulary; only its link to the session is missing
+    #[serde(default)]
+    pub recorded: bool,
 }
 
 /// Create a new session
@@ -95,6 +141,23 @@ pub async fn create_session(
     Ok(session_id)
 }
 
+/// Delete a just-created session if the step that had to follow it failed
+/// (e.g. the recorder couldn't start), so no empty session is left behind
+pub async fn rollback_session_on_error<T>(
+    pool: &SqlitePool,
+    session_id: &str,
+    result: std::result::Result<T, String>,
+) -> Result<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            println!("[rollback_session_on_error] Removing session {}: {}", session_id, e);
+            delete_session(pool, session_id).await?;
+            Err(anyhow::anyhow!(e))
+        }
+    }
+}
+
 /// Complete a session with transcript and audio data
 pub async fn complete_session(
     pool: &SqlitePool,
@@ -102,7 +165,7 @@ pub async fn complete_session(
     session_id: &str,
     audio_path: &str,
     transcript: &str,
-    segments_json: &str,
+    segments: &[TranscriptSegment],
     duration_seconds: f32,
     language: &str,
     session_type: Option<&str>,
@@ -111,6 +174,7 @@ pub async fn complete_session(
 ) -> Result<SessionStats> {
     let now = Utc::now().timestamp();
     let duration = duration_seconds as i64;
+    let segments_json = serde_json::to_string(segments).context("Failed to serialize segments")?;
 
     // Get the primary_language from the session
     let primary_language: String = sqlx::query_scalar(
@@ -122,7 +186,20 @@ pub async fn complete_session(
     .context("Failed to get primary language from session")?;
 
     // Process the transcript to extract words and calculate stats
-    let stats = process_transcript(pool, app_handle, session_id, transcript, duration, language, &primary_language).await?;
+    let stats = process_transcript(
+        pool,
+        app_handle,
+        session_id,
+        transcript,
+        segments,
+        duration,
+        language,
+        &primary_language,
+    )
+    .await?;
+
+    // Score read-aloud sessions against the text that was read
+    let accuracy_score = read_aloud_accuracy(session_type, source_text, transcript);
 
     // Update the session with all data
     sqlx::query(
@@ -136,10 +213,13 @@ pub async fn complete_session(
             word_count = ?,
             unique_word_count = ?,
             wpm = ?,
+            articulation_wpm = ?,
             new_word_count = ?,
             session_type = ?,
             text_library_id = ?,
             source_text = ?,
+            accuracy_score = ?,
+            filler_count = ?,
             updated_at = ?
         WHERE id = ?
         "#,
@@ -148,14 +228,17 @@ pub async fn complete_session(
     .bind(duration)
     .bind(audio_path)
     .bind(transcript)
-    .bind(segments_json)
+    .bind(&segments_json)
     .bind(stats.word_count)
     .bind(stats.unique_word_count)
     .bind(stats.wpm)
+    .bind(stats.articulation_wpm)
     .bind(stats.new_word_count)
     .bind(session_type)
     .bind(text_library_id)
     .bind(source_text)
+    .bind(accuracy_score)
+    .bind(stats.filler_count)
     .bind(now)
     .bind(session_id)
     .execute(pool)
@@ -171,57 +254,151 @@ async fn process_transcript(
     app_handle: &tauri::AppHandle,
     session_id: &str,
     transcript: &str,
+    segments: &[TranscriptSegment],
     duration_seconds: i64,
     language: &str,
     primary_language: &str,
 ) -> Result<SessionStats> {
     // Tokenize the transcript into words
     let words = tokenize_transcript(transcript);
-    let word_count = words.len() as i64;
+    let vocab_options = vocab_options(pool).await;
+    let lemmatized = lemmatize_words(app_handle, &words, language, primary_language, vocab_options).await;
+    let fillers = count_filler_words(transcript, language);
+
+    record_session_words(
+        &mut *pool.acquire().await?,
+        session_id,
+        words.len() as i64,
+        fillers.total,
+        &lemmatized,
+        duration_seconds,
+        speaking_seconds(segments),
+        language,
+    )
+    .await
+}
 
-    // Calculate WPM (words per minute)
-    let duration_minutes = duration_seconds as f64 / 60.0;
-    let wpm = if duration_minutes > 0.0 {
-        word_count as f64 / duration_minutes
-    } else {
-        0.0
-    };
+/// Time spent actually speaking: the summed duration of the transcript segments
+pub fn speaking_seconds(segments: &[TranscriptSegment]) -> f64 {
+    segments
+        .iter()
+        .map(|s| (s.end_time - s.start_time).max(0.0) as f64)
+        .sum()
+}
 
-    // Lemmatize words and count unique lemmas
-    let mut lemma_counts: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
-    let mut new_words = 0;
+/// Settings that change which words end up in the vocabulary
+#[derive(Debug, Clone, Copy)]
+struct VocabOptions {
+    /// Stemming fallback for words missing from the lemma DB
+    stem_fallback: bool,
+    /// Record numbers ("2024") as vocabulary
+    keep_numbers: bool,
+}
+
+/// Vocabulary settings chosen by the user (both off unless turned on)
+async fn vocab_options(pool: &SqlitePool) -> VocabOptions {
+    let enabled = |value: Result<Option<String>>| matches!(value, Ok(Some(value)) if value == "true");
+
+    VocabOptions {
+        stem_fallback: enabled(get_setting(pool, LEMMA_STEMMING_SETTING).await),
+        keep_numbers: enabled(get_setting(pool, VOCAB_NUMBERS_SETTING).await),
+    }
+}
+
+/// Lemmatize transcript words, dropping words from the user's primary language
+/// and, unless `options.keep_numbers`, numbers
+/// Returns (spoken form, lemma) pairs
+async fn lemmatize_words(
+    app_handle: &tauri::AppHandle,
+    words: &[String],
+    language: &str,
+    primary_language: &str,
+    options: VocabOptions,
+) -> Vec<(String, String)> {
+    let mut lemmatized = Vec::with_capacity(words.len());
+
+    for word in words {
+        if !options.keep_numbers && is_number_token(word) {
+            continue;
+        }
 
-    for word in &words {
         // Skip if word exists in primary language (filter out native language words)
         if is_primary_language_word(word, primary_language, app_handle).await {
             continue;
         }
 
         // Lemmatize the word
-        let lemma = get_lemma(word, language, app_handle)
+        let lemma = get_lemma_with_fallback(word, language, app_handle, options.stem_fallback)
             .await
             .ok()
             .flatten()
             .unwrap_or_else(|| word.clone());
 
-        // Count occurrences of each lemma in this session
-        *lemma_counts.entry(lemma.clone()).or_insert(0) += 1;
+        lemmatized.push((word.clone(), lemma));
+    }
+
+    lemmatized
+}
 
-        // Record word in vocabulary and check if it's new
-        let is_new = record_word(pool, &lemma, language, word).await?;
-        if is_new {
-            new_words += 1;
+/// Record lemmatized words in the vocabulary and link them to the session
+async fn record_session_words(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+    word_count: i64,
+    filler_count: i64,
+    lemmatized: &[(String, String)],
+    duration_seconds: i64,
+    speaking_seconds: f64,
+    language: &str,
+) -> Result<SessionStats> {
+    let (wpm, filler_rate, articulation_wpm) = speaking_rates(word_count, filler_count, duration_seconds, speaking_seconds);
+
+    // Count unique lemmas
+    let unique_word_count = lemmatized.iter().map(|(_, lemma)| lemma).collect::<HashSet<_>>().len() as i64;
+    let mut lemma_forms: HashMap<String, Vec<String>> = HashMap::new();
+    let mut new_lemmas: HashSet<String> = HashSet::new();
+    let mut failed_words = Vec::new();
+
+    for (word, lemma) in lemmatized {
+        // Record word in vocabulary and check if it's new. A failed write (e.g. the
+        // database is locked) is kept for a retry rather than failing the whole session
+        match record_word_in(&mut *conn, lemma, language, word, Some(session_id)).await {
+            Ok(is_new) => {
+                // Keep the forms of each recorded lemma to count its occurrences in this session
+                lemma_forms.entry(lemma.clone()).or_default().push(word.clone());
+                if is_new {
+                    new_lemmas.insert(lemma.clone());
+                }
+            }
+            Err(e) => {
+                println!("[record_session_words] Failed to record '{}': {}", lemma, e);
+                failed_words.push(FailedWord {
+                    word: word.clone(),
+                    lemma: lemma.clone(),
+                    error: e.to_string(),
+                    recorded: false,
+                });
+            }
         }
     }
 
-    let unique_word_count = lemma_counts.len() as i64;
+    // A reprocessed session's words are already in the vocabulary; those it introduced
+    // are still new in it
+    let introduced: Vec<String> = sqlx::query_scalar("SELECT lemma FROM vocab WHERE language = ? AND first_session_id = ?")
+        .bind(language)
+        .bind(session_id)
+        .fetch_all(&mut *conn)
+        .await
+        .context("Failed to fetch session's new words")?;
+    new_lemmas.extend(introduced.into_iter().filter(|lemma| lemma_forms.contains_key(lemma)));
 
-    // Save session_words links
-    for (lemma, count) in lemma_counts {
-        // Check if this is the first time seeing this word globally
-        let is_new = is_new_word_for_user(pool, &lemma, language).await?;
+    // Save session_words links. Newness comes from record_word: checking the vocabulary
+    // now would always find the word, since it was just recorded. A failed link is kept
+    // for a retry too, as its words are already in the vocabulary
+    for (lemma, forms) in lemma_forms {
+        let is_new = new_lemmas.contains(&lemma);
 
-        sqlx::query(
+        let linked = sqlx::query(
             r#"
             INSERT INTO session_words (session_id, lemma, count, is_new)
             VALUES (?, ?, ?, ?)
@@ -229,49 +406,1085 @@ async fn process_transcript(
         )
         .bind(session_id)
         .bind(&lemma)
-        .bind(count)
+        .bind(forms.len() as i64)
         .bind(is_new)
-        .execute(pool)
-        .await
-        .context("Failed to insert session word")?;
+        .execute(&mut *conn)
+        .await;
+
+        if let Err(e) = linked {
+            println!("[record_session_words] Failed to link '{}': {}", lemma, e);
+            failed_words.extend(forms.into_iter().map(|word| FailedWord {
+                word,
+                lemma: lemma.clone(),
+                error: e.to_string(),
+                recorded: true,
+            }));
+        }
     }
 
     Ok(SessionStats {
         word_count,
         unique_word_count,
         wpm,
-        new_word_count: new_words,
+        articulation_wpm,
+        new_word_count: new_lemmas.len() as i64,
+        filler_count,
+        filler_rate,
+        failed_words,
     })
 }
 
-/// Simple tokenization: split on whitespace and remove punctuation
-fn tokenize_transcript(text: &str) -> Vec<String> {
-    text.split_whitespace()
-        .map(|word| {
-            // Remove all punctuation (including Unicode like ¿ ¡)
-            word.trim_matches(|c: char| c.is_ascii_punctuation() || !c.is_alphanumeric())
-                .to_lowercase()
-        })
-        .filter(|word| !word.is_empty())
-        .collect()
+/// WPM (words per minute), fillers per minute and articulation rate of a recording
+fn speaking_rates(word_count: i64, filler_count: i64, duration_seconds: i64, speaking_seconds: f64) -> (f64, f64, Option<f64>) {
+    let duration_minutes = duration_seconds as f64 / 60.0;
+    let (wpm, filler_rate) = if duration_minutes > 0.0 {
+        (word_count as f64 / duration_minutes, filler_count as f64 / duration_minutes)
+    } else {
+        (0.0, 0.0)
+    };
+
+    (wpm, filler_rate, articulation_wpm(word_count, filler_count, speaking_seconds))
+}
+
+/// Articulation rate: fillers aren't words the user meant to say, and the
+/// denominator is speaking time rather than the whole recording
+fn articulation_wpm(word_count: i64, filler_count: i64, speaking_seconds: f64) -> Option<f64> {
+    (speaking_seconds > 0.0).then(|| (word_count - filler_count).max(0) as f64 / (speaking_seconds / 60.0))
 }
 
-/// Check if a word is new for the user (first time seeing it)
-async fn is_new_word_for_user(pool: &SqlitePool, lemma: &str, language: &str) -> Result<bool> {
-    let count: i64 = sqlx::query_scalar(
+/// Record words that failed while a session was processed and link them to the session
+/// (words already in the vocabulary are only linked). Returns the words that failed again
+pub async fn retry_failed_words(pool: &SqlitePool, session_id: &str, failed_words: &[FailedWord]) -> Result<Vec<FailedWord>> {
+    let language: String = sqlx::query_scalar("SELECT language FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to fetch session")?;
+
+    let mut still_failed = Vec::new();
+    let mut new_words = 0;
+
+    for failed in failed_words {
+        let is_new = if failed.recorded {
+            // Already counted in the session's new_word_count when it was recorded
+            sqlx::query_scalar("SELECT COUNT(*) > 0 FROM vocab WHERE language = ? AND lemma = ? AND first_session_id = ?")
+                .bind(&language)
+                .bind(&failed.lemma)
+                .bind(session_id)
+                .fetch_one(pool)
+                .await
+                .context("Failed to fetch word")?
+        } else {
+            match record_word(pool, &failed.lemma, &language, &failed.word, Some(session_id)).await {
+                Ok(is_new) => {
+                    if is_new {
+                        new_words += 1;
+                    }
+                    is_new
+                }
+                Err(e) => {
+                    still_failed.push(FailedWord { error: e.to_string(), ..failed.clone() });
+                    continue;
+                }
+            }
+        };
+
+        let linked = sqlx::query("UPDATE session_words SET count = count + 1 WHERE session_id = ? AND lemma = ?")
+            .bind(session_id)
+            .bind(&failed.lemma)
+            .execute(pool)
+            .await
+            .context("Failed to update session word")?;
+        if linked.rows_affected() == 0 {
+            let inserted = sqlx::query("INSERT INTO session_words (session_id, lemma, count, is_new) VALUES (?, ?, 1, ?)")
+                .bind(session_id)
+                .bind(&failed.lemma)
+                .bind(is_new)
+                .execute(pool)
+                .await;
+            if let Err(e) = inserted {
+                // The vocabulary write went through, so only the link is retried next time
+                still_failed.push(FailedWord { error: e.to_string(), recorded: true, ..failed.clone() });
+            }
+        }
+    }
+
+    if new_words > 0 {
+        sqlx::query("UPDATE sessions SET new_word_count = COALESCE(new_word_count, 0) + ? WHERE id = ?")
+            .bind(new_words)
+            .bind(session_id)
+            .execute(pool)
+            .await
+            .context("Failed to update new word count")?;
+    }
+
+    println!(
+        "[retry_failed_words] Recorded {} of {} words for session {}",
+        failed_words.len() - still_failed.len(),
+        failed_words.len(),
+        session_id
+    );
+    Ok(still_failed)
+}
+
+/// Recalculate which of a session's words were new, e.g. for sessions saved while
+/// `is_new` was computed after the word had already been recorded (so was always false)
+///
+/// A word is new unless an earlier session in the same language used it, or it was in
+/// the vocabulary before the session started. Also updates the session's new_word_count,
+/// which is returned.
+pub async fn recompute_session_word_newness(pool: &SqlitePool, session_id: &str) -> Result<i64> {
+    let (language, started_at): (String, i64) =
+        sqlx::query_as("SELECT language, started_at FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to fetch session")?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
         r#"
-        SELECT COUNT(*)
-        FROM vocab
-        WHERE lemma = ? AND language = ?
+        UPDATE session_words
+        SET is_new = NOT (
+            EXISTS (
+                SELECT 1
+                FROM session_words earlier_words
+                JOIN sessions earlier ON earlier.id = earlier_words.session_id
+                WHERE earlier_words.lemma = session_words.lemma
+                  AND earlier.language = ?1
+                  AND (earlier.started_at < ?2 OR (earlier.started_at = ?2 AND earlier.id < ?3))
+            )
+            OR EXISTS (
+                SELECT 1 FROM vocab
+                WHERE vocab.language = ?1 AND vocab.lemma = session_words.lemma AND vocab.first_seen_at < ?2
+            )
+        )
+        WHERE session_id = ?3
         "#,
     )
-    .bind(lemma)
-    .bind(language)
+    .bind(&language)
+    .bind(started_at)
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to update session words")?;
+
+    let new_word_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM session_words WHERE session_id = ? AND is_new")
+        .bind(session_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE sessions SET new_word_count = ? WHERE id = ?")
+        .bind(new_word_count)
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update new word count")?;
+
+    tx.commit().await?;
+
+    println!("[recompute_session_word_newness] Session {} has {} new words", session_id, new_word_count);
+    Ok(new_word_count)
+}
+
+/// Take a session's words out of the vocabulary's usage counts and remove its
+/// session_words links, returning the lemmas it used
+///
+/// Vocabulary rows are kept (with their first_seen_at, first_session_id and mastery), so
+/// words recorded again afterwards only get their usage counts adjusted.
+async fn unrecord_session_words(conn: &mut SqliteConnection, session_id: &str, language: &str) -> Result<Vec<String>> {
+    let words: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT lemma, count FROM session_words WHERE session_id = ?"
+    )
+    .bind(session_id)
+    .fetch_all(&mut *conn)
+    .await
+    .context("Failed to fetch session words")?;
+
+    let now = Utc::now().timestamp();
+    for (lemma, count) in &words {
+        sqlx::query(
+            r#"
+            UPDATE vocab
+            SET usage_count = MAX(usage_count - ?, 0),
+                updated_at = ?
+            WHERE language = ? AND lemma = ?
+            "#,
+        )
+        .bind(count)
+        .bind(now)
+        .bind(language)
+        .bind(lemma)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to revert vocabulary usage")?;
+    }
+
+    sqlx::query("DELETE FROM session_words WHERE session_id = ?")
+        .bind(session_id)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to delete session words")?;
+
+    Ok(words.into_iter().map(|(lemma, _)| lemma).collect())
+}
+
+/// Remove vocabulary rows for `lemmas` that are no longer used by any session,
+/// unless the user tagged or mastered them
+async fn remove_unused_words(conn: &mut SqliteConnection, language: &str, lemmas: &[String]) -> Result<()> {
+    for lemma in lemmas {
+        sqlx::query(
+            r#"
+            DELETE FROM vocab
+            WHERE language = ? AND lemma = ? AND usage_count <= 0
+              AND COALESCE(tags, '[]') = '[]' AND NOT mastered
+              AND NOT EXISTS (
+                  SELECT 1 FROM session_words
+                  JOIN sessions ON sessions.id = session_words.session_id
+                  WHERE session_words.lemma = vocab.lemma AND sessions.language = vocab.language
+              )
+            "#,
+        )
+        .bind(language)
+        .bind(lemma)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to remove unused vocabulary")?;
+    }
+
+    Ok(())
+}
+
+/// Re-run vocabulary processing for a session with a corrected transcript
+///
+/// Usage counts are adjusted from the old transcript's words to the new one's, so they
+/// aren't doubled; words keep when they were first seen. Stored segments keep their
+/// original (uncorrected) text.
+pub async fn reprocess_session(
+    pool: &SqlitePool,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    corrected_transcript: &str,
+) -> Result<SessionStats> {
+    let (language, primary_language): (String, String) = sqlx::query_as(
+        "SELECT language, COALESCE(primary_language, 'en') FROM sessions WHERE id = ?"
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to fetch session")?;
+    let language = require_lang_code(&language)?;
+
+    let words = tokenize_transcript(corrected_transcript);
+    let vocab_options = vocab_options(pool).await;
+    let lemmatized = lemmatize_words(app_handle, &words, &language, &primary_language, vocab_options).await;
+
+    replace_session_transcript(pool, session_id, corrected_transcript, words.len() as i64, &lemmatized).await
+}
+
+/// Swap a session's recorded words and stats for those of a new transcript
+///
+/// A language stored before codes were normalized (e.g. "ES") is normalized, so the
+/// new words land in the same vocabulary as the rest of that language.
+async fn replace_session_transcript(
+    pool: &SqlitePool,
+    session_id: &str,
+    transcript: &str,
+    word_count: i64,
+    lemmatized: &[(String, String)],
+) -> Result<SessionStats> {
+    let (stored_language, duration, segments_json, session_type, source_text): (
+        String,
+        Option<i64>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = sqlx::query_as(
+        "SELECT language, duration, segments, session_type, source_text FROM sessions WHERE id = ?"
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to fetch session")?;
+    let language = require_lang_code(&stored_language)?;
+
+    let mut tx = pool.begin().await?;
+    let old_lemmas = unrecord_session_words(&mut tx, session_id, &stored_language).await?;
+
+    // Segment timings still describe the recording, even though their text isn't corrected
+    let segments: Vec<TranscriptSegment> = segments_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let fillers = count_filler_words(transcript, &language);
+    let stats = record_session_words(
+        &mut tx,
+        session_id,
+        word_count,
+        fillers.total,
+        lemmatized,
+        duration.unwrap_or(0),
+        speaking_seconds(&segments),
+        &language,
+    )
+    .await?;
+
+    let accuracy_score = read_aloud_accuracy(session_type.as_deref(), source_text.as_deref(), transcript);
+
+    sqlx::query(
+        r#"
+        UPDATE sessions
+        SET language = ?,
+            transcript = ?,
+            word_count = ?,
+            unique_word_count = ?,
+            wpm = ?,
+            articulation_wpm = ?,
+            new_word_count = ?,
+            filler_count = ?,
+            accuracy_score = ?,
+            updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&language)
+    .bind(transcript)
+    .bind(stats.word_count)
+    .bind(stats.unique_word_count)
+    .bind(stats.wpm)
+    .bind(stats.articulation_wpm)
+    .bind(stats.new_word_count)
+    .bind(stats.filler_count)
+    .bind(accuracy_score)
+    .bind(Utc::now().timestamp())
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to update session")?;
+
+    // Words the corrected transcript no longer contains
+    remove_unused_words(&mut tx, &stored_language, &old_lemmas).await?;
+
+    tx.commit().await?;
+    Ok(stats)
+}
+
+/// One aligned word in a read-aloud comparison
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WordDiff {
+    Match { word: String },
+    /// Word in the source text that wasn't spoken
+    Missed { expected: String },
+    /// Spoken word that isn't in the source text
+    Inserted { spoken: String },
+    /// Source word spoken as a different word
+    Substituted { expected: String, spoken: String },
+}
+
+/// Sessions between `recompute_stats_progress` events
+const RECOMPUTE_PROGRESS_INTERVAL: usize = 25;
+
+/// Progress payload for the `recompute_stats_progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// Recalculate word_count, unique_word_count, wpm, articulation_wpm, filler_count and
+/// new_word_count of every completed session from its stored transcript and segments,
+/// e.g. after tokenizer or lemmatizer improvements
+///
+/// Vocabulary and session_words are left as they are. Emits `recompute_stats_progress`.
+/// Returns the number of sessions updated.
+pub async fn recompute_all_session_stats(pool: &SqlitePool, app_handle: &tauri::AppHandle) -> Result<usize> {
+    let vocab_options = vocab_options(pool).await;
+
+    recompute_session_stats_with(
+        pool,
+        |words, language, primary_language| async move {
+            lemmatize_words(app_handle, &words, &language, &primary_language, vocab_options).await
+        },
+        |progress| {
+            let _ = app_handle.emit("recompute_stats_progress", progress);
+        },
+    )
+    .await
+}
+
+/// `recompute_all_session_stats` with an explicit lemmatizer and progress callback
+///
+/// `lemmatize` gets (words, language, primary_language) and returns (word, lemma) pairs.
+/// Sessions are replayed oldest first, so a lemma is new in the first session that uses it,
+/// unless it was already in the vocabulary before that session (e.g. from deleted sessions).
+pub async fn recompute_session_stats_with<L, Fut>(
+    pool: &SqlitePool,
+    mut lemmatize: L,
+    mut on_progress: impl FnMut(RecomputeProgress),
+) -> Result<usize>
+where
+    L: FnMut(Vec<String>, String, String) -> Fut,
+    Fut: Future<Output = Vec<(String, String)>>,
+{
+    let sessions = sqlx::query(
+        r#"
+        SELECT id, language, COALESCE(primary_language, 'en') AS primary_language, started_at,
+               transcript, duration, segments
+        FROM sessions
+        WHERE ended_at IS NOT NULL AND transcript IS NOT NULL
+        ORDER BY started_at ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to load sessions")?;
+
+    // When each word first entered the vocabulary
+    let mut first_seen: HashMap<(String, String), i64> = HashMap::new();
+    for (language, lemma, first_seen_at) in sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT language, lemma, first_seen_at FROM vocab"
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to load vocabulary")?
+    {
+        first_seen.insert((language, lemma), first_seen_at);
+    }
+
+    let total = sessions.len();
+    let mut seen_by_language: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for (i, row) in sessions.into_iter().enumerate() {
+        let session_id: String = row.get("id");
+        let language: String = row.get("language");
+        let started_at: i64 = row.get("started_at");
+        let transcript: String = row.get("transcript");
+        let duration: Option<i64> = row.get("duration");
+
+        let words = tokenize_transcript(&transcript);
+        let word_count = words.len() as i64;
+        let lemmatized = lemmatize(words, language.clone(), row.get("primary_language")).await;
+
+        let lemmas: HashSet<String> = lemmatized.into_iter().map(|(_, lemma)| lemma).collect();
+        let seen = seen_by_language.entry(language.clone()).or_default();
+        let new_word_count = lemmas
+            .iter()
+            .filter(|lemma| seen.insert((*lemma).clone()))
+            .filter(|lemma| {
+                first_seen
+                    .get(&(language.clone(), (*lemma).clone()))
+                    .is_none_or(|&first_seen_at| first_seen_at >= started_at)
+            })
+            .count() as i64;
+
+        let duration_minutes = duration.unwrap_or(0) as f64 / 60.0;
+        let wpm = if duration_minutes > 0.0 {
+            word_count as f64 / duration_minutes
+        } else {
+            0.0
+        };
+
+        let segments: Vec<TranscriptSegment> = row
+            .get::<Option<String>, _>("segments")
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let filler_count = count_filler_words(&transcript, &language).total;
+        let articulation_wpm = articulation_wpm(word_count, filler_count, speaking_seconds(&segments));
+
+        sqlx::query(
+            r#"
+            UPDATE sessions
+            SET word_count = ?, unique_word_count = ?, wpm = ?, articulation_wpm = ?,
+                filler_count = ?, new_word_count = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(word_count)
+        .bind(lemmas.len() as i64)
+        .bind(wpm)
+        .bind(articulation_wpm)
+        .bind(filler_count)
+        .bind(new_word_count)
+        .bind(Utc::now().timestamp())
+        .bind(&session_id)
+        .execute(pool)
+        .await
+        .context("Failed to update session stats")?;
+
+        let processed = i + 1;
+        if processed % RECOMPUTE_PROGRESS_INTERVAL == 0 || processed == total {
+            on_progress(RecomputeProgress { processed, total });
+        }
+    }
+
+    println!("[recompute_all_session_stats] Recomputed stats for {} sessions", total);
+    Ok(total)
+}
+
+/// Pause between transcript segments (seconds) that `split_session` splits at by default
+pub const DEFAULT_SPLIT_GAP_SECONDS: f32 = 10.0;
+
+/// Group segments into runs separated by pauses longer than `min_gap_seconds`
+pub fn split_segments_at_gaps(segments: &[TranscriptSegment], min_gap_seconds: f32) -> Vec<Vec<TranscriptSegment>> {
+    let mut groups: Vec<Vec<TranscriptSegment>> = Vec::new();
+    let mut last_end = 0.0f32;
+
+    for segment in segments {
+        match groups.last_mut() {
+            Some(group) if segment.start_time - last_end <= min_gap_seconds => group.push(segment.clone()),
+            _ => groups.push(vec![segment.clone()]),
+        }
+        last_end = last_end.max(segment.end_time);
+    }
+
+    groups
+}
+
+/// Split a session into one new session per stretch of speech, cutting at pauses
+/// longer than `min_gap_seconds` in its stored segments
+///
+/// Each part gets its own trimmed WAV and recalculated stats, and takes over the
+/// original's session words; vocabulary rows aren't changed, apart from which session
+/// introduced a word. The original is replaced, so nothing is counted twice in stats.
+/// Either every part is saved or the session is left as it was.
+/// Returns the new session IDs.
+pub async fn split_session(
+    pool: &SqlitePool,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    min_gap_seconds: f32,
+) -> Result<Vec<String>> {
+    let vocab_options = vocab_options(pool).await;
+
+    split_session_with(pool, session_id, min_gap_seconds, |words, language, primary_language| async move {
+        lemmatize_words(app_handle, &words, &language, &primary_language, vocab_options).await
+    })
+    .await
+}
+
+/// One part of a session being split, before it is saved
+struct SplitPart {
+    id: String,
+    start: f32,
+    end: f32,
+    segments: Vec<TranscriptSegment>,
+    transcript: String,
+    word_count: i64,
+    lemmatized: Vec<(String, String)>,
+}
+
+/// `split_session` with an explicit lemmatizer (see `recompute_session_stats_with`)
+pub async fn split_session_with<L, Fut>(
+    pool: &SqlitePool,
+    session_id: &str,
+    min_gap_seconds: f32,
+    mut lemmatize: L,
+) -> Result<Vec<String>>
+where
+    L: FnMut(Vec<String>, String, String) -> Fut,
+    Fut: Future<Output = Vec<(String, String)>>,
+{
+    let row = sqlx::query(
+        r#"
+        SELECT language, COALESCE(primary_language, 'en') AS primary_language, started_at, duration,
+               audio_path, segments, session_type, text_library_id, source_text
+        FROM sessions
+        WHERE id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch session")?
+    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+    let stored_language: String = row.get("language");
+    let language = require_lang_code(&stored_language)?;
+    let primary_language: String = row.get("primary_language");
+    let started_at: i64 = row.get("started_at");
+    let audio_path: Option<String> = row.get("audio_path");
+    let session_type: Option<String> = row.get("session_type");
+    let text_library_id: Option<String> = row.get("text_library_id");
+    let source_text: Option<String> = row.get("source_text");
+
+    let segments: Vec<TranscriptSegment> = row
+        .get::<Option<String>, _>("segments")
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let groups = split_segments_at_gaps(&segments, min_gap_seconds);
+    if groups.len() < 2 {
+        anyhow::bail!("Session has no pause longer than {}s to split at", min_gap_seconds);
+    }
+
+    // Cut halfway through each pause so no part loses the edges of its speech
+    let segments_end = segments.iter().map(|s| s.end_time).fold(0.0f32, f32::max);
+    let mut cuts = vec![0.0f32];
+    for pair in groups.windows(2) {
+        let previous_end = pair[0].iter().map(|s| s.end_time).fold(0.0f32, f32::max);
+        cuts.push((previous_end + pair[1][0].start_time) / 2.0);
+    }
+    cuts.push(row.get::<Option<i64>, _>("duration").map_or(segments_end, |d| (d as f32).max(segments_end)));
+
+    let mut parts = Vec::with_capacity(groups.len());
+    for (i, group) in groups.iter().enumerate() {
+        let (start, end) = (cuts[i], cuts[i + 1]);
+        let part_segments: Vec<TranscriptSegment> = group
+            .iter()
+            .map(|s| TranscriptSegment {
+                start_time: s.start_time - start,
+                end_time: s.end_time - start,
+                ..s.clone()
+            })
+            .collect();
+        let transcript = part_segments
+            .iter()
+            .map(|s| s.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let words = tokenize_transcript(&transcript);
+        let word_count = words.len() as i64;
+        let lemmatized = lemmatize(words, language.clone(), primary_language.clone()).await;
+
+        parts.push(SplitPart {
+            id: Uuid::new_v4().to_string(),
+            start,
+            end,
+            segments: part_segments,
+            transcript,
+            word_count,
+            lemmatized,
+        });
+    }
+
+    // Trim every recording before touching the database. Sessions without a
+    // recording on disk are split by transcript only
+    let mut part_audio: Vec<Option<std::path::PathBuf>> = Vec::with_capacity(parts.len());
+    if let Some(source) = audio_path.as_deref().map(Path::new).filter(|path| path.exists()) {
+        for (i, part) in parts.iter().enumerate() {
+            let dest = source.with_file_name(format!("{}.wav", part.id));
+            let last = i == parts.len() - 1;
+            part_audio.push(Some(dest.clone()));
+            if let Err(e) = trim_wav(source, &dest, part.start, (!last).then_some(part.end)) {
+                remove_split_audio(&part_audio);
+                anyhow::bail!(e);
+            }
+        }
+    } else {
+        part_audio.resize(parts.len(), None);
+    }
+
+    // Replace the original with its parts in one transaction
+    let saved: Result<()> = async {
+        let now = Utc::now().timestamp();
+        let mut tx = pool.begin().await?;
+
+        // The parts take over the original's session_words; the vocabulary itself
+        // (usage counts, first_seen_at) is unchanged, as the same words were spoken
+        let original_words: Vec<(String, i64, bool)> =
+            sqlx::query_as("SELECT lemma, count, is_new FROM session_words WHERE session_id = ?")
+                .bind(session_id)
+                .fetch_all(&mut *tx)
+                .await
+                .context("Failed to fetch session words")?;
+        let mut unassigned: HashMap<String, (i64, bool)> = original_words
+            .into_iter()
+            .map(|(lemma, count, is_new)| (lemma, (count, is_new)))
+            .collect();
+
+        sqlx::query("DELETE FROM session_words WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete session words")?;
+
+        // A word belongs to the first part that uses it, counted by its uses there;
+        // words no part's transcript contains (e.g. from a corrected transcript) stay
+        // with the first part
+        let mut part_words: Vec<Vec<(String, i64, bool)>> = Vec::with_capacity(parts.len());
+        for part in &parts {
+            let mut counts: HashMap<&str, i64> = HashMap::new();
+            for (_, lemma) in &part.lemmatized {
+                *counts.entry(lemma.as_str()).or_insert(0) += 1;
+            }
+
+            let mut words = Vec::new();
+            for (lemma, count) in counts {
+                let is_new = match unassigned.remove(lemma) {
+                    Some((_, is_new)) => is_new,
+                    None if part_words.iter().flatten().any(|(l, _, _)| l == lemma) => false,
+                    // Not in the vocabulary through this session
+                    None => continue,
+                };
+                words.push((lemma.to_string(), count, is_new));
+            }
+            part_words.push(words);
+        }
+        part_words[0].extend(unassigned.into_iter().map(|(lemma, (count, is_new))| (lemma, count, is_new)));
+
+        for ((part, audio), words) in parts.iter().zip(&part_audio).zip(&part_words) {
+            let duration = (part.end - part.start).round() as i64;
+            let part_started_at = started_at + part.start.round() as i64;
+
+            let fillers = count_filler_words(&part.transcript, &language);
+            let (wpm, _, articulation_wpm) =
+                speaking_rates(part.word_count, fillers.total, duration, speaking_seconds(&part.segments));
+            let unique_word_count = part.lemmatized.iter().map(|(_, lemma)| lemma).collect::<HashSet<_>>().len() as i64;
+            let new_word_count = words.iter().filter(|(_, _, is_new)| *is_new).count() as i64;
+            let accuracy_score = read_aloud_accuracy(session_type.as_deref(), source_text.as_deref(), &part.transcript);
+
+            sqlx::query(
+                r#"
+                INSERT INTO sessions (
+                    id, language, primary_language, started_at, ended_at, duration, audio_path, transcript,
+                    segments, session_type, text_library_id, source_text, word_count, unique_word_count,
+                    wpm, articulation_wpm, new_word_count, filler_count, accuracy_score, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&part.id)
+            .bind(&stored_language)
+            .bind(&primary_language)
+            .bind(part_started_at)
+            .bind(part_started_at + duration)
+            .bind(duration)
+            .bind(audio.as_ref().map(|path| path.to_string_lossy().to_string()))
+            .bind(&part.transcript)
+            .bind(serde_json::to_string(&part.segments).context("Failed to serialize segments")?)
+            .bind(&session_type)
+            .bind(&text_library_id)
+            .bind(&source_text)
+            .bind(part.word_count)
+            .bind(unique_word_count)
+            .bind(wpm)
+            .bind(articulation_wpm)
+            .bind(new_word_count)
+            .bind(fillers.total)
+            .bind(accuracy_score)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create split session")?;
+
+            for (lemma, count, is_new) in words {
+                sqlx::query("INSERT INTO session_words (session_id, lemma, count, is_new) VALUES (?, ?, ?, ?)")
+                    .bind(&part.id)
+                    .bind(lemma)
+                    .bind(count)
+                    .bind(is_new)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to move session word")?;
+
+                sqlx::query("UPDATE vocab SET first_session_id = ? WHERE language = ? AND lemma = ? AND first_session_id = ?")
+                    .bind(&part.id)
+                    .bind(&stored_language)
+                    .bind(lemma)
+                    .bind(session_id)
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to update word's first session")?;
+            }
+        }
+
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete original session")?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = saved {
+        println!("[split_session] Failed to split {}: {}", session_id, e);
+        remove_split_audio(&part_audio);
+        return Err(e);
+    }
+
+    // Only remove the original recording once its parts are saved
+    if let Some(path) = audio_path.filter(|path| !path.is_empty()) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            println!("[split_session] Warning: Could not delete audio file {}: {}", path, e);
+        }
+    }
+
+    let part_ids: Vec<String> = parts.into_iter().map(|part| part.id).collect();
+    println!("[split_session] Split {} into {} sessions", session_id, part_ids.len());
+    Ok(part_ids)
+}
+
+/// Remove the trimmed recordings of a split that didn't go through
+fn remove_split_audio(paths: &[Option<std::path::PathBuf>]) {
+    for path in paths.iter().flatten() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Read-aloud accuracy with a word-level diff for highlighting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadAloudScore {
+    /// Percentage of source words read correctly (0-100)
+    pub accuracy: f64,
+    pub missed: Vec<String>,
+    pub inserted: Vec<String>,
+    pub substituted: Vec<(String, String)>,
+    /// Full alignment in reading order
+    pub words: Vec<WordDiff>,
+}
+
+/// Compare a transcript with the source text it was read from
+///
+/// Aligns the two word sequences with a word-level Levenshtein distance
+/// (case and punctuation are ignored).
+pub fn score_read_aloud(source_text: &str, transcript: &str) -> ReadAloudScore {
+    let expected = tokenize_transcript(source_text);
+    let spoken = tokenize_transcript(transcript);
+    let (n, m) = (expected.len(), spoken.len());
+
+    // dist[i][j] = edits to turn expected[..i] into spoken[..j]
+    let mut dist = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = if expected[i - 1] == spoken[j - 1] { 0 } else { 1 };
+            dist[i][j] = (dist[i - 1][j - 1] + cost)
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+        }
+    }
+
+    // Walk back from the end to recover the alignment
+    let mut words = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && expected[i - 1] == spoken[j - 1] && dist[i][j] == dist[i - 1][j - 1] {
+            words.push(WordDiff::Match { word: expected[i - 1].clone() });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && dist[i][j] == dist[i - 1][j - 1] + 1 {
+            words.push(WordDiff::Substituted {
+                expected: expected[i - 1].clone(),
+                spoken: spoken[j - 1].clone(),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && dist[i][j] == dist[i - 1][j] + 1 {
+            words.push(WordDiff::Missed { expected: expected[i - 1].clone() });
+            i -= 1;
+        } else {
+            words.push(WordDiff::Inserted { spoken: spoken[j - 1].clone() });
+            j -= 1;
+        }
+    }
+    words.reverse();
+
+    let mut matched = 0;
+    let mut missed = Vec::new();
+    let mut inserted = Vec::new();
+    let mut substituted = Vec::new();
+    for diff in &words {
+        match diff {
+            WordDiff::Match { .. } => matched += 1,
+            WordDiff::Missed { expected } => missed.push(expected.clone()),
+            WordDiff::Inserted { spoken } => inserted.push(spoken.clone()),
+            WordDiff::Substituted { expected, spoken } => {
+                substituted.push((expected.clone(), spoken.clone()))
+            }
+        }
+    }
+
+    let accuracy = if n > 0 {
+        matched as f64 / n as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    ReadAloudScore {
+        accuracy,
+        missed,
+        inserted,
+        substituted,
+        words,
+    }
+}
+
+/// Accuracy to store for a session: only read-aloud sessions with a source text are scored
+fn read_aloud_accuracy(session_type: Option<&str>, source_text: Option<&str>, transcript: &str) -> Option<f64> {
+    match (session_type, source_text) {
+        (Some("read_aloud"), Some(source)) if !source.trim().is_empty() => {
+            Some(score_read_aloud(source, transcript).accuracy)
+        }
+        _ => None,
+    }
+}
+
+/// Get the read-aloud comparison for a session
+pub async fn get_read_aloud_score(pool: &SqlitePool, session_id: &str) -> Result<ReadAloudScore> {
+    let (source_text, transcript): (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT source_text, transcript FROM sessions WHERE id = ?"
+    )
+    .bind(session_id)
     .fetch_one(pool)
     .await
-    .context("Failed to check if word is new")?;
+    .context("Failed to fetch session")?;
+
+    let source_text = source_text
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Session has no source text to compare against"))?;
+
+    Ok(score_read_aloud(&source_text, transcript.as_deref().unwrap_or("")))
+}
+
+/// Filler words and phrases per language (lowercase, as produced by the tokenizer):
+/// hesitation sounds, and discourse markers that are also ordinary words ("like", "also", "é")
+fn filler_words(language: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    match language {
+        "en" => (&["uh", "um", "erm", "hmm"], &["like", "you know", "i mean"]),
+        "es" => (&["eh", "mmm"], &["este", "pues", "o sea", "bueno"]),
+        "fr" => (&["euh", "ben", "bah"], &["du coup", "genre", "enfin"]),
+        "de" => (&["äh", "ähm"], &["halt", "also", "quasi", "sozusagen"]),
+        "it" => (&["ehm"], &["cioè", "tipo", "allora", "insomma", "praticamente"]),
+        "pt" => (&["hum"], &["é", "tipo", "né", "então", "tipo assim"]),
+        "nl" => (&["eh", "uhm"], &["dus", "nou", "zeg maar"]),
+        "ru" => (&["э", "эм"], &["ну", "вот", "типа", "как бы", "это самое", "короче"]),
+        _ => (&["uh", "um", "eh", "hmm"], &[]),
+    }
+}
 
-    Ok(count == 0)
+/// Punctuation that marks a pause in a Whisper transcript (hyphens and apostrophes
+/// inside words don't)
+fn is_pause_mark(c: char) -> bool {
+    !is_word_char(c) && !c.is_whitespace() && !matches!(c, '-' | '\u{2010}' | '\'' | '\u{2019}')
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerWord {
+    pub filler: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FillerWordCount {
+    pub total: i64,
+    /// Per-filler counts, most frequent first
+    pub breakdown: Vec<FillerWord>,
+}
+
+/// Count filler words in a transcript (multi-word fillers like "o sea" count once)
+///
+/// Hesitation sounds count anywhere. Discourse markers only count when they stand alone
+/// between pauses ("I was, like, going" but not "I like it"), apart from any hesitations
+/// next to them, since they are also common content words.
+pub fn count_filler_words(transcript: &str, language: &str) -> FillerWordCount {
+    let (hesitations, markers) = filler_words(language);
+
+    let mut counts: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    for phrase in transcript.split(is_pause_mark) {
+        let words = tokenize_transcript(phrase);
+        let mut rest = Vec::with_capacity(words.len());
+        for word in &words {
+            if hesitations.contains(&word.as_str()) {
+                *counts.entry(word.clone()).or_insert(0) += 1;
+            } else {
+                rest.push(word.as_str());
+            }
+        }
+
+        if let Some(marker) = markers.iter().find(|marker| marker.split_whitespace().eq(rest.iter().copied())) {
+            *counts.entry(marker.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    let mut breakdown: Vec<FillerWord> = counts
+        .into_iter()
+        .map(|(filler, count)| FillerWord { filler, count })
+        .collect();
+    breakdown.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.filler.cmp(&b.filler)));
+
+    FillerWordCount {
+        total: breakdown.iter().map(|f| f.count).sum(),
+        breakdown,
+    }
+}
+
+/// Get the filler word breakdown for a session's transcript
+pub async fn get_session_filler_words(pool: &SqlitePool, session_id: &str) -> Result<FillerWordCount> {
+    let (transcript, language): (Option<String>, String) = sqlx::query_as(
+        "SELECT transcript, language FROM sessions WHERE id = ?"
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to fetch session")?;
+
+    Ok(count_filler_words(transcript.as_deref().unwrap_or(""), &language))
+}
+
+/// Split text into lowercase words
+///
+/// Words are runs of Unicode letters and digits (accents, including combining ones,
+/// stay intact). Hyphens and apostrophes are kept inside words ("well-being", "l'eau",
+/// "don't"), as are decimal and thousands separators inside numbers ("3.5", "1,000").
+/// Everything else, including punctuation like ¿ ¡ and dashes between words, separates words.
+pub(crate) fn tokenize_transcript(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if is_word_char(c) {
+            current.push(c);
+            continue;
+        }
+
+        let joins = |allowed: fn(char) -> bool| {
+            i > 0 && allowed(chars[i - 1]) && chars.get(i + 1).is_some_and(|&next| allowed(next))
+        };
+        let keep = match c {
+            '-' | '\u{2010}' | '\'' | '\u{2019}' => joins(is_word_char),
+            '.' | ',' => joins(|c| c.is_numeric()),
+            _ => false,
+        };
+
+        if keep && !current.is_empty() {
+            current.push(c);
+        } else if !current.is_empty() {
+            words.push(current.to_lowercase());
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        words.push(current.to_lowercase());
+    }
+
+    words
+}
+
+/// Letters, digits and combining accents (e.g. the second code point of a decomposed "é")
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+/// Whether a token is a number rather than a word ("2024", "3.5", "1,000")
+pub(crate) fn is_number_token(word: &str) -> bool {
+    word.chars().any(|c| c.is_numeric()) && word.chars().all(|c| c.is_numeric() || matches!(c, '.' | ','))
 }
 
 /// Check if a word exists in the primary language lemma database
@@ -322,7 +1535,9 @@ pub async fn get_session(pool: &SqlitePool, session_id: &str) -> Result<SessionD
         r#"
         SELECT id, language, started_at, ended_at, duration, audio_path, transcript,
                word_count, unique_word_count, wpm, new_word_count,
-               session_type, text_library_id, source_text
+               session_type, text_library_id, source_text,
+               COALESCE(is_pinned, 0) AS is_pinned, COALESCE(is_favorite, 0) AS is_favorite,
+               accuracy_score, filler_count, articulation_wpm, segments
         FROM sessions
         WHERE id = ?
         "#,
@@ -335,44 +1550,445 @@ pub async fn get_session(pool: &SqlitePool, session_id: &str) -> Result<SessionD
     Ok(session)
 }
 
+/// Get a session's transcript segments with their timings
+/// Sessions without stored segments (unfinished, or older than segment storage) return none
+pub async fn get_session_segments(pool: &SqlitePool, session_id: &str) -> Result<Vec<TranscriptSegment>> {
+    let segments_json: Option<String> = sqlx::query_scalar("SELECT segments FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch session")?
+        .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+
+    match segments_json {
+        Some(json) => serde_json::from_str(&json).context("Failed to parse session segments"),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// A session's transcript as subtitles, `format` being "srt" or "vtt"
+/// Empty (apart from the VTT header) for sessions without stored segments
+pub async fn get_session_subtitles(pool: &SqlitePool, session_id: &str, format: &str) -> Result<String> {
+    let to_subtitles = match format {
+        "srt" => segments_to_srt,
+        "vtt" => segments_to_vtt,
+        _ => anyhow::bail!("Subtitle format must be 'srt' or 'vtt'"),
+    };
+
+    let segments = get_session_segments(pool, session_id).await?;
+    Ok(to_subtitles(&segments))
+}
+
+/// How well the user knows a transcript word, from the vocabulary at query time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WordStatus {
+    /// First learned in this session
+    New,
+    /// Learned in an earlier session (or added manually)
+    Known,
+    /// Marked as mastered
+    Mastered,
+    /// Not in the vocabulary (numbers, primary language words, or since deleted)
+    Untracked,
+}
+
+/// A transcript word with its lemma and vocabulary status
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedWord {
+    pub word: String,
+    pub lemma: String,
+    pub status: WordStatus,
+}
+
+/// A session's transcript word by word, each labelled new/known/mastered for a
+/// highlighted transcript view
+pub async fn annotate_transcript(
+    pool: &SqlitePool,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+) -> Result<Vec<AnnotatedWord>> {
+    let vocab_options = vocab_options(pool).await;
+
+    annotate_transcript_with(pool, session_id, |words, language, primary_language| async move {
+        lemmatize_words(app_handle, &words, &language, &primary_language, vocab_options).await
+    })
+    .await
+}
+
+/// `annotate_transcript` with an explicit lemmatizer
+///
+/// `lemmatize` gets (words, language, primary_language) and returns (word, lemma) pairs;
+/// words it drops keep themselves as lemma.
+pub async fn annotate_transcript_with<L, Fut>(
+    pool: &SqlitePool,
+    session_id: &str,
+    lemmatize: L,
+) -> Result<Vec<AnnotatedWord>>
+where
+    L: FnOnce(Vec<String>, String, String) -> Fut,
+    Fut: Future<Output = Vec<(String, String)>>,
+{
+    let (language, primary_language, transcript): (String, String, Option<String>) = sqlx::query_as(
+        "SELECT language, COALESCE(primary_language, 'en'), transcript FROM sessions WHERE id = ?",
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to load session")?
+    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+    let transcript = transcript.ok_or_else(|| anyhow::anyhow!("Session has no transcript yet"))?;
+
+    let words = tokenize_transcript(&transcript);
+    let lemmas: HashMap<String, String> = lemmatize(words.clone(), language.clone(), primary_language)
+        .await
+        .into_iter()
+        .collect();
+
+    let mut statuses: HashMap<String, WordStatus> = HashMap::new();
+    for lemma in lemmas.values() {
+        if statuses.contains_key(lemma) {
+            continue;
+        }
+        let row: Option<(bool, Option<String>)> =
+            sqlx::query_as("SELECT mastered, first_session_id FROM vocab WHERE language = ? AND lemma = ?")
+                .bind(&language)
+                .bind(lemma)
+                .fetch_optional(pool)
+                .await
+                .context("Failed to look up word")?;
+
+        let status = match row {
+            Some((true, _)) => WordStatus::Mastered,
+            Some((false, first_session)) if first_session.as_deref() == Some(session_id) => WordStatus::New,
+            Some(_) => WordStatus::Known,
+            None => WordStatus::Untracked,
+        };
+        statuses.insert(lemma.clone(), status);
+    }
+
+    Ok(words
+        .into_iter()
+        .map(|word| match lemmas.get(&word) {
+            Some(lemma) => AnnotatedWord {
+                status: statuses[lemma],
+                lemma: lemma.clone(),
+                word,
+            },
+            None => AnnotatedWord {
+                lemma: word.clone(),
+                word,
+                status: WordStatus::Untracked,
+            },
+        })
+        .collect())
+}
+
+/// Result of a session export
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExport {
+    pub path: String,
+    pub size_bytes: u64,
+    /// False if the recording was missing and only the transcript was exported
+    pub has_audio: bool,
+}
+
+/// Contents of session.json in a session export
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionExportMetadata<'a> {
+    exported_at: i64,
+    session: &'a SessionData,
+    words: &'a [SessionWord],
+}
+
+/// Name of the recording inside a session export
+const EXPORT_AUDIO_FILE: &str = "session.wav";
+
+/// Write one session to a zip at `dest_path` to share it (e.g. with a tutor):
+/// session.wav, transcript.txt, transcript.srt (when segments are stored)
+/// and session.json with the session's stats and words
+pub async fn export_session(pool: &SqlitePool, session_id: &str, dest_path: &Path) -> Result<SessionExport> {
+    let mut session = get_session(pool, session_id).await?;
+    let segments = get_session_segments(pool, session_id).await?;
+    let words = get_session_words(pool, session_id).await?;
+
+    let audio = session
+        .audio_path
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .filter(|path| path.is_file());
+
+    // Local paths and raw segment JSON mean nothing to the recipient
+    session.audio_path = audio.as_ref().map(|_| EXPORT_AUDIO_FILE.to_string());
+    session.segments = None;
+
+    let metadata = serde_json::to_vec_pretty(&SessionExportMetadata {
+        exported_at: Utc::now().timestamp(),
+        session: &session,
+        words: &words,
+    })?;
+    let transcript = session.transcript.clone().unwrap_or_default();
+    let srt = (!segments.is_empty()).then(|| segments_to_srt(&segments));
+    let dest = dest_path.to_path_buf();
+    let has_audio = audio.is_some();
+
+    let size_bytes = tokio::task::spawn_blocking(move || {
+        write_session_archive(&dest, audio.as_deref(), &transcript, srt.as_deref(), &metadata)
+    })
+    .await
+    .context("Export task failed")??;
+
+    println!("[export_session] Exported session {} to {:?} ({} bytes)", session_id, dest_path, size_bytes);
+
+    Ok(SessionExport {
+        path: dest_path.to_string_lossy().to_string(),
+        size_bytes,
+        has_audio,
+    })
+}
+
+/// Write the files of a session export; returns the archive size
+fn write_session_archive(
+    dest_path: &Path,
+    audio: Option<&Path>,
+    transcript: &str,
+    srt: Option<&str>,
+    metadata: &[u8],
+) -> Result<u64> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create export directory")?;
+    }
+
+    // Write to a temp name so a failed export never leaves a truncated archive behind
+    let temp_path = dest_path.with_extension("zip.partial");
+    let file = std::fs::File::create(&temp_path).context("Failed to create export file")?;
+    let mut zip = ZipWriter::new(std::io::BufWriter::new(file));
+
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("session.json", deflated)?;
+    zip.write_all(metadata)?;
+    zip.start_file("transcript.txt", deflated)?;
+    zip.write_all(transcript.as_bytes())?;
+    if let Some(srt) = srt {
+        zip.start_file("transcript.srt", deflated)?;
+        zip.write_all(srt.as_bytes())?;
+    }
+
+    if let Some(audio) = audio {
+        // WAV barely shrinks, so store it as-is
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        zip.start_file(EXPORT_AUDIO_FILE, stored)?;
+        let mut reader = std::fs::File::open(audio).with_context(|| format!("Failed to open {}", audio.display()))?;
+        std::io::copy(&mut reader, &mut zip).context("Failed to archive recording")?;
+    }
+
+    zip.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&temp_path, dest_path).context("Failed to move export into place")?;
+
+    Ok(std::fs::metadata(dest_path)?.len())
+}
+
 /// Get all sessions for a language
 pub async fn get_sessions_by_language(
     pool: &SqlitePool,
     language: &str,
+) -> Result<Vec<SessionData>> {
+    fetch_sessions_by_language(pool, language, NO_LIMIT, 0).await
+}
+
+/// Get one page of a language's sessions (newest first) with the total count
+pub async fn get_sessions_by_language_paged(
+    pool: &SqlitePool,
+    language: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<SessionData>> {
+    validate_page(limit, offset)?;
+
+    let items = fetch_sessions_by_language(pool, language, limit, offset).await?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM sessions WHERE language = ? AND ended_at IS NOT NULL"
+    )
+    .bind(language)
+    .fetch_one(pool)
+    .await
+    .context("Failed to count sessions")?;
+
+    Ok(Page { items, total, limit, offset })
+}
+
+async fn fetch_sessions_by_language(
+    pool: &SqlitePool,
+    language: &str,
+    limit: i64,
+    offset: i64,
 ) -> Result<Vec<SessionData>> {
     let sessions = sqlx::query_as::<_, SessionData>(
         r#"
         SELECT id, language, started_at, ended_at, duration, audio_path, transcript,
                word_count, unique_word_count, wpm, new_word_count,
-               session_type, text_library_id, source_text
+               session_type, text_library_id, source_text,
+               COALESCE(is_pinned, 0) AS is_pinned, COALESCE(is_favorite, 0) AS is_favorite,
+               accuracy_score, filler_count, articulation_wpm
         FROM sessions
         WHERE language = ? AND ended_at IS NOT NULL
         ORDER BY started_at DESC
+        LIMIT ? OFFSET ?
         "#,
     )
     .bind(language)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch sessions")?;
+
+    Ok(sessions)
+}
+
+/// Get a language's completed sessions (newest first), optionally narrowed to a time window
+/// (`start_ts` inclusive, `end_ts` exclusive) and a session type
+/// Sessions without a type count as "free_speak". A `limit` of None returns every match.
+pub async fn get_sessions_filtered(
+    pool: &SqlitePool,
+    language: &str,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    session_type: Option<&str>,
+    limit: Option<i64>,
+    offset: i64,
+) -> Result<Vec<SessionData>> {
+    validate_page(limit.unwrap_or(1), offset)?;
+
+    let sessions = sqlx::query_as::<_, SessionData>(
+        r#"
+        SELECT id, language, started_at, ended_at, duration, audio_path, transcript,
+               word_count, unique_word_count, wpm, new_word_count,
+               session_type, text_library_id, source_text,
+               COALESCE(is_pinned, 0) AS is_pinned, COALESCE(is_favorite, 0) AS is_favorite,
+               accuracy_score, filler_count, articulation_wpm
+        FROM sessions
+        WHERE language = ?1 AND ended_at IS NOT NULL
+          AND (?2 IS NULL OR started_at >= ?2)
+          AND (?3 IS NULL OR started_at < ?3)
+          AND (?4 IS NULL OR COALESCE(session_type, 'free_speak') = ?4)
+        ORDER BY started_at DESC
+        LIMIT ?5 OFFSET ?6
+        "#,
+    )
+    .bind(language)
+    .bind(start_ts)
+    .bind(end_ts)
+    .bind(session_type)
+    .bind(limit.unwrap_or(NO_LIMIT))
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch filtered sessions")?;
+
+    Ok(sessions)
+}
+
+/// Get all sessions (all languages)
+pub async fn get_all_sessions(pool: &SqlitePool) -> Result<Vec<SessionData>> {
+    fetch_all_sessions(pool, NO_LIMIT, 0).await
+}
+
+/// Get one page of sessions across all languages (newest first) with the total count
+pub async fn get_all_sessions_paged(pool: &SqlitePool, limit: i64, offset: i64) -> Result<Page<SessionData>> {
+    validate_page(limit, offset)?;
+
+    let items = fetch_all_sessions(pool, limit, offset).await?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE ended_at IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .context("Failed to count sessions")?;
+
+    Ok(Page { items, total, limit, offset })
+}
+
+async fn fetch_all_sessions(pool: &SqlitePool, limit: i64, offset: i64) -> Result<Vec<SessionData>> {
+    let sessions = sqlx::query_as::<_, SessionData>(
+        r#"
+        SELECT id, language, started_at, ended_at, duration, audio_path, transcript,
+               word_count, unique_word_count, wpm, new_word_count,
+               session_type, text_library_id, source_text,
+               COALESCE(is_pinned, 0) AS is_pinned, COALESCE(is_favorite, 0) AS is_favorite,
+               accuracy_score, filler_count, articulation_wpm
+        FROM sessions
+        WHERE ended_at IS NOT NULL
+        ORDER BY started_at DESC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
     .fetch_all(pool)
     .await
-    .context("Failed to fetch sessions")?;
+    .context("Failed to fetch all sessions")?;
 
     Ok(sessions)
 }
 
-/// Get all sessions (all languages)
-pub async fn get_all_sessions(pool: &SqlitePool) -> Result<Vec<SessionData>> {
+/// Pin or unpin a session (pinned sessions are skipped by retention cleanup)
+pub async fn set_session_pinned(pool: &SqlitePool, session_id: &str, pinned: bool) -> Result<()> {
+    let result = sqlx::query("UPDATE sessions SET is_pinned = ?, updated_at = ? WHERE id = ?")
+        .bind(pinned)
+        .bind(Utc::now().timestamp())
+        .bind(session_id)
+        .execute(pool)
+        .await
+        .context("Failed to update session pin")?;
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("Session not found: {}", session_id);
+    }
+
+    Ok(())
+}
+
+/// Flip a session's favorite flag, returning the new state
+pub async fn toggle_session_favorite(pool: &SqlitePool, session_id: &str) -> Result<bool> {
+    let favorite: Option<bool> = sqlx::query_scalar(
+        "UPDATE sessions SET is_favorite = 1 - COALESCE(is_favorite, 0), updated_at = ?
+         WHERE id = ?
+         RETURNING is_favorite",
+    )
+    .bind(Utc::now().timestamp())
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to update session favorite")?;
+
+    favorite.ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))
+}
+
+/// Get completed favorite sessions (newest first), across all languages if `language` is None
+pub async fn get_favorite_sessions(pool: &SqlitePool, language: Option<&str>) -> Result<Vec<SessionData>> {
     let sessions = sqlx::query_as::<_, SessionData>(
         r#"
         SELECT id, language, started_at, ended_at, duration, audio_path, transcript,
                word_count, unique_word_count, wpm, new_word_count,
-               session_type, text_library_id, source_text
+               session_type, text_library_id, source_text,
+               COALESCE(is_pinned, 0) AS is_pinned, COALESCE(is_favorite, 0) AS is_favorite,
+               accuracy_score, filler_count, articulation_wpm
         FROM sessions
-        WHERE ended_at IS NOT NULL
+        WHERE COALESCE(is_favorite, 0) = 1 AND ended_at IS NOT NULL
+          AND (?1 IS NULL OR language = ?1)
         ORDER BY started_at DESC
         "#,
     )
+    .bind(language)
     .fetch_all(pool)
     .await
-    .context("Failed to fetch all sessions")?;
+    .context("Failed to fetch favorite sessions")?;
 
     Ok(sessions)
 }
@@ -499,6 +2115,12 @@ mod tests {
                 session_type TEXT DEFAULT 'free_speak',
                 text_library_id TEXT,
                 source_text TEXT,
+                is_pinned INTEGER DEFAULT 0,
+                is_favorite INTEGER DEFAULT 0,
+                accuracy_score REAL,
+                filler_count INTEGER,
+                segments TEXT,
+                articulation_wpm REAL,
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL
             )
@@ -512,12 +2134,19 @@ mod tests {
         sqlx::query(
             r#"
             CREATE TABLE vocab (
-                lemma TEXT NOT NULL,
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
                 language TEXT NOT NULL,
-                first_seen INTEGER NOT NULL,
-                last_seen INTEGER NOT NULL,
-                times_seen INTEGER NOT NULL DEFAULT 1,
-                PRIMARY KEY (lemma, language)
+                lemma TEXT NOT NULL,
+                forms_spoken TEXT,
+                first_seen_at INTEGER NOT NULL,
+                last_seen_at INTEGER NOT NULL,
+                usage_count INTEGER DEFAULT 1,
+                mastered BOOLEAN DEFAULT 0,
+                tags TEXT DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                first_session_id TEXT,
+                UNIQUE(language, lemma)
             )
             "#,
         )
@@ -756,4 +2385,825 @@ mod tests {
             // Note: This requires updating SessionData struct and query
         }
     }
+
+    #[tokio::test]
+    async fn test_get_sessions_filtered_by_date_and_type() {
+        let pool = setup_test_db().await;
+
+        for (started_at, session_type, language) in [
+            (1_000, Some("read_aloud"), "es"),
+            (2_000, Some("free_speak"), "es"),
+            (3_000, Some("read_aloud"), "es"),
+            (4_000, None, "es"),
+            (3_500, Some("read_aloud"), "fr"),
+        ] {
+            let id = create_session(&pool, language, "en", session_type, None, None).await.unwrap();
+            sqlx::query("UPDATE sessions SET started_at = ?, ended_at = ? WHERE id = ?")
+                .bind(started_at)
+                .bind(started_at + 60)
+                .bind(&id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // Still in progress: never returned
+        create_session(&pool, "es", "en", Some("read_aloud"), None, None).await.unwrap();
+
+        let started = |sessions: Vec<SessionData>| sessions.iter().map(|s| s.started_at).collect::<Vec<_>>();
+
+        // Open-ended range, no type: everything completed, newest first
+        let all = get_sessions_filtered(&pool, "es", None, None, None, None, 0).await.unwrap();
+        assert_eq!(started(all), vec![4_000, 3_000, 2_000, 1_000]);
+
+        // Date bounded: start inclusive, end exclusive
+        let window = get_sessions_filtered(&pool, "es", Some(2_000), Some(4_000), None, None, 0).await.unwrap();
+        assert_eq!(started(window), vec![3_000, 2_000]);
+        let since = get_sessions_filtered(&pool, "es", Some(2_500), None, None, None, 0).await.unwrap();
+        assert_eq!(started(since), vec![4_000, 3_000]);
+
+        // Type bounded, alone and combined with a range
+        let read_aloud = get_sessions_filtered(&pool, "es", None, None, Some("read_aloud"), None, 0).await.unwrap();
+        assert_eq!(started(read_aloud), vec![3_000, 1_000]);
+        let read_aloud_before = get_sessions_filtered(&pool, "es", None, Some(2_000), Some("read_aloud"), None, 0)
+            .await
+            .unwrap();
+        assert_eq!(started(read_aloud_before), vec![1_000]);
+
+        // Untyped sessions count as free speaking
+        let free_speak = get_sessions_filtered(&pool, "es", None, None, Some("free_speak"), None, 0).await.unwrap();
+        assert_eq!(started(free_speak), vec![4_000, 2_000]);
+
+        // Paging applies after filtering
+        let page = get_sessions_filtered(&pool, "es", None, None, None, Some(2), 1).await.unwrap();
+        assert_eq!(started(page), vec![3_000, 2_000]);
+    }
+
+    #[tokio::test]
+    async fn test_recompute_session_stats_from_transcripts() {
+        let pool = setup_test_db().await;
+
+        let mut ids = Vec::new();
+        for (started_at, duration, transcript) in [
+            (1_000, 60, "Hola mundo, hola amigo."),
+            (2_000, 30, "¡Hola! Eh, ¿qué tal, amigo?"),
+        ] {
+            let id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+            // Stale stats from an older tokenizer
+            sqlx::query(
+                "UPDATE sessions SET started_at = ?, ended_at = ?, duration = ?, transcript = ?,
+                 word_count = 99, unique_word_count = 99, wpm = 99, new_word_count = 99 WHERE id = ?",
+            )
+            .bind(started_at)
+            .bind(started_at + duration)
+            .bind(duration)
+            .bind(transcript)
+            .bind(&id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        sqlx::query("UPDATE sessions SET segments = ? WHERE id = ?")
+            .bind(serde_json::to_string(&[segment(0.0, 6.0)]).unwrap())
+            .bind(&ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // "mundo" was in the vocabulary before the first session (from a deleted one),
+        // "tal" only since after the second
+        for (lemma, first_seen_at) in [("mundo", 500), ("tal", 5_000)] {
+            sqlx::query(
+                "INSERT INTO vocab (language, lemma, first_seen_at, last_seen_at, usage_count, created_at, updated_at)
+                 VALUES ('es', ?, ?, ?, 1, ?, ?)",
+            )
+            .bind(lemma)
+            .bind(first_seen_at)
+            .bind(first_seen_at)
+            .bind(first_seen_at)
+            .bind(first_seen_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let mut progress = Vec::new();
+        let updated = recompute_session_stats_with(
+            &pool,
+            |words, _, _| async move { words.into_iter().map(|w| (w.clone(), w)).collect() },
+            |p| progress.push((p.processed, p.total)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated, 2);
+        assert_eq!(progress, vec![(2, 2)]);
+
+        let first = get_session(&pool, &ids[0]).await.unwrap();
+        assert_eq!(first.word_count, Some(4));
+        assert_eq!(first.unique_word_count, Some(3));
+        assert_eq!(first.wpm, Some(4.0));
+        assert_eq!(first.new_word_count, Some(2));
+        assert_eq!(first.filler_count, Some(0));
+        assert_eq!(first.articulation_wpm, None);
+
+        // "hola" and "amigo" were already used in the first session
+        let second = get_session(&pool, &ids[1]).await.unwrap();
+        assert_eq!(second.word_count, Some(5));
+        assert_eq!(second.unique_word_count, Some(5));
+        assert_eq!(second.wpm, Some(10.0));
+        assert_eq!(second.new_word_count, Some(3));
+        assert_eq!(second.filler_count, Some(1));
+        assert_eq!(second.articulation_wpm, Some(40.0));
+
+        // Vocabulary isn't touched
+        let (vocab,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM vocab").fetch_one(&pool).await.unwrap();
+        assert_eq!(vocab, 2);
+    }
+
+    /// Whether a lemma is missing from the vocabulary
+    async fn is_new_word_for_user(pool: &SqlitePool, lemma: &str, language: &str) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vocab WHERE lemma = ? AND language = ?")
+            .bind(lemma)
+            .bind(language)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(count == 0)
+    }
+
+    /// Identity lemmatization for tests (no lemma database available)
+    fn as_lemmatized(words: &[&str]) -> Vec<(String, String)> {
+        words.iter().map(|w| (w.to_string(), w.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_session_replaces_words() {
+        let pool = setup_test_db().await;
+
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        sqlx::query("UPDATE sessions SET duration = 60, ended_at = started_at WHERE id = ?")
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Original (mis-transcribed) transcript: "hola mundo hola"
+        record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 3, 0, &as_lemmatized(&["hola", "mundo", "hola"]), 60, 0.0, "es")
+            .await
+            .unwrap();
+
+        // Corrected transcript: "hola amigo"
+        let stats = replace_session_transcript(
+            &pool,
+            &session_id,
+            "Hola amigo",
+            2,
+            &as_lemmatized(&["hola", "amigo"]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stats.word_count, 2);
+        assert_eq!(stats.unique_word_count, 2);
+        assert_eq!(stats.wpm, 2.0);
+
+        let session = get_session(&pool, &session_id).await.unwrap();
+        assert_eq!(session.word_count, Some(2));
+        assert_eq!(session.transcript.as_deref(), Some("Hola amigo"));
+
+        let words: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT lemma, count FROM session_words WHERE session_id = ? ORDER BY lemma"
+        )
+        .bind(&session_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(words, vec![("amigo".to_string(), 1), ("hola".to_string(), 1)]);
+
+        // Usage counts reflect only the corrected transcript, and "mundo" is gone
+        let vocab: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT lemma, usage_count FROM vocab WHERE language = 'es' ORDER BY lemma"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(vocab, vec![("amigo".to_string(), 1), ("hola".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_session_keeps_vocabulary_history() {
+        let pool = setup_test_db().await;
+
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 2, 0, &as_lemmatized(&["hola", "mundo"]), 60, 0.0, "es")
+            .await
+            .unwrap();
+        // Seen long ago; "mundo" mastered without a tag
+        sqlx::query("UPDATE vocab SET first_seen_at = 100")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE vocab SET mastered = 1 WHERE lemma = 'mundo'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stats = replace_session_transcript(&pool, &session_id, "Hola hola", 2, &as_lemmatized(&["hola", "hola"]))
+            .await
+            .unwrap();
+        assert_eq!(stats.new_word_count, 1);
+
+        let vocab: Vec<(String, i64, Option<String>, bool, i64)> = sqlx::query_as(
+            "SELECT lemma, first_seen_at, first_session_id, mastered, usage_count FROM vocab ORDER BY lemma"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            vocab,
+            vec![
+                ("hola".to_string(), 100, Some(session_id.clone()), false, 2),
+                ("mundo".to_string(), 100, Some(session_id.clone()), true, 0),
+            ]
+        );
+        assert!(get_session_words(&pool, &session_id).await.unwrap().iter().all(|w| w.lemma == "hola" && w.is_new));
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_read_aloud_session_rescores_accuracy() {
+        let pool = setup_test_db().await;
+
+        let session_id = create_session(&pool, "es", "en", Some("read_aloud"), None, Some("hola mundo")).await.unwrap();
+        sqlx::query("UPDATE sessions SET accuracy_score = 50.0 WHERE id = ?")
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        replace_session_transcript(&pool, &session_id, "Hola mundo", 2, &as_lemmatized(&["hola", "mundo"])).await.unwrap();
+
+        assert_eq!(get_session(&pool, &session_id).await.unwrap().accuracy_score, Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_session_normalizes_stored_language() {
+        let pool = setup_test_db().await;
+
+        let session_id = create_session(&pool, "ES", "en", None, None, None).await.unwrap();
+        record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 1, 0, &as_lemmatized(&["hola"]), 60, 0.0, "ES")
+            .await
+            .unwrap();
+
+        replace_session_transcript(&pool, &session_id, "Hola", 1, &as_lemmatized(&["hola"])).await.unwrap();
+
+        assert_eq!(get_session(&pool, &session_id).await.unwrap().language, "es");
+        let vocab: Vec<(String, i64)> = sqlx::query_as("SELECT language, usage_count FROM vocab WHERE lemma = 'hola'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(vocab, vec![("es".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_word_doesnt_fail_session() {
+        let pool = setup_test_db().await;
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+
+        // Simulate a write failing for one word
+        sqlx::query(
+            "CREATE TRIGGER fail_mundo BEFORE INSERT ON vocab WHEN NEW.lemma = 'mundo' BEGIN SELECT RAISE(ABORT, 'database is locked'); END"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // ... and linking another to the session
+        sqlx::query(
+            "CREATE TRIGGER fail_amigo_link BEFORE INSERT ON session_words WHEN NEW.lemma = 'amigo' BEGIN SELECT RAISE(ABORT, 'database is locked'); END"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let stats = record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 4, 0, &as_lemmatized(&["hola", "mundo", "amigo", "hola"]), 60, 0.0, "es")
+            .await
+            .unwrap();
+
+        assert_eq!(stats.unique_word_count, 3);
+        assert_eq!(stats.new_word_count, 2);
+        let failed: Vec<(&str, bool)> = stats.failed_words.iter().map(|f| (f.lemma.as_str(), f.recorded)).collect();
+        assert_eq!(failed, vec![("mundo", false), ("amigo", true)]);
+        assert!(stats.failed_words[0].error.contains("database is locked"));
+
+        let lemmas = || async {
+            sqlx::query_scalar::<_, String>("SELECT lemma FROM session_words WHERE session_id = ? ORDER BY lemma")
+                .bind(&session_id)
+                .fetch_all(&pool)
+                .await
+                .unwrap()
+        };
+        assert_eq!(lemmas().await, vec!["hola"]);
+        assert!(is_new_word_for_user(&pool, "mundo", "es").await.unwrap());
+        assert!(!is_new_word_for_user(&pool, "amigo", "es").await.unwrap());
+
+        // Still failing: nothing changes
+        let still_failed = retry_failed_words(&pool, &session_id, &stats.failed_words).await.unwrap();
+        assert_eq!(still_failed.len(), 2);
+
+        sqlx::query("DROP TRIGGER fail_mundo").execute(&pool).await.unwrap();
+        sqlx::query("DROP TRIGGER fail_amigo_link").execute(&pool).await.unwrap();
+        let still_failed = retry_failed_words(&pool, &session_id, &still_failed).await.unwrap();
+        assert!(still_failed.is_empty());
+        assert_eq!(lemmas().await, vec!["amigo", "hola", "mundo"]);
+        assert!(!is_new_word_for_user(&pool, "mundo", "es").await.unwrap());
+
+        // The linked word isn't recorded a second time and is still new in this session
+        let (amigo_usage,): (i64,) = sqlx::query_as("SELECT usage_count FROM vocab WHERE lemma = 'amigo'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(amigo_usage, 1);
+        let words = get_session_words(&pool, &session_id).await.unwrap();
+        assert!(words.iter().any(|w| w.lemma == "amigo" && w.is_new));
+        // Only "mundo" is added; "amigo" was counted when it was recorded
+        assert_eq!(get_session(&pool, &session_id).await.unwrap().new_word_count, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_session_words_is_new_for_first_use_only() {
+        let pool = setup_test_db().await;
+
+        let mut ids = Vec::new();
+        for (started_at, words) in [(1_000, ["hola", "amigo", "hola"]), (2_000, ["hola", "gato", "gato"])] {
+            let id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+            sqlx::query("UPDATE sessions SET started_at = ? WHERE id = ?")
+                .bind(started_at)
+                .bind(&id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let stats = record_session_words(&mut pool.acquire().await.unwrap(), &id, 3, 0, &as_lemmatized(&words), 60, 0.0, "es")
+                .await
+                .unwrap();
+            sqlx::query("UPDATE sessions SET new_word_count = ? WHERE id = ?")
+                .bind(stats.new_word_count)
+                .bind(&id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        let newness = |id: String| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_as::<_, (String, bool)>("SELECT lemma, is_new FROM session_words WHERE session_id = ? ORDER BY lemma")
+                    .bind(id)
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+        let first = vec![("amigo".to_string(), true), ("hola".to_string(), true)];
+        let second = vec![("gato".to_string(), true), ("hola".to_string(), false)];
+        assert_eq!(newness(ids[0].clone()).await, first);
+        assert_eq!(newness(ids[1].clone()).await, second);
+
+        // Historical data saved with is_new always false gets corrected
+        sqlx::query("UPDATE session_words SET is_new = 0").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE sessions SET new_word_count = 0").execute(&pool).await.unwrap();
+        assert_eq!(recompute_session_word_newness(&pool, &ids[0]).await.unwrap(), 2);
+        assert_eq!(recompute_session_word_newness(&pool, &ids[1]).await.unwrap(), 1);
+        assert_eq!(newness(ids[0].clone()).await, first);
+        assert_eq!(newness(ids[1].clone()).await, second);
+        assert_eq!(get_session(&pool, &ids[1]).await.unwrap().new_word_count, Some(1));
+
+        // A word already in the vocabulary before the session isn't new
+        sqlx::query("UPDATE vocab SET first_seen_at = 500 WHERE lemma = 'gato'").execute(&pool).await.unwrap();
+        assert_eq!(recompute_session_word_newness(&pool, &ids[1]).await.unwrap(), 0);
+
+        assert!(recompute_session_word_newness(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_pages() {
+        let pool = setup_test_db().await;
+
+        // 5 Spanish and 2 French finished sessions, plus one still in progress
+        for (i, language) in ["es", "es", "es", "es", "es", "fr", "fr"].iter().enumerate() {
+            let id = create_session(&pool, language, "en", None, None, None).await.unwrap();
+            sqlx::query("UPDATE sessions SET started_at = ?, ended_at = ? WHERE id = ?")
+                .bind(1000 + i as i64)
+                .bind(2000 + i as i64)
+                .bind(&id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        create_session(&pool, "es", "en", None, None, None).await.unwrap();
+
+        let page = get_sessions_by_language_paged(&pool, "es", 2, 0).await.unwrap();
+        assert_eq!(page.total, 5);
+        let starts: Vec<i64> = page.items.iter().map(|s| s.started_at).collect();
+        assert_eq!(starts, vec![1004, 1003]);
+
+        let last = get_sessions_by_language_paged(&pool, "es", 2, 4).await.unwrap();
+        let starts: Vec<i64> = last.items.iter().map(|s| s.started_at).collect();
+        assert_eq!(starts, vec![1000]);
+
+        let all = get_all_sessions_paged(&pool, 3, 1).await.unwrap();
+        assert_eq!(all.total, 7);
+        let starts: Vec<i64> = all.items.iter().map(|s| s.started_at).collect();
+        assert_eq!(starts, vec![1005, 1004, 1003]);
+
+        assert_eq!(get_all_sessions(&pool).await.unwrap().len(), 7);
+        assert!(get_all_sessions_paged(&pool, 0, 0).await.is_err());
+    }
+
+    fn segment(start_time: f32, end_time: f32) -> TranscriptSegment {
+        TranscriptSegment {
+            text: String::new(),
+            start_time,
+            end_time,
+            confidence: None,
+            no_speech_prob: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_rolls_back_session() {
+        let pool = setup_test_db().await;
+
+        let started = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        rollback_session_on_error(&pool, &started, Ok(())).await.unwrap();
+        assert!(get_session(&pool, &started).await.is_ok());
+
+        // The recorder failing to open the device
+        let failed = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        let err = rollback_session_on_error(&pool, &failed, Err::<(), _>("No input device available".to_string()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No input device"));
+        assert!(get_session(&pool, &failed).await.is_err());
+        assert!(get_session(&pool, &started).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_toggle_and_list_favorite_sessions() {
+        let pool = setup_test_db().await;
+
+        let mut ids = Vec::new();
+        for (started_at, language) in [(1_000, "es"), (2_000, "es"), (3_000, "fr")] {
+            let id = create_session(&pool, language, "en", None, None, None).await.unwrap();
+            sqlx::query("UPDATE sessions SET started_at = ?, ended_at = ? WHERE id = ?")
+                .bind(started_at)
+                .bind(started_at + 60)
+                .bind(&id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        assert!(!get_session(&pool, &ids[0]).await.unwrap().is_favorite);
+        assert!(toggle_session_favorite(&pool, &ids[0]).await.unwrap());
+        assert!(toggle_session_favorite(&pool, &ids[1]).await.unwrap());
+        assert!(toggle_session_favorite(&pool, &ids[2]).await.unwrap());
+        assert!(!toggle_session_favorite(&pool, &ids[1]).await.unwrap());
+        assert!(toggle_session_favorite(&pool, "missing").await.is_err());
+
+        let session = get_session(&pool, &ids[0]).await.unwrap();
+        assert!(session.is_favorite);
+        assert!(!session.is_pinned);
+
+        let spanish: Vec<String> = get_favorite_sessions(&pool, Some("es")).await.unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(spanish, vec![ids[0].clone()]);
+
+        let all: Vec<String> = get_favorite_sessions(&pool, None).await.unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(all, vec![ids[2].clone(), ids[0].clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_articulation_wpm_excludes_pauses_and_fillers() {
+        let pool = setup_test_db().await;
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+
+        // 60s recording with 30s of speech in two segments (the rest is pauses)
+        let segments = [segment(5.0, 20.0), segment(40.0, 55.0)];
+        assert_eq!(speaking_seconds(&segments), 30.0);
+
+        // 12 words, 2 of them fillers
+        let words: Vec<&str> = vec!["hola"; 12];
+        let stats = record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 12, 2, &as_lemmatized(&words), 60, speaking_seconds(&segments), "es")
+            .await
+            .unwrap();
+
+        assert_eq!(stats.wpm, 12.0);
+        assert_eq!(stats.articulation_wpm, Some(20.0));
+
+        // No segment timings: articulation rate is unknown
+        let other = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        let stats = record_session_words(&mut pool.acquire().await.unwrap(), &other, 12, 2, &as_lemmatized(&words), 60, 0.0, "es")
+            .await
+            .unwrap();
+        assert_eq!(stats.articulation_wpm, None);
+    }
+
+    #[test]
+    fn test_score_read_aloud_exact_match() {
+        let score = score_read_aloud("Hola, ¿cómo estás?", "hola cómo estás");
+
+        assert_eq!(score.accuracy, 100.0);
+        assert!(score.missed.is_empty());
+        assert!(score.inserted.is_empty());
+        assert!(score.substituted.is_empty());
+    }
+
+    #[test]
+    fn test_score_read_aloud_omission() {
+        let score = score_read_aloud("el perro come mucho", "el perro mucho");
+
+        assert_eq!(score.accuracy, 75.0);
+        assert_eq!(score.missed, vec!["come".to_string()]);
+        assert!(score.substituted.is_empty());
+        assert_eq!(
+            score.words[2],
+            WordDiff::Missed { expected: "come".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_score_read_aloud_substitution_and_insertion() {
+        let score = score_read_aloud("la casa es grande", "la cosa es muy grande");
+
+        assert_eq!(score.accuracy, 75.0);
+        assert_eq!(score.substituted, vec![("casa".to_string(), "cosa".to_string())]);
+        assert_eq!(score.inserted, vec!["muy".to_string()]);
+        assert!(score.missed.is_empty());
+    }
+
+    #[test]
+    fn test_count_filler_words_english() {
+        let counts = count_filler_words("Um, I was, like, going to the store, you know? Um yeah.", "en");
+
+        assert_eq!(counts.total, 4);
+        assert_eq!(
+            counts.breakdown,
+            vec![
+                FillerWord { filler: "um".to_string(), count: 2 },
+                FillerWord { filler: "like".to_string(), count: 1 },
+                FillerWord { filler: "you know".to_string(), count: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_filler_words_spanish() {
+        let counts = count_filler_words("Este... fui al mercado, o sea, eh, pues, compré pan. Este sí.", "es");
+
+        // The second "este" means "this one"
+        assert_eq!(counts.total, 4);
+        assert!(counts.breakdown.contains(&FillerWord { filler: "este".to_string(), count: 1 }));
+        assert!(counts.breakdown.contains(&FillerWord { filler: "o sea".to_string(), count: 1 }));
+        assert!(counts.breakdown.contains(&FillerWord { filler: "eh".to_string(), count: 1 }));
+        assert!(counts.breakdown.contains(&FillerWord { filler: "pues".to_string(), count: 1 }));
+
+        // English fillers aren't counted in Spanish
+        assert_eq!(count_filler_words("um like", "es").total, 0);
+    }
+
+    #[test]
+    fn test_filler_markers_only_count_between_pauses() {
+        assert_eq!(count_filler_words("Ich habe also keine Zeit.", "de").total, 0);
+        assert_eq!(count_filler_words("Also, ich weiß nicht, äh, also.", "de").total, 3);
+        assert_eq!(count_filler_words("Ele é meu amigo, é.", "pt").total, 1);
+        assert_eq!(count_filler_words("I like it. Um like, you know.", "en").total, 3);
+    }
+
+    #[test]
+    fn test_tokenize_keeps_hyphens_and_apostrophes_inside_words() {
+        assert_eq!(
+            tokenize_transcript("Well-being matters, don't you think? L'eau -- c'est ‘bon’."),
+            vec!["well-being", "matters", "don't", "you", "think", "l'eau", "c'est", "bon"]
+        );
+        // Dashes and quotes around words separate them
+        assert_eq!(tokenize_transcript("'hola'-'adiós' - sí-"), vec!["hola", "adiós", "sí"]);
+    }
+
+    #[test]
+    fn test_tokenize_numbers() {
+        assert_eq!(
+            tokenize_transcript("En 2024 gasté 1,000 euros, o 3.5 por día."),
+            vec!["en", "2024", "gasté", "1,000", "euros", "o", "3.5", "por", "día"]
+        );
+
+        assert!(is_number_token("2024"));
+        assert!(is_number_token("1,000"));
+        assert!(is_number_token("3.5"));
+        assert!(!is_number_token("2nd"));
+        assert!(!is_number_token("mp3"));
+    }
+
+    #[test]
+    fn test_tokenize_keeps_accented_characters() {
+        assert_eq!(
+            tokenize_transcript("¿Qué tal? ¡Mañana, Ñandú y Über!"),
+            vec!["qué", "tal", "mañana", "ñandú", "y", "über"]
+        );
+        // Decomposed accents (e + combining acute) stay part of the word
+        assert_eq!(tokenize_transcript("cafe\u{0301}, naïve"), vec!["cafe\u{0301}", "naïve"]);
+        assert_eq!(tokenize_transcript("Привет, мир"), vec!["привет", "мир"]);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_transcript_labels_word_status() {
+        let pool = setup_test_db().await;
+        let earlier = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        let session = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+        sqlx::query("UPDATE sessions SET transcript = 'Hola amigos, el gato come pan en 2024.' WHERE id = ?")
+            .bind(&session)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (lemma, mastered, first_session) in [
+            ("hola", false, &earlier),
+            ("amigo", false, &session),
+            ("gato", true, &earlier),
+            ("comer", false, &earlier),
+            ("pan", true, &session),
+        ] {
+            sqlx::query(
+                "INSERT INTO vocab (language, lemma, first_seen_at, last_seen_at, mastered, created_at, updated_at, first_session_id)
+                 VALUES ('es', ?, 0, 0, ?, 0, 0, ?)",
+            )
+            .bind(lemma)
+            .bind(mastered)
+            .bind(first_session)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let annotated = annotate_transcript_with(&pool, &session, |words, language, primary_language| async move {
+            assert_eq!((language.as_str(), primary_language.as_str()), ("es", "en"));
+            // Numbers are dropped, like lemmatize_words does by default
+            words
+                .into_iter()
+                .filter(|w| !is_number_token(w))
+                .map(|w| {
+                    let lemma = match w.as_str() {
+                        "amigos" => "amigo".to_string(),
+                        "come" => "comer".to_string(),
+                        _ => w.clone(),
+                    };
+                    (w, lemma)
+                })
+                .collect()
+        })
+        .await
+        .unwrap();
+
+        let labels: Vec<(&str, &str, WordStatus)> = annotated
+            .iter()
+            .map(|w| (w.word.as_str(), w.lemma.as_str(), w.status))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("hola", "hola", WordStatus::Known),
+                ("amigos", "amigo", WordStatus::New),
+                ("el", "el", WordStatus::Untracked),
+                ("gato", "gato", WordStatus::Mastered),
+                ("come", "comer", WordStatus::Known),
+                ("pan", "pan", WordStatus::Mastered),
+                ("en", "en", WordStatus::Untracked),
+                ("2024", "2024", WordStatus::Untracked),
+            ]
+        );
+
+        // Not transcribed yet
+        assert!(annotate_transcript_with(&pool, &earlier, |_, _, _| async { Vec::new() }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_session_segments() {
+        let pool = setup_test_db().await;
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+
+        // Not completed yet
+        assert!(get_session_segments(&pool, &session_id).await.unwrap().is_empty());
+
+        let segments = vec![
+            TranscriptSegment { text: " Hola.".to_string(), ..segment(0.0, 1.5) },
+            TranscriptSegment { text: " ¿Qué tal?".to_string(), ..segment(2.0, 3.25) },
+        ];
+        sqlx::query("UPDATE sessions SET ended_at = started_at, segments = ? WHERE id = ?")
+            .bind(serde_json::to_string(&segments).unwrap())
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stored = get_session_segments(&pool, &session_id).await.unwrap();
+        let timings: Vec<(&str, f32, f32)> = stored.iter().map(|s| (s.text.as_str(), s.start_time, s.end_time)).collect();
+        assert_eq!(timings, vec![(" Hola.", 0.0, 1.5), (" ¿Qué tal?", 2.0, 3.25)]);
+        assert!(get_session(&pool, &session_id).await.unwrap().segments.is_some());
+
+        assert!(get_session_segments(&pool, "missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_split_session_at_long_pause() {
+        let pool = setup_test_db().await;
+        let session_id = create_session(&pool, "es", "en", None, None, None).await.unwrap();
+
+        let text_segment = |text: &str, start_time: f32, end_time: f32| TranscriptSegment {
+            text: text.to_string(),
+            ..segment(start_time, end_time)
+        };
+        let segments = vec![
+            text_segment(" Hola amigo.", 0.0, 4.0),
+            text_segment(" ¿Qué tal?", 5.0, 8.0),
+            // Second attempt after a 30 second pause
+            text_segment(" Hola otra vez.", 38.0, 44.0),
+        ];
+        sqlx::query("UPDATE sessions SET started_at = 1000, ended_at = 1050, duration = 50, transcript = ?, segments = ? WHERE id = ?")
+            .bind("Hola amigo. ¿Qué tal? Hola otra vez.")
+            .bind(serde_json::to_string(&segments).unwrap())
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        record_session_words(&mut pool.acquire().await.unwrap(), &session_id, 7, 0, &as_lemmatized(&["hola", "amigo", "qué", "tal", "hola", "otra", "vez"]), 50, 13.0, "es")
+            .await
+            .unwrap();
+        sqlx::query("UPDATE vocab SET first_seen_at = 100").execute(&pool).await.unwrap();
+
+        // Nothing to split at with a larger threshold
+        let identity = |words: Vec<String>, _, _| async move { words.into_iter().map(|w| (w.clone(), w)).collect() };
+        assert!(split_session_with(&pool, &session_id, 60.0, identity).await.is_err());
+
+        // A recording that can't be cut leaves the session and its vocabulary untouched
+        let dir = tempfile::tempdir().unwrap();
+        let broken = dir.path().join("broken.wav");
+        std::fs::write(&broken, b"not a wav").unwrap();
+        sqlx::query("UPDATE sessions SET audio_path = ? WHERE id = ?")
+            .bind(broken.to_string_lossy().to_string())
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(split_session_with(&pool, &session_id, DEFAULT_SPLIT_GAP_SECONDS, identity).await.is_err());
+        assert_eq!(get_session_words(&pool, &session_id).await.unwrap().len(), 6);
+        let (session_count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM sessions").fetch_one(&pool).await.unwrap();
+        assert_eq!(session_count, 1);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        sqlx::query("UPDATE sessions SET audio_path = NULL WHERE id = ?")
+            .bind(&session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let parts = split_session_with(&pool, &session_id, DEFAULT_SPLIT_GAP_SECONDS, identity).await.unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(get_session(&pool, &session_id).await.is_err());
+
+        // Cut in the middle of the pause (8s-38s)
+        let first = get_session(&pool, &parts[0]).await.unwrap();
+        assert_eq!(first.transcript.as_deref(), Some("Hola amigo. ¿Qué tal?"));
+        assert_eq!((first.started_at, first.duration), (1000, Some(23)));
+        assert_eq!(first.word_count, Some(4));
+
+        let second = get_session(&pool, &parts[1]).await.unwrap();
+        assert_eq!(second.transcript.as_deref(), Some("Hola otra vez."));
+        assert_eq!((second.started_at, second.duration), (1023, Some(27)));
+        assert_eq!(second.word_count, Some(3));
+        assert_eq!(second.new_word_count, Some(2));
+
+        let (segments_json,): (String,) = sqlx::query_as("SELECT segments FROM sessions WHERE id = ?")
+            .bind(&parts[1])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let second_segments: Vec<TranscriptSegment> = serde_json::from_str(&segments_json).unwrap();
+        assert_eq!((second_segments[0].start_time, second_segments[0].end_time), (15.0, 21.0));
+
+        assert_eq!(first.new_word_count, Some(4));
+
+        // Vocabulary usage isn't counted twice, and words keep when they were first seen,
+        // introduced by the part that now contains them
+        let vocab: Vec<(String, i64, i64, String)> =
+            sqlx::query_as("SELECT lemma, usage_count, first_seen_at, first_session_id FROM vocab WHERE lemma IN ('hola', 'vez') ORDER BY lemma")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            vocab,
+            vec![("hola".to_string(), 2, 100, parts[0].clone()), ("vez".to_string(), 1, 100, parts[1].clone())]
+        );
+        let second_words = get_session_words(&pool, &parts[1]).await.unwrap();
+        assert!(second_words.iter().any(|w| w.lemma == "hola" && w.count == 1 && !w.is_new));
+        assert!(second_words.iter().any(|w| w.lemma == "vez" && w.is_new));
+    }
 }
@@ -1,13 +1,15 @@
 /**
  * Stats and analytics service
  *
- * Provides aggregate statistics across all sessions and vocabulary
+ * Provides aggregate statistics across all sessions and vocabulary,
+ * for one language, all languages combined, or per language
  */
 
 use anyhow::Result;
 use chrono::{Local, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::BTreeMap;
 
 /// Overall statistics summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +23,9 @@ pub struct OverallStats {
     pub longest_streak_days: i64,
     pub avg_unique_words_per_session: f64,
     pub avg_new_words_per_session: f64,
+    /// Longest session (sessions without a duration aren't counted)
+    pub max_session_duration_seconds: i64,
+    pub median_session_duration_seconds: f64,
 }
 
 /// Top word statistics
@@ -38,7 +43,10 @@ pub struct TopWord {
 pub struct DailySessionCount {
     pub date: String, // YYYY-MM-DD format
     pub session_count: i64,
+    /// Speaking time rounded up to whole minutes
     pub total_minutes: i64,
+    /// Exact speaking time, for the frontend to format
+    pub total_seconds: i64,
 }
 
 /// WPM trend data point
@@ -132,6 +140,19 @@ pub async fn get_overall_stats(pool: &SqlitePool, language: Option<&str>) -> Res
             .await?
     };
 
+    // Session lengths
+    let durations: Vec<i64> = if let Some(lang) = language {
+        sqlx::query_scalar("SELECT duration FROM sessions WHERE language = ? AND duration IS NOT NULL ORDER BY duration")
+            .bind(lang)
+            .fetch_all(pool)
+            .await?
+    } else {
+        sqlx::query_scalar("SELECT duration FROM sessions WHERE duration IS NOT NULL ORDER BY duration")
+            .fetch_all(pool)
+            .await?
+    };
+    let (max_duration, median_duration) = duration_spread(&durations);
+
     // Calculate streaks
     let daily_counts = get_daily_session_counts(pool, language, None).await?;
     let (current_streak, longest_streak) = calculate_streaks(&daily_counts);
@@ -145,9 +166,130 @@ pub async fn get_overall_stats(pool: &SqlitePool, language: Option<&str>) -> Res
         longest_streak_days: longest_streak,
         avg_unique_words_per_session: avg_unique.unwrap_or(0.0),
         avg_new_words_per_session: avg_new.unwrap_or(0.0),
+        max_session_duration_seconds: max_duration,
+        median_session_duration_seconds: median_duration,
     })
 }
 
+/// Longest and median of sorted session durations (0 when there are none)
+fn duration_spread(sorted_durations: &[i64]) -> (i64, f64) {
+    let n = sorted_durations.len();
+    if n == 0 {
+        return (0, 0.0);
+    }
+
+    let median = if n % 2 == 1 {
+        sorted_durations[n / 2] as f64
+    } else {
+        (sorted_durations[n / 2 - 1] + sorted_durations[n / 2]) as f64 / 2.0
+    };
+    (sorted_durations[n - 1], median)
+}
+
+/// Combined stats plus the same stats for each language
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStatsBreakdown {
+    pub total: OverallStats,
+    pub by_language: BTreeMap<String, OverallStats>,
+}
+
+/// (language, sessions, speaking time, avg wpm, avg unique words, avg new words)
+type LanguageSessionTotals = (String, i64, Option<i64>, Option<f64>, Option<f64>, Option<f64>);
+
+/// Get overall statistics for every language in one go, plus the combined total
+/// Uses grouped queries rather than one `get_overall_stats` call per language
+pub async fn get_stats_by_language(pool: &SqlitePool) -> Result<LanguageStatsBreakdown> {
+    let session_rows: Vec<LanguageSessionTotals> = sqlx::query_as(
+        r#"
+        SELECT language, COUNT(*), SUM(duration), AVG(wpm), AVG(unique_word_count), AVG(new_word_count)
+        FROM sessions
+        GROUP BY language
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let vocab_rows: Vec<(String, i64)> = sqlx::query_as("SELECT language, COUNT(*) FROM vocab GROUP BY language")
+        .fetch_all(pool)
+        .await?;
+
+    let day_rows: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT language, DATE(started_at, 'unixepoch', 'localtime') as date, COUNT(*)
+        FROM sessions
+        GROUP BY language, DATE(started_at, 'unixepoch', 'localtime')
+        ORDER BY date
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let duration_rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT language, duration FROM sessions WHERE duration IS NOT NULL ORDER BY duration",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut durations_by_language: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for (language, duration) in duration_rows {
+        durations_by_language.entry(language).or_default().push(duration);
+    }
+
+    let mut days_by_language: BTreeMap<String, Vec<DailySessionCount>> = BTreeMap::new();
+    for (language, date, session_count) in day_rows {
+        days_by_language.entry(language).or_default().push(DailySessionCount {
+            date,
+            session_count,
+            total_minutes: 0,
+            total_seconds: 0,
+        });
+    }
+
+    let empty = || OverallStats {
+        total_sessions: 0,
+        total_speaking_time_seconds: 0,
+        total_vocabulary_size: 0,
+        average_wpm: 0.0,
+        current_streak_days: 0,
+        longest_streak_days: 0,
+        avg_unique_words_per_session: 0.0,
+        avg_new_words_per_session: 0.0,
+        max_session_duration_seconds: 0,
+        median_session_duration_seconds: 0.0,
+    };
+
+    let mut by_language: BTreeMap<String, OverallStats> = BTreeMap::new();
+    for (language, sessions, time, wpm, unique, new) in session_rows {
+        let (current_streak, longest_streak) =
+            calculate_streaks(days_by_language.get(&language).map_or(&[][..], |d| d.as_slice()));
+        let (max_duration, median_duration) =
+            duration_spread(durations_by_language.get(&language).map_or(&[][..], |d| d.as_slice()));
+
+        by_language.insert(language, OverallStats {
+            total_sessions: sessions,
+            total_speaking_time_seconds: time.unwrap_or(0),
+            average_wpm: wpm.unwrap_or(0.0),
+            current_streak_days: current_streak,
+            longest_streak_days: longest_streak,
+            avg_unique_words_per_session: unique.unwrap_or(0.0),
+            avg_new_words_per_session: new.unwrap_or(0.0),
+            max_session_duration_seconds: max_duration,
+            median_session_duration_seconds: median_duration,
+            ..empty()
+        });
+    }
+
+    // Languages with vocabulary but no sessions still get an entry
+    for (language, vocab_size) in vocab_rows {
+        by_language.entry(language).or_insert_with(empty).total_vocabulary_size = vocab_size;
+    }
+
+    let total = get_overall_stats(pool, None).await?;
+
+    Ok(LanguageStatsBreakdown { total, by_language })
+}
+
 /// Get top N most practiced words
 pub async fn get_top_words(
     pool: &SqlitePool,
@@ -191,12 +333,13 @@ pub async fn get_daily_session_counts(
 ) -> Result<Vec<DailySessionCount>> {
     let rows = match (language, days) {
         (Some(lang), Some(d)) => {
-            sqlx::query_as::<_, (String, i64, i64)>(
+            sqlx::query_as::<_, (String, i64, i64, i64)>(
                 r#"
                 SELECT
                     DATE(started_at, 'unixepoch', 'localtime') as date,
                     COUNT(*) as session_count,
-                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes
+                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes,
+                    COALESCE(SUM(duration), 0) as total_seconds
                 FROM sessions
                 WHERE language = ? AND started_at >= strftime('%s', 'now', '-' || ? || ' days')
                 GROUP BY DATE(started_at, 'unixepoch', 'localtime')
@@ -209,12 +352,13 @@ pub async fn get_daily_session_counts(
             .await?
         }
         (Some(lang), None) => {
-            sqlx::query_as::<_, (String, i64, i64)>(
+            sqlx::query_as::<_, (String, i64, i64, i64)>(
                 r#"
                 SELECT
                     DATE(started_at, 'unixepoch', 'localtime') as date,
                     COUNT(*) as session_count,
-                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes
+                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes,
+                    COALESCE(SUM(duration), 0) as total_seconds
                 FROM sessions
                 WHERE language = ?
                 GROUP BY DATE(started_at, 'unixepoch', 'localtime')
@@ -226,12 +370,13 @@ pub async fn get_daily_session_counts(
             .await?
         }
         (None, Some(d)) => {
-            sqlx::query_as::<_, (String, i64, i64)>(
+            sqlx::query_as::<_, (String, i64, i64, i64)>(
                 r#"
                 SELECT
                     DATE(started_at, 'unixepoch', 'localtime') as date,
                     COUNT(*) as session_count,
-                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes
+                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes,
+                    COALESCE(SUM(duration), 0) as total_seconds
                 FROM sessions
                 WHERE started_at >= strftime('%s', 'now', '-' || ? || ' days')
                 GROUP BY DATE(started_at, 'unixepoch', 'localtime')
@@ -243,12 +388,13 @@ pub async fn get_daily_session_counts(
             .await?
         }
         (None, None) => {
-            sqlx::query_as::<_, (String, i64, i64)>(
+            sqlx::query_as::<_, (String, i64, i64, i64)>(
                 r#"
                 SELECT
                     DATE(started_at, 'unixepoch', 'localtime') as date,
                     COUNT(*) as session_count,
-                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes
+                    COALESCE((SUM(duration) + 59) / 60, 0) as total_minutes,
+                    COALESCE(SUM(duration), 0) as total_seconds
                 FROM sessions
                 GROUP BY DATE(started_at, 'unixepoch', 'localtime')
                 ORDER BY date
@@ -261,10 +407,11 @@ pub async fn get_daily_session_counts(
 
     let daily_counts = rows
         .into_iter()
-        .map(|(date, count, minutes)| DailySessionCount {
+        .map(|(date, count, minutes, seconds)| DailySessionCount {
             date,
             session_count: count,
             total_minutes: minutes,
+            total_seconds: seconds,
         })
         .collect();
 
@@ -355,28 +502,63 @@ pub async fn get_wpm_trends(
     Ok(trends)
 }
 
-/// Get vocabulary growth over time
+/// Get vocabulary growth over time, optionally for the last `days` only
+/// Within a window the cumulative total still includes words learned before it.
 pub async fn get_vocab_growth(
     pool: &SqlitePool,
     language: &str,
+    days: Option<i64>,
 ) -> Result<Vec<VocabGrowth>> {
-    let rows = sqlx::query_as::<_, (String, i64)>(
-        r#"
-        SELECT
-            DATE(first_seen_at, 'unixepoch', 'localtime') as date,
-            COUNT(*) as new_words
-        FROM vocab
-        WHERE language = ?
-        GROUP BY DATE(first_seen_at, 'unixepoch', 'localtime')
-        ORDER BY date
-        "#,
-    )
-    .bind(language)
-    .fetch_all(pool)
-    .await?;
+    let (rows, words_before) = match days {
+        Some(d) => {
+            let rows = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                SELECT
+                    DATE(first_seen_at, 'unixepoch', 'localtime') as date,
+                    COUNT(*) as new_words
+                FROM vocab
+                WHERE language = ? AND first_seen_at >= strftime('%s', 'now', '-' || ? || ' days')
+                GROUP BY DATE(first_seen_at, 'unixepoch', 'localtime')
+                ORDER BY date
+                "#,
+            )
+            .bind(language)
+            .bind(d)
+            .fetch_all(pool)
+            .await?;
+
+            let words_before: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM vocab WHERE language = ? AND first_seen_at < strftime('%s', 'now', '-' || ? || ' days')",
+            )
+            .bind(language)
+            .bind(d)
+            .fetch_one(pool)
+            .await?;
+
+            (rows, words_before)
+        }
+        None => {
+            let rows = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                SELECT
+                    DATE(first_seen_at, 'unixepoch', 'localtime') as date,
+                    COUNT(*) as new_words
+                FROM vocab
+                WHERE language = ?
+                GROUP BY DATE(first_seen_at, 'unixepoch', 'localtime')
+                ORDER BY date
+                "#,
+            )
+            .bind(language)
+            .fetch_all(pool)
+            .await?;
+
+            (rows, 0)
+        }
+    };
 
-    // Calculate cumulative totals
-    let mut cumulative = 0i64;
+    // Calculate cumulative totals, starting from the words learned before the window
+    let mut cumulative = words_before;
     let growth = rows
         .into_iter()
         .map(|(date, new_words)| {
@@ -447,3 +629,189 @@ fn calculate_streaks(daily_counts: &[DailySessionCount]) -> (i64, i64) {
 
     (current_streak, longest_streak)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stats_by_language_and_combined() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE sessions (id INTEGER PRIMARY KEY, language TEXT NOT NULL, started_at INTEGER NOT NULL,
+             duration INTEGER, wpm REAL, unique_word_count INTEGER, new_word_count INTEGER)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let day = 86_400;
+        for (language, started_at, duration, wpm) in [
+            ("es", 1_000_000, 60, Some(100.0)),
+            ("es", 1_000_000 + day, 120, Some(80.0)),
+            ("es", 1_000_000 + 5 * day, 30, None),
+            ("fr", 1_000_000, 300, Some(50.0)),
+        ] {
+            sqlx::query(
+                "INSERT INTO sessions (language, started_at, duration, wpm, unique_word_count, new_word_count)
+                 VALUES (?, ?, ?, ?, 10, 2)",
+            )
+            .bind(language)
+            .bind(started_at)
+            .bind(duration)
+            .bind(wpm)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for (language, lemma) in [("es", "hola"), ("es", "casa"), ("fr", "chat"), ("de", "hund")] {
+            sqlx::query("INSERT INTO vocab (language, lemma) VALUES (?, ?)")
+                .bind(language)
+                .bind(lemma)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let stats = get_stats_by_language(&pool).await.unwrap();
+
+        let es = &stats.by_language["es"];
+        assert_eq!(es.total_sessions, 3);
+        assert_eq!(es.total_speaking_time_seconds, 210);
+        assert_eq!(es.total_vocabulary_size, 2);
+        assert_eq!(es.average_wpm, 90.0);
+        assert_eq!(es.longest_streak_days, 2);
+
+        let fr = &stats.by_language["fr"];
+        assert_eq!((fr.total_sessions, fr.total_speaking_time_seconds, fr.total_vocabulary_size), (1, 300, 1));
+        assert_eq!(fr.average_wpm, 50.0);
+
+        // Vocabulary without sessions
+        let de = &stats.by_language["de"];
+        assert_eq!((de.total_sessions, de.total_vocabulary_size), (0, 1));
+
+        assert_eq!(stats.total.total_sessions, 4);
+        assert_eq!(stats.total.total_speaking_time_seconds, 510);
+        assert_eq!(stats.total.total_vocabulary_size, 4);
+        assert!((stats.total.average_wpm - 230.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_daily_counts_keep_exact_seconds() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE sessions (id INTEGER PRIMARY KEY, language TEXT NOT NULL, started_at INTEGER NOT NULL, duration INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Three 40-second sessions on one day, a 5-second one (and an unfinished one) on the next
+        let day = 86_400;
+        for (started_at, duration) in [
+            (1_000_000, Some(40)),
+            (1_000_100, Some(40)),
+            (1_000_200, Some(40)),
+            (1_000_000 + day, Some(5)),
+            (1_000_100 + day, None),
+        ] {
+            sqlx::query("INSERT INTO sessions (language, started_at, duration) VALUES ('es', ?, ?)")
+                .bind(started_at)
+                .bind(duration)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let days = get_daily_session_counts(&pool, Some("es"), None).await.unwrap();
+        let totals: Vec<(i64, i64, i64)> = days
+            .iter()
+            .map(|d| (d.session_count, d.total_minutes, d.total_seconds))
+            .collect();
+        assert_eq!(totals, vec![(3, 2, 120), (2, 1, 5)]);
+
+        let all = get_daily_session_counts(&pool, None, None).await.unwrap();
+        assert_eq!(all.iter().map(|d| d.total_seconds).sum::<i64>(), 125);
+    }
+
+    #[tokio::test]
+    async fn test_session_duration_max_and_median() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE sessions (id INTEGER PRIMARY KEY, language TEXT NOT NULL, started_at INTEGER NOT NULL,
+             duration INTEGER, wpm REAL, unique_word_count INTEGER, new_word_count INTEGER)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // An unfinished session has no duration and isn't counted
+        for (language, duration) in [("es", Some(300)), ("es", Some(60)), ("es", Some(720)), ("es", None), ("fr", Some(90)), ("fr", Some(30))] {
+            sqlx::query("INSERT INTO sessions (language, started_at, duration) VALUES (?, 1000000, ?)")
+                .bind(language)
+                .bind(duration)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let es = get_overall_stats(&pool, Some("es")).await.unwrap();
+        assert_eq!(es.max_session_duration_seconds, 720);
+        assert_eq!(es.median_session_duration_seconds, 300.0);
+
+        let all = get_overall_stats(&pool, None).await.unwrap();
+        assert_eq!(all.max_session_duration_seconds, 720);
+        assert_eq!(all.median_session_duration_seconds, 90.0);
+
+        let breakdown = get_stats_by_language(&pool).await.unwrap();
+        assert_eq!(breakdown.by_language["fr"].max_session_duration_seconds, 90);
+        assert_eq!(breakdown.by_language["fr"].median_session_duration_seconds, 60.0);
+
+        let empty = get_overall_stats(&pool, Some("de")).await.unwrap();
+        assert_eq!((empty.max_session_duration_seconds, empty.median_session_duration_seconds), (0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_vocab_growth_window_keeps_earlier_words_in_total() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL, first_seen_at INTEGER NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let now = Utc::now().timestamp();
+        let day = 86_400;
+        for (language, lemma, days_ago) in [
+            ("es", "hola", 90),
+            ("es", "casa", 60),
+            ("es", "perro", 45),
+            ("es", "gato", 10),
+            ("es", "comer", 10),
+            ("es", "beber", 2),
+            ("fr", "chat", 60),
+        ] {
+            sqlx::query("INSERT INTO vocab (language, lemma, first_seen_at) VALUES (?, ?, ?)")
+                .bind(language)
+                .bind(lemma)
+                .bind(now - days_ago * day)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let window = get_vocab_growth(&pool, "es", Some(30)).await.unwrap();
+        let points: Vec<(i64, i64)> = window.iter().map(|g| (g.new_words, g.cumulative_total)).collect();
+        assert_eq!(points, vec![(2, 5), (1, 6)]);
+
+        let all = get_vocab_growth(&pool, "es", None).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[0].cumulative_total, 1);
+        assert_eq!(all.last().unwrap().cumulative_total, 6);
+    }
+}
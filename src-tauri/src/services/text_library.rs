@@ -3,17 +3,28 @@
  *
  * Manages imported texts for read-aloud practice:
  * - Creating text library items from various sources
- * - Retrieving and filtering text library items
+ * - Retrieving and filtering text library items (by language or tag)
  * - Updating and deleting text library items
- * - Calculating text statistics (word count, estimated duration)
+ * - Calculating text statistics (word count, estimated duration, difficulty)
+ * - Configurable reading speed for duration estimates
+ * - Importing articles from web pages
+ * - Measuring how much of a text the user already knows
+ * - Building practice texts from the user's weak words
  */
 
 use anyhow::{Context, Result};
 use chrono::Utc;
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use tauri::AppHandle;
 use uuid::Uuid;
 
+use crate::db::user::{get_setting, set_setting, READING_SPEED_SETTING};
+use super::lemmatization::get_lemma;
+use super::sessions::tokenize_transcript;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct TextLibraryItem {
@@ -59,9 +70,141 @@ fn calculate_word_count(text: &str) -> i64 {
     text.split_whitespace().count() as i64
 }
 
-/// Estimate reading duration in seconds (assumes 150 WPM average reading speed)
-fn estimate_duration(word_count: i64) -> i64 {
-    (word_count as f64 / 150.0 * 60.0) as i64
+/// Reading speed used when the user hasn't configured one
+pub const DEFAULT_READING_WPM: f64 = 150.0;
+
+/// Reading speeds accepted by `set_reading_speed`
+const READING_WPM_RANGE: std::ops::RangeInclusive<f64> = 20.0..=400.0;
+
+/// Words per minute the user reads aloud at, for text duration estimates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingSpeed {
+    /// Speed for languages without their own entry
+    pub default_wpm: f64,
+    /// Per-language overrides, e.g. {"de": 90}
+    #[serde(default)]
+    pub by_language: BTreeMap<String, f64>,
+}
+
+impl Default for ReadingSpeed {
+    fn default() -> Self {
+        Self {
+            default_wpm: DEFAULT_READING_WPM,
+            by_language: BTreeMap::new(),
+        }
+    }
+}
+
+impl ReadingSpeed {
+    /// Expected speed for a text, slower for harder texts
+    pub fn wpm_for(&self, language: &str, difficulty_level: Option<&str>) -> f64 {
+        let base = self.by_language.get(language).copied().unwrap_or(self.default_wpm);
+        base * difficulty_speed_factor(difficulty_level)
+    }
+}
+
+/// How much slower than their usual speed people read a text of this level
+fn difficulty_speed_factor(difficulty_level: Option<&str>) -> f64 {
+    match difficulty_level {
+        Some("A2") => 0.95,
+        Some("B1") => 0.9,
+        Some("B2") => 0.85,
+        Some("C1") | Some("C2") => 0.8,
+        _ => 1.0,
+    }
+}
+
+/// Get the configured reading speed (defaults if unset or unreadable)
+pub async fn get_reading_speed(pool: &SqlitePool) -> Result<ReadingSpeed> {
+    let Some(json) = get_setting(pool, READING_SPEED_SETTING).await? else {
+        return Ok(ReadingSpeed::default());
+    };
+
+    Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+        println!("[get_reading_speed] Ignoring invalid setting: {}", e);
+        ReadingSpeed::default()
+    }))
+}
+
+/// Save the reading speed; existing texts keep their estimates until edited
+pub async fn set_reading_speed(pool: &SqlitePool, speed: &ReadingSpeed) -> Result<()> {
+    let speeds = std::iter::once(&speed.default_wpm).chain(speed.by_language.values());
+    for wpm in speeds {
+        if !READING_WPM_RANGE.contains(wpm) {
+            anyhow::bail!(
+                "Reading speed must be between {} and {} WPM",
+                READING_WPM_RANGE.start(),
+                READING_WPM_RANGE.end()
+            );
+        }
+    }
+
+    let json = serde_json::to_string(speed).context("Failed to serialize reading speed")?;
+    set_setting(pool, READING_SPEED_SETTING, Some(&json)).await
+}
+
+/// Estimate reading duration in seconds at `wpm` words per minute
+fn estimate_duration(word_count: i64, wpm: f64) -> i64 {
+    (word_count as f64 / wpm * 60.0) as i64
+}
+
+/// Word length (in characters) from which a word counts as low-frequency
+///
+/// Language packs don't ship frequency lists, so long words stand in for rare ones.
+/// German compounds are long even when common, hence the higher threshold.
+fn long_word_threshold(language: &str) -> usize {
+    match language {
+        "de" | "nl" | "fi" => 11,
+        _ => 8,
+    }
+}
+
+/// Split text into sentences, keeping each sentence's closing punctuation
+fn split_sentences(content: &str) -> Vec<&str> {
+    content
+        .split_inclusive(['.', '!', '?', '。', '！', '？', '\n'])
+        .map(str::trim)
+        .filter(|sentence| sentence.chars().any(|c| c.is_alphanumeric()))
+        .collect()
+}
+
+/// Estimate a CEFR-style difficulty level (A1-C1) for a text
+///
+/// Combines average sentence length, type-token ratio and the share of long
+/// (low-frequency) words into a 0-1 score. Returns None for texts without words.
+pub fn estimate_difficulty(content: &str, language: &str) -> Option<String> {
+    let words = tokenize_transcript(content);
+    if words.is_empty() {
+        return None;
+    }
+
+    let sentence_count = split_sentences(content).len().max(1);
+    let avg_sentence_length = words.len() as f64 / sentence_count as f64;
+
+    // Type-token ratio drops as texts get longer, so only sample the opening
+    let sample = &words[..words.len().min(100)];
+    let unique: HashSet<&String> = sample.iter().collect();
+    let type_token_ratio = unique.len() as f64 / sample.len() as f64;
+
+    let threshold = long_word_threshold(language);
+    let long_words = words.iter().filter(|w| w.chars().count() >= threshold).count();
+    let long_word_share = long_words as f64 / words.len() as f64;
+
+    let sentence_score = ((avg_sentence_length - 5.0) / 20.0).clamp(0.0, 1.0);
+    let variety_score = ((type_token_ratio - 0.4) / 0.5).clamp(0.0, 1.0);
+    let rarity_score = (long_word_share / 0.3).clamp(0.0, 1.0);
+    let score = 0.4 * sentence_score + 0.2 * variety_score + 0.4 * rarity_score;
+
+    let level = match score {
+        s if s < 0.2 => "A1",
+        s if s < 0.4 => "A2",
+        s if s < 0.6 => "B1",
+        s if s < 0.8 => "B2",
+        _ => "C1",
+    };
+
+    Some(level.to_string())
 }
 
 /// Create a new text library item
@@ -74,7 +217,14 @@ pub async fn create_text_library_item(
 
     // Calculate stats
     let word_count = calculate_word_count(&input.content);
-    let estimated_duration = estimate_duration(word_count);
+    let difficulty_level = input
+        .difficulty_level
+        .clone()
+        .or_else(|| estimate_difficulty(&input.content, &input.language));
+    let wpm = get_reading_speed(pool)
+        .await?
+        .wpm_for(&input.language, difficulty_level.as_deref());
+    let estimated_duration = estimate_duration(word_count, wpm);
 
     // Serialize tags to JSON
     let tags_json = input.tags.as_ref().map(|t| serde_json::to_string(t).ok()).flatten();
@@ -96,7 +246,7 @@ pub async fn create_text_library_item(
     .bind(&input.language)
     .bind(word_count)
     .bind(estimated_duration)
-    .bind(&input.difficulty_level)
+    .bind(&difficulty_level)
     .bind(now)
     .bind(now)
     .bind(&tags_json)
@@ -162,6 +312,50 @@ pub async fn get_text_library_by_language(
     .context("Failed to get text library items by language")
 }
 
+/// Get text library items in a language that carry `tag`
+/// Tags are stored as a JSON array, so they're matched with json_each (exact match)
+pub async fn get_text_library_by_tag(
+    pool: &SqlitePool,
+    language: &str,
+    tag: &str,
+) -> Result<Vec<TextLibraryItem>> {
+    sqlx::query_as::<_, TextLibraryItem>(
+        r#"
+        SELECT id, title, source_type, source_url, content, language,
+               word_count, estimated_duration, difficulty_level,
+               created_at, updated_at, tags
+        FROM text_library
+        WHERE language = ?
+          AND json_valid(tags)
+          AND EXISTS (SELECT 1 FROM json_each(text_library.tags) WHERE json_each.value = ?)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(language)
+    .bind(tag)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get text library items by tag")
+}
+
+/// Get every distinct tag used by text library items in a language, alphabetically
+pub async fn get_all_text_library_tags(pool: &SqlitePool, language: &str) -> Result<Vec<String>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT json_each.value
+        FROM text_library, json_each(text_library.tags)
+        WHERE text_library.language = ?
+          AND json_valid(text_library.tags)
+          AND json_each.type = 'text'
+        ORDER BY json_each.value
+        "#,
+    )
+    .bind(language)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get text library tags")
+}
+
 /// Update a text library item
 pub async fn update_text_library_item(
     pool: &SqlitePool,
@@ -177,11 +371,17 @@ pub async fn update_text_library_item(
     let source_type = updates.source_type.unwrap_or(current.source_type);
     let source_url = updates.source_url.or(current.source_url);
     let content = updates.content.unwrap_or(current.content);
-    let difficulty_level = updates.difficulty_level.or(current.difficulty_level);
+    let difficulty_level = updates
+        .difficulty_level
+        .or(current.difficulty_level)
+        .or_else(|| estimate_difficulty(&content, &current.language));
 
-    // Recalculate stats if content changed
+    // Recalculate stats (the reading speed may have changed too)
     let word_count = calculate_word_count(&content);
-    let estimated_duration = estimate_duration(word_count);
+    let wpm = get_reading_speed(pool)
+        .await?
+        .wpm_for(&current.language, difficulty_level.as_deref());
+    let estimated_duration = estimate_duration(word_count, wpm);
 
     // Serialize tags
     let tags_json = updates.tags.as_ref()
@@ -233,10 +433,620 @@ pub async fn delete_text_library_item(pool: &SqlitePool, id: &str) -> Result<()>
     Ok(())
 }
 
+/// Known-word coverage of a text against the user's vocabulary
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextCoverage {
+    pub total_lemmas: i64,
+    pub known_count: i64,
+    pub unknown_count: i64,
+    /// Percentage of unique lemmas already in the vocabulary (0-100)
+    pub coverage_percent: f64,
+    /// Unknown lemmas, alphabetically sorted
+    pub unknown_lemmas: Vec<String>,
+}
+
+/// Compare a set of unique lemmas against the user's vocabulary for a language
+async fn calculate_coverage(
+    pool: &SqlitePool,
+    lemmas: &BTreeSet<String>,
+    language: &str,
+) -> Result<TextCoverage> {
+    let known: HashSet<String> = sqlx::query_scalar("SELECT lemma FROM vocab WHERE language = ?")
+        .bind(language)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load vocabulary")?
+        .into_iter()
+        .collect();
+
+    let unknown_lemmas: Vec<String> = lemmas
+        .iter()
+        .filter(|lemma| !known.contains(*lemma))
+        .cloned()
+        .collect();
+
+    let total_lemmas = lemmas.len() as i64;
+    let unknown_count = unknown_lemmas.len() as i64;
+    let known_count = total_lemmas - unknown_count;
+    let coverage_percent = if total_lemmas > 0 {
+        known_count as f64 / total_lemmas as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(TextCoverage {
+        total_lemmas,
+        known_count,
+        unknown_count,
+        coverage_percent,
+        unknown_lemmas,
+    })
+}
+
+/// Compute what share of a text's unique lemmas the user already knows
+pub async fn get_text_coverage(
+    pool: &SqlitePool,
+    text_id: &str,
+    language: &str,
+    app_handle: &AppHandle,
+) -> Result<TextCoverage> {
+    let item = get_text_library_item(pool, text_id).await?;
+
+    // Lemmatize each distinct surface form once
+    let words: BTreeSet<String> = tokenize_transcript(&item.content).into_iter().collect();
+    let mut lemmas = BTreeSet::new();
+    for word in words {
+        let lemma = get_lemma(&word, language, app_handle)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(word);
+        lemmas.insert(lemma);
+    }
+
+    calculate_coverage(pool, &lemmas, language).await
+}
+
+/// Vocabulary tag marking words the user wants to practice
+pub const NEEDS_PRACTICE_TAG: &str = "needs-practice";
+
+/// Most words targeted by one practice text
+const MAX_PRACTICE_WORDS: i64 = 15;
+
+/// Words used at most this often count as weak when nothing carries the practice tag
+const LOW_USAGE_COUNT: i64 = 2;
+
+/// Most recent transcripts searched for example sentences
+const PRACTICE_TRANSCRIPT_LIMIT: i64 = 200;
+
+/// A read-aloud text assembled from the user's own sentences
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PracticeText {
+    pub content: String,
+    /// Lemmas the text practices (each appears at least once)
+    pub target_words: Vec<String>,
+    /// Weak lemmas no stored transcript has an example sentence for
+    pub missing_words: Vec<String>,
+}
+
+/// Pick example sentences so that every weak word appears at least once
+///
+/// `targets` maps each lemma to the surface forms it was spoken as. Greedily takes the
+/// sentence covering the most uncovered words (the most recent transcript wins ties)
+/// so the text stays short.
+fn assemble_practice_text(targets: &[(String, Vec<String>)], transcripts: &[String]) -> PracticeText {
+    let sentences: Vec<(&str, HashSet<String>)> = transcripts
+        .iter()
+        .flat_map(|transcript| split_sentences(transcript))
+        .map(|sentence| (sentence, tokenize_transcript(sentence).into_iter().collect()))
+        .collect();
+
+    let covers = |target: &(String, Vec<String>), words: &HashSet<String>| {
+        let (lemma, forms) = target;
+        words.contains(&lemma.to_lowercase()) || forms.iter().any(|f| words.contains(&f.to_lowercase()))
+    };
+
+    let mut uncovered: Vec<usize> = (0..targets.len()).collect();
+    let mut chosen: Vec<usize> = Vec::new();
+
+    loop {
+        let best = sentences
+            .iter()
+            .enumerate()
+            .map(|(i, (_, words))| (i, uncovered.iter().filter(|&&t| covers(&targets[t], words)).count()))
+            .filter(|&(_, count)| count > 0)
+            .max_by_key(|&(i, count)| (count, std::cmp::Reverse(i)));
+
+        let Some((i, _)) = best else { break };
+        uncovered.retain(|&t| !covers(&targets[t], &sentences[i].1));
+        chosen.push(i);
+    }
+
+    let target_words = (0..targets.len())
+        .filter(|t| !uncovered.contains(t))
+        .map(|t| targets[t].0.clone())
+        .collect();
+    let missing_words = uncovered.iter().map(|&t| targets[t].0.clone()).collect();
+
+    // Keep sentences from the same transcript in their original order
+    chosen.sort_unstable();
+    let content = chosen
+        .iter()
+        .map(|&i| sentences[i].0)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    PracticeText {
+        content,
+        target_words,
+        missing_words,
+    }
+}
+
+/// Build a practice text from sentences the user has spoken containing their weak words
+///
+/// Weak words are vocabulary words carrying `tag` (usually `NEEDS_PRACTICE_TAG`);
+/// if none do, rarely used words that aren't mastered are practiced instead.
+pub async fn build_practice_text(pool: &SqlitePool, language: &str, tag: &str) -> Result<PracticeText> {
+    let mut rows: Vec<(String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT lemma, forms_spoken
+        FROM vocab
+        WHERE language = ?
+          AND EXISTS (SELECT 1 FROM json_each(COALESCE(vocab.tags, '[]')) WHERE json_each.value = ?)
+        ORDER BY usage_count ASC, last_seen_at ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(language)
+    .bind(tag)
+    .bind(MAX_PRACTICE_WORDS)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load tagged words")?;
+
+    if rows.is_empty() {
+        rows = sqlx::query_as(
+            r#"
+            SELECT lemma, forms_spoken
+            FROM vocab
+            WHERE language = ? AND mastered = 0 AND usage_count <= ?
+            ORDER BY usage_count ASC, last_seen_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(language)
+        .bind(LOW_USAGE_COUNT)
+        .bind(MAX_PRACTICE_WORDS)
+        .fetch_all(pool)
+        .await
+        .context("Failed to load rarely used words")?;
+    }
+
+    let targets: Vec<(String, Vec<String>)> = rows
+        .into_iter()
+        .map(|(lemma, forms)| {
+            let forms = forms
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            (lemma, forms)
+        })
+        .collect();
+
+    let transcripts: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT transcript
+        FROM sessions
+        WHERE language = ? AND transcript IS NOT NULL AND transcript != ''
+        ORDER BY started_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(language)
+    .bind(PRACTICE_TRANSCRIPT_LIMIT)
+    .fetch_all(pool)
+    .await
+    .context("Failed to load transcripts")?;
+
+    let practice = assemble_practice_text(&targets, &transcripts);
+    println!(
+        "[build_practice_text] {} words practiced, {} without example sentences",
+        practice.target_words.len(),
+        practice.missing_words.len()
+    );
+
+    Ok(practice)
+}
+
+/// Store a practice text in the text library so it can be read aloud
+pub async fn save_practice_text(
+    pool: &SqlitePool,
+    language: &str,
+    practice: &PracticeText,
+) -> Result<TextLibraryItem> {
+    if practice.content.trim().is_empty() {
+        anyhow::bail!("Practice text is empty");
+    }
+
+    create_text_library_item(
+        pool,
+        CreateTextLibraryItem {
+            title: format!("Practice: {}", practice.target_words.join(", ")),
+            source_type: "practice".to_string(),
+            source_url: None,
+            content: practice.content.clone(),
+            language: language.to_string(),
+            difficulty_level: None,
+            tags: Some(vec!["practice".to_string()]),
+        },
+    )
+    .await
+}
+
+/// Title and body text extracted from a web page
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedArticle {
+    pub title: String,
+    pub content: String,
+}
+
+/// Elements that never contain article text
+const BOILERPLATE_TAGS: &[&str] = &[
+    "nav", "header", "footer", "aside", "script", "style", "noscript", "form", "figure",
+];
+
+/// Shortest paragraph kept as article text (filters out bylines, captions, buttons)
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Check if an element sits inside navigation/boilerplate markup
+fn is_inside_boilerplate(element: &ElementRef) -> bool {
+    element.ancestors().any(|node| {
+        node.value()
+            .as_element()
+            .map(|e| {
+                BOILERPLATE_TAGS.contains(&e.name())
+                    || e.attr("role").map_or(false, |r| r == "navigation" || r == "banner" || r == "contentinfo")
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// Collapse runs of whitespace into single spaces
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extract the main article text and title from an HTML page
+///
+/// Readability-style heuristic: prefer `<article>`, then `<main>`, then the whole body,
+/// and keep only reasonably long paragraphs outside nav/header/footer/aside.
+/// Returns None if no article text could be found.
+pub fn extract_article(html: &str) -> Option<ExtractedArticle> {
+    let document = Html::parse_document(html);
+
+    // Title: og:title > <title> > first <h1>
+    let og_title = Selector::parse(r#"meta[property="og:title"]"#).ok()?;
+    let title_tag = Selector::parse("title").ok()?;
+    let h1 = Selector::parse("h1").ok()?;
+
+    let title = document
+        .select(&og_title)
+        .next()
+        .and_then(|e| e.value().attr("content").map(normalize_whitespace))
+        .or_else(|| document.select(&title_tag).next().map(|e| normalize_whitespace(&e.text().collect::<String>())))
+        .or_else(|| document.select(&h1).next().map(|e| normalize_whitespace(&e.text().collect::<String>())))
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "Untitled article".to_string());
+
+    // Body: paragraphs from the most specific content container available
+    for container in ["article p", "main p", "body p"] {
+        let selector = Selector::parse(container).ok()?;
+        let paragraphs: Vec<String> = document
+            .select(&selector)
+            .filter(|p| !is_inside_boilerplate(p))
+            .map(|p| normalize_whitespace(&p.text().collect::<String>()))
+            .filter(|text| text.chars().count() >= MIN_PARAGRAPH_CHARS)
+            .collect();
+
+        if !paragraphs.is_empty() {
+            return Some(ExtractedArticle {
+                title,
+                content: paragraphs.join("\n\n"),
+            });
+        }
+    }
+
+    None
+}
+
+/// Fetch a web page and import its article text as a read-aloud text
+pub async fn create_text_library_item_from_url(
+    pool: &SqlitePool,
+    url: &str,
+    language: &str,
+) -> Result<TextLibraryItem> {
+    println!("[create_text_library_item_from_url] Fetching {}", url);
+
+    let response = reqwest::get(url)
+        .await
+        .context(format!("Failed to fetch {}", url))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch {}: HTTP {}", url, response.status());
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if !content_type.contains("text/html") && !content_type.contains("application/xhtml") {
+        anyhow::bail!(
+            "URL does not point to a web page (content type: {})",
+            if content_type.is_empty() { "unknown" } else { &content_type }
+        );
+    }
+
+    let html = response.text().await.context("Failed to read page content")?;
+
+    let article = extract_article(&html)
+        .ok_or_else(|| anyhow::anyhow!("Could not find any article text at {}", url))?;
+
+    create_text_library_item(
+        pool,
+        CreateTextLibraryItem {
+            title: article.title,
+            source_type: "url".to_string(),
+            source_url: Some(url.to_string()),
+            content: article.content,
+            language: language.to_string(),
+            difficulty_level: None,
+            tags: None,
+        },
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const ARTICLE_FIXTURE: &str = r#"
+        <html>
+        <head>
+            <title>El tiempo en Madrid | Noticias</title>
+            <meta property="og:title" content="El tiempo en Madrid">
+        </head>
+        <body>
+            <nav><p>Inicio · Deportes · Cultura · Economía · Contacto</p></nav>
+            <header><p>Suscríbete a nuestro boletín para recibir noticias</p></header>
+            <article>
+                <h1>El tiempo en Madrid</h1>
+                <p>Por Ana</p>
+                <p>Hoy hace mucho calor en Madrid y la gente busca la sombra.</p>
+                <aside><p>Artículo relacionado: las mejores piscinas de la ciudad</p></aside>
+                <p>Mañana se esperan tormentas   por la tarde en toda la región.</p>
+            </article>
+            <footer><p>© 2024 Periódico Ejemplo. Todos los derechos reservados.</p></footer>
+        </body>
+        </html>
+    "#;
+
+    #[test]
+    fn test_extract_article_title_and_body() {
+        let article = extract_article(ARTICLE_FIXTURE).unwrap();
+
+        assert_eq!(article.title, "El tiempo en Madrid");
+        assert_eq!(
+            article.content,
+            "Hoy hace mucho calor en Madrid y la gente busca la sombra.\n\nMañana se esperan tormentas por la tarde en toda la región."
+        );
+        assert!(!article.content.contains("Deportes"));
+        assert!(!article.content.contains("boletín"));
+        assert!(!article.content.contains("piscinas"));
+        assert!(!article.content.contains("derechos"));
+    }
+
+    #[test]
+    fn test_extract_article_without_text() {
+        let html = "<html><head><title>Empty</title></head><body><nav><p>Only navigation links here</p></nav></body></html>";
+        assert_eq!(extract_article(html), None);
+    }
+
+    #[tokio::test]
+    async fn test_calculate_coverage() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (language, lemma) in [("es", "ser"), ("es", "casa"), ("es", "grande"), ("fr", "chat")] {
+            sqlx::query("INSERT INTO vocab (language, lemma) VALUES (?, ?)")
+                .bind(language)
+                .bind(lemma)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let lemmas: BTreeSet<String> = ["ser", "casa", "grande", "perro", "chat"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let coverage = calculate_coverage(&pool, &lemmas, "es").await.unwrap();
+
+        assert_eq!(coverage.total_lemmas, 5);
+        assert_eq!(coverage.known_count, 3);
+        assert_eq!(coverage.unknown_count, 2);
+        assert_eq!(coverage.coverage_percent, 60.0);
+        // "chat" is known in French only
+        assert_eq!(coverage.unknown_lemmas, vec!["chat".to_string(), "perro".to_string()]);
+
+        let empty = calculate_coverage(&pool, &BTreeSet::new(), "es").await.unwrap();
+        assert_eq!(empty.coverage_percent, 0.0);
+    }
+
+    async fn setup_text_library_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE text_library (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                source_type TEXT NOT NULL,
+                source_url TEXT,
+                content TEXT NOT NULL,
+                language TEXT NOT NULL,
+                word_count INTEGER,
+                estimated_duration INTEGER,
+                difficulty_level TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                tags TEXT
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at INTEGER NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn add_text(pool: &SqlitePool, title: &str, language: &str, tags: Option<&[&str]>) {
+        create_text_library_item(
+            pool,
+            CreateTextLibraryItem {
+                title: title.to_string(),
+                source_type: "manual".to_string(),
+                source_url: None,
+                content: "Hola mundo".to_string(),
+                language: language.to_string(),
+                difficulty_level: None,
+                tags: tags.map(|tags| tags.iter().map(|t| t.to_string()).collect()),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_filter_text_library_by_tag() {
+        let pool = setup_text_library_db().await;
+        add_text(&pool, "Noticias", "es", Some(&["news", "politics"])).await;
+        add_text(&pool, "Cuento", "es", Some(&["fiction"])).await;
+        add_text(&pool, "Crónica", "es", Some(&["news", "fiction"])).await;
+        add_text(&pool, "Sin etiquetas", "es", None).await;
+        add_text(&pool, "Nouvelles", "fr", Some(&["news", "sport"])).await;
+
+        let mut news: Vec<String> = get_text_library_by_tag(&pool, "es", "news")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.title)
+            .collect();
+        news.sort();
+        assert_eq!(news, vec!["Crónica", "Noticias"]);
+
+        // Tags match exactly, not as substrings
+        assert!(get_text_library_by_tag(&pool, "es", "new").await.unwrap().is_empty());
+        assert!(get_text_library_by_tag(&pool, "es", "sport").await.unwrap().is_empty());
+
+        let tags = get_all_text_library_tags(&pool, "es").await.unwrap();
+        assert_eq!(tags, vec!["fiction", "news", "politics"]);
+        assert_eq!(get_all_text_library_tags(&pool, "fr").await.unwrap(), vec!["news", "sport"]);
+        assert!(get_all_text_library_tags(&pool, "de").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_practice_text_contains_each_target() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE vocab (id INTEGER PRIMARY KEY, language TEXT NOT NULL, lemma TEXT NOT NULL,
+             forms_spoken TEXT, last_seen_at INTEGER NOT NULL DEFAULT 0, usage_count INTEGER DEFAULT 1,
+             mastered BOOLEAN DEFAULT 0, tags TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE sessions (id TEXT PRIMARY KEY, language TEXT NOT NULL, started_at INTEGER NOT NULL, transcript TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for (lemma, forms, tags) in [
+            ("perro", r#"["perro"]"#, r#"["needs-practice"]"#),
+            ("ir", r#"["fui", "vamos"]"#, r#"["needs-practice"]"#),
+            ("biblioteca", r#"["biblioteca"]"#, r#"["needs-practice"]"#),
+            ("volcán", r#"["volcán"]"#, r#"["needs-practice"]"#),
+            ("casa", r#"["casa"]"#, r#"["learning"]"#),
+        ] {
+            sqlx::query("INSERT INTO vocab (language, lemma, forms_spoken, tags) VALUES ('es', ?, ?, ?)")
+                .bind(lemma)
+                .bind(forms)
+                .bind(tags)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (id, started_at, transcript) in [
+            ("s1", 100, "Ayer fui a la biblioteca con mi perro. Hacía sol."),
+            ("s2", 200, "Mi casa es pequeña. El perro duerme mucho."),
+        ] {
+            sqlx::query("INSERT INTO sessions (id, language, started_at, transcript) VALUES (?, 'es', ?, ?)")
+                .bind(id)
+                .bind(started_at)
+                .bind(transcript)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let practice = build_practice_text(&pool, "es", NEEDS_PRACTICE_TAG).await.unwrap();
+
+        let mut targets = practice.target_words.clone();
+        targets.sort();
+        assert_eq!(targets, vec!["biblioteca", "ir", "perro"]);
+        assert_eq!(practice.missing_words, vec!["volcán"]);
+
+        // Each target appears as the lemma or one of its spoken forms
+        let words: HashSet<String> = tokenize_transcript(&practice.content).into_iter().collect();
+        assert!(words.contains("perro"));
+        assert!(words.contains("fui"));
+        assert!(words.contains("biblioteca"));
+
+        // One sentence covers all three, so nothing else is pulled in
+        assert_eq!(practice.content, "Ayer fui a la biblioteca con mi perro.");
+    }
+
+    #[test]
+    fn test_estimate_difficulty_orders_texts() {
+        let easy = "Yo tengo un gato. El gato es negro. Me gusta mi gato.";
+        let hard = "La consolidación institucional de las democracias latinoamericanas, \
+                    particularmente durante las transiciones posautoritarias, dependió \
+                    fundamentalmente de complejísimos equilibrios constitucionales, \
+                    económicos y socioculturales históricamente inestables.";
+
+        let easy_level = estimate_difficulty(easy, "es").unwrap();
+        let hard_level = estimate_difficulty(hard, "es").unwrap();
+
+        assert_eq!(easy_level, "A1");
+        assert!(hard_level > easy_level, "{} should be harder than {}", hard_level, easy_level);
+    }
+
+    #[test]
+    fn test_estimate_difficulty_empty_text() {
+        assert_eq!(estimate_difficulty("", "es"), None);
+        assert_eq!(estimate_difficulty("  ... !!", "es"), None);
+    }
+
     #[test]
     fn test_calculate_word_count() {
         let text = "The quick brown fox jumps over the lazy dog";
@@ -249,12 +1059,68 @@ mod tests {
     #[test]
     fn test_estimate_duration() {
         // 150 words at 150 WPM = 1 minute = 60 seconds
-        assert_eq!(estimate_duration(150), 60);
+        assert_eq!(estimate_duration(150, DEFAULT_READING_WPM), 60);
 
         // 300 words at 150 WPM = 2 minutes = 120 seconds
-        assert_eq!(estimate_duration(300), 120);
+        assert_eq!(estimate_duration(300, DEFAULT_READING_WPM), 120);
 
         // 75 words at 150 WPM = 0.5 minutes = 30 seconds
-        assert_eq!(estimate_duration(75), 30);
+        assert_eq!(estimate_duration(75, DEFAULT_READING_WPM), 30);
+
+        // Half the speed takes twice as long
+        assert_eq!(estimate_duration(150, 75.0), 120);
+    }
+
+    #[tokio::test]
+    async fn test_duration_follows_configured_reading_speed() {
+        let pool = setup_text_library_db().await;
+        let content = vec!["palabra"; 150].join(" ");
+        let create = |language: &str, difficulty: &str| CreateTextLibraryItem {
+            title: "Texto".to_string(),
+            source_type: "manual".to_string(),
+            source_url: None,
+            content: content.clone(),
+            language: language.to_string(),
+            difficulty_level: Some(difficulty.to_string()),
+            tags: None,
+        };
+
+        let item = create_text_library_item(&pool, create("es", "A1")).await.unwrap();
+        assert_eq!(item.estimated_duration, Some(60));
+
+        // Harder texts are read more slowly
+        let hard = create_text_library_item(&pool, create("es", "C1")).await.unwrap();
+        assert_eq!(hard.estimated_duration, Some(75));
+
+        let speed = ReadingSpeed {
+            default_wpm: 75.0,
+            by_language: [("de".to_string(), 50.0)].into_iter().collect(),
+        };
+        set_reading_speed(&pool, &speed).await.unwrap();
+        assert_eq!(get_reading_speed(&pool).await.unwrap(), speed);
+
+        let slow = create_text_library_item(&pool, create("es", "A1")).await.unwrap();
+        assert_eq!(slow.estimated_duration, Some(120));
+        let german = create_text_library_item(&pool, create("de", "A1")).await.unwrap();
+        assert_eq!(german.estimated_duration, Some(180));
+
+        // Editing re-estimates with the current speed
+        let updated = update_text_library_item(
+            &pool,
+            &item.id,
+            UpdateTextLibraryItem {
+                title: Some("Nuevo".to_string()),
+                source_type: None,
+                source_url: None,
+                content: None,
+                difficulty_level: None,
+                tags: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.estimated_duration, Some(120));
+
+        assert!(set_reading_speed(&pool, &ReadingSpeed { default_wpm: 0.0, ..ReadingSpeed::default() }).await.is_err());
     }
 }
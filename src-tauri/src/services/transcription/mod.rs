@@ -1,5 +1,10 @@
 mod error;
+mod subtitles;
 mod whisper;
 
 pub use error::TranscriptionError;
-pub use whisper::{transcribe_audio_file, TranscriptSegment, TranscriptionWithSegments};
+pub use subtitles::{segments_to_srt, segments_to_vtt, srt_timestamp, vtt_timestamp};
+pub use whisper::{
+    transcribe_audio_file, validate_model_file, waveform_peaks, ProgressCallback, TranscriptSegment,
+    TranscriptionOptions, TranscriptionWithSegments,
+};
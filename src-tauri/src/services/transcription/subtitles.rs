@@ -0,0 +1,149 @@
+/**
+ * Subtitle formatting for transcript segments
+ *
+ * Turns stored `TranscriptSegment`s into SRT or WebVTT cues, e.g. for session
+ * exports and subtitle downloads.
+ */
+
+use super::whisper::TranscriptSegment;
+
+/// `HH:MM:SS<sep>mmm` timecode (negative or NaN times become 0)
+fn timecode(seconds: f32, millis_separator: char) -> String {
+    let total_ms = (f64::from(seconds.max(0.0)) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        total_ms / 3_600_000,
+        total_ms / 60_000 % 60,
+        total_ms / 1000 % 60,
+        millis_separator,
+        total_ms % 1000
+    )
+}
+
+/// `HH:MM:SS,mmm` timecode used by SRT
+pub fn srt_timestamp(seconds: f32) -> String {
+    timecode(seconds, ',')
+}
+
+/// `HH:MM:SS.mmm` timecode used by WebVTT
+pub fn vtt_timestamp(seconds: f32) -> String {
+    timecode(seconds, '.')
+}
+
+/// Non-blank segments as (start, end, text) cues in order
+///
+/// A cue never starts before the previous one ended, and never ends before it starts,
+/// so zero, inverted or overlapping segment times still give valid subtitles.
+fn cues(segments: &[TranscriptSegment]) -> Vec<(f32, f32, &str)> {
+    let mut previous_end = 0.0f32;
+    segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| {
+            let start = segment.start_time.max(previous_end);
+            let end = segment.end_time.max(start);
+            previous_end = end;
+            (start, end, segment.text.trim())
+        })
+        .collect()
+}
+
+/// Segments as an SRT file: numbered cues with `start --> end` timecodes
+pub fn segments_to_srt(segments: &[TranscriptSegment]) -> String {
+    cues(segments)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end, text))| {
+            format!("{}\n{} --> {}\n{}\n\n", i + 1, srt_timestamp(start), srt_timestamp(end), text)
+        })
+        .collect()
+}
+
+/// Segments as a WebVTT file: `WEBVTT` header, then numbered cues
+pub fn segments_to_vtt(segments: &[TranscriptSegment]) -> String {
+    let body: String = cues(segments)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end, text))| {
+            format!("{}\n{} --> {}\n{}\n\n", i + 1, vtt_timestamp(start), vtt_timestamp(end), text)
+        })
+        .collect();
+    format!("WEBVTT\n\n{}", body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_time: f32, end_time: f32) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_time,
+            end_time,
+            confidence: None,
+            no_speech_prob: None,
+        }
+    }
+
+    #[test]
+    fn test_segments_to_srt_timecodes() {
+        assert_eq!(srt_timestamp(0.0), "00:00:00,000");
+        assert_eq!(srt_timestamp(1.5), "00:00:01,500");
+        assert_eq!(srt_timestamp(61.25), "00:01:01,250");
+        assert_eq!(srt_timestamp(3723.004), "01:02:03,004");
+        assert_eq!(srt_timestamp(-2.0), "00:00:00,000");
+
+        let srt = segments_to_srt(&[
+            segment(" Hola, ¿qué tal? ", 0.0, 2.4),
+            segment("  ", 2.4, 3.0),
+            segment("Muy bien.", 59.9, 61.2),
+            segment("Adiós.", 3600.5, 3599.0),
+        ]);
+
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:02,400\nHola, ¿qué tal?\n\n\
+             2\n00:00:59,900 --> 00:01:01,200\nMuy bien.\n\n\
+             3\n01:00:00,500 --> 01:00:00,500\nAdiós.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_timecodes_across_boundaries() {
+        // Sub-second
+        assert_eq!(vtt_timestamp(0.001), "00:00:00.001");
+        assert_eq!(vtt_timestamp(0.9994), "00:00:00.999");
+        // Rounding up carries into the next second, minute and hour
+        assert_eq!(vtt_timestamp(0.9996), "00:00:01.000");
+        assert_eq!(vtt_timestamp(59.9996), "00:01:00.000");
+        assert_eq!(srt_timestamp(3599.9996), "01:00:00,000");
+        // Minute and hour boundaries
+        assert_eq!(vtt_timestamp(60.0), "00:01:00.000");
+        assert_eq!(vtt_timestamp(3599.0), "00:59:59.000");
+        assert_eq!(vtt_timestamp(3600.0), "01:00:00.000");
+        assert_eq!(srt_timestamp(36_000.0), "10:00:00,000");
+        assert_eq!(vtt_timestamp(f32::NAN), "00:00:00.000");
+    }
+
+    #[test]
+    fn test_segments_to_vtt_clamps_times() {
+        let vtt = segments_to_vtt(&[
+            segment("Uno.", 0.0, 0.0),
+            segment("Dos.", 1.0, 2.5),
+            // Overlaps the previous cue
+            segment("Tres.", 2.0, 4.0),
+            // No timing at all
+            segment("Cuatro.", 0.0, 0.0),
+        ]);
+
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n\
+             1\n00:00:00.000 --> 00:00:00.000\nUno.\n\n\
+             2\n00:00:01.000 --> 00:00:02.500\nDos.\n\n\
+             3\n00:00:02.500 --> 00:00:04.000\nTres.\n\n\
+             4\n00:00:04.000 --> 00:00:04.000\nCuatro.\n\n"
+        );
+        assert_eq!(segments_to_vtt(&[]), "WEBVTT\n\n");
+    }
+}
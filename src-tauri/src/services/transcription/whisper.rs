@@ -1,10 +1,11 @@
 use super::error::TranscriptionError;
+use crate::services::recording::sinc_resampler;
 use hound::WavReader;
-use rubato::{Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction};
+use rubato::Resampler;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 use std::path::Path;
-use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
+use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy, WhisperState};
 
 /// A segment of transcribed text with timing information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,12 @@ pub struct TranscriptSegment {
     pub text: String,
     pub start_time: f32,  // seconds
     pub end_time: f32,    // seconds
+    /// Average token probability (0-1); None for segments not produced by local Whisper
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    /// Whisper's probability that the segment contains no speech (0-1)
+    #[serde(default)]
+    pub no_speech_prob: Option<f32>,
 }
 
 /// Transcription result with full text and timed segments
@@ -21,6 +28,161 @@ pub struct TranscriptSegment {
 pub struct TranscriptionWithSegments {
     pub text: String,
     pub segments: Vec<TranscriptSegment>,
+    /// Set when GPU was requested but couldn't be used, so the CPU was used instead
+    pub warning: Option<String>,
+    /// Language Whisper confidently heard instead of the requested one (see
+    /// `TranscriptionOptions::check_language`), e.g. "en" for a "es" session
+    pub language_mismatch: Option<String>,
+}
+
+/// Optional knobs for a transcription run
+#[derive(Debug, Clone)]
+pub struct TranscriptionOptions {
+    /// Use the GPU if this build has a GPU backend (see `transcribe_audio_file`)
+    pub use_gpu: bool,
+    /// Text Whisper treats as preceding context, biasing it toward these words
+    /// (proper nouns, jargon, or the source text of a read-aloud session)
+    pub initial_prompt: Option<String>,
+    /// Drop leading/trailing silence before transcribing (timestamps still match the file)
+    pub trim_silence: bool,
+    /// Decode with beam search of this width instead of greedy decoding (slower, sometimes more accurate)
+    pub beam_size: Option<u32>,
+    /// Have Whisper translate the speech into English; the text is English whatever
+    /// language was spoken, and segments keep their timings
+    pub translate_to_english: bool,
+    /// Decoding hints; None uses the language's default (see `decoding_hints`)
+    pub suppress_blank: Option<bool>,
+    /// Keep Whisper from emitting non-speech tokens like "[música]" or "♪"
+    pub suppress_non_speech_tokens: Option<bool>,
+    /// Segments whose no-speech probability exceeds this are treated as silence
+    pub no_speech_threshold: Option<f32>,
+    /// Also run language detection when a language is given, reporting a confident
+    /// mismatch in `language_mismatch` (costs one extra encoder pass)
+    pub check_language: bool,
+    /// CPU threads Whisper runs with, at most one per core; None uses all cores but one
+    /// (see `resolve_thread_count`)
+    /// More threads than the CPU has performance cores can make transcription slower on
+    /// CPUs with efficiency cores (Apple Silicon, recent Intel), as work waits on the slow cores.
+    pub n_threads: Option<u32>,
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        Self {
+            use_gpu: true,
+            initial_prompt: None,
+            trim_silence: true,
+            beam_size: None,
+            translate_to_english: false,
+            suppress_blank: None,
+            suppress_non_speech_tokens: None,
+            no_speech_threshold: None,
+            check_language: false,
+            n_threads: None,
+        }
+    }
+}
+
+/// Receives transcription progress as a percentage (0-100)
+pub type ProgressCallback = Box<dyn FnMut(u8) + Send + 'static>;
+
+/// Minimum progress change between two reports, so the UI isn't flooded
+const PROGRESS_STEP: u8 = 5;
+
+/// Turns Whisper's raw progress values into clamped, increasing, throttled percentages
+#[derive(Debug, Default)]
+struct ProgressThrottle {
+    last: Option<u8>,
+}
+
+impl ProgressThrottle {
+    /// The percentage to report for a raw value, or None if it shouldn't be reported
+    /// Values never go backwards; 100 is always reported once
+    fn update(&mut self, raw: i32) -> Option<u8> {
+        let percent = raw.clamp(0, 100) as u8;
+        let report = match self.last {
+            None => true,
+            Some(last) => percent > last && (percent - last >= PROGRESS_STEP || percent == 100),
+        };
+
+        if report {
+            self.last = Some(percent);
+            Some(percent)
+        } else {
+            None
+        }
+    }
+}
+
+/// Mean of token probabilities, clamped to [0, 1] (0 for a segment without tokens)
+fn average_probability(probabilities: impl IntoIterator<Item = f32>) -> f32 {
+    let (sum, count) = probabilities
+        .into_iter()
+        .filter(|p| p.is_finite())
+        .fold((0.0f32, 0usize), |(sum, count), p| (sum + p, count + 1));
+
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f32).clamp(0.0, 1.0)
+    }
+}
+
+/// Sample rate of the audio handed to Whisper
+const WHISPER_SAMPLE_RATE: usize = 16000;
+
+/// Frame size for voice activity detection (20ms)
+const VAD_FRAME_SAMPLES: usize = WHISPER_SAMPLE_RATE / 50;
+
+/// Frames with RMS below this (about -40 dBFS) count as silence
+const VAD_RMS_THRESHOLD: f32 = 0.01;
+
+/// Audio kept on either side of the detected speech (250ms)
+const VAD_PAD_SAMPLES: usize = WHISPER_SAMPLE_RATE / 4;
+
+/// Range of `samples` between the first and last voiced frame, padded
+///
+/// Energy-based: a frame is voiced if its RMS reaches `VAD_RMS_THRESHOLD`.
+/// Returns the full range if nothing is voiced, so quiet recordings aren't discarded.
+fn voiced_range(samples: &[f32]) -> std::ops::Range<usize> {
+    let is_voiced = |frame: &[f32]| {
+        let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+        mean_square.sqrt() >= VAD_RMS_THRESHOLD
+    };
+
+    let frames: Vec<bool> = samples.chunks(VAD_FRAME_SAMPLES).map(is_voiced).collect();
+    let (Some(first), Some(last)) = (
+        frames.iter().position(|&v| v),
+        frames.iter().rposition(|&v| v),
+    ) else {
+        return 0..samples.len();
+    };
+
+    let start = (first * VAD_FRAME_SAMPLES).saturating_sub(VAD_PAD_SAMPLES);
+    let end = ((last + 1) * VAD_FRAME_SAMPLES + VAD_PAD_SAMPLES).min(samples.len());
+    start..end
+}
+
+/// Whisper only uses the last ~224 tokens of the prompt, so longer texts are cut
+/// (at a word boundary) to roughly that size
+const MAX_PROMPT_CHARS: usize = 800;
+
+/// The prompt to hand to Whisper, if any: trimmed, non-empty, and within the length limit
+fn effective_prompt(options: &TranscriptionOptions) -> Option<&str> {
+    let prompt = options.initial_prompt.as_deref()?.trim();
+    if prompt.is_empty() {
+        return None;
+    }
+    if prompt.len() <= MAX_PROMPT_CHARS {
+        return Some(prompt);
+    }
+
+    let mut end = MAX_PROMPT_CHARS;
+    while !prompt.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = &prompt[..end];
+    Some(cut.rfind(char::is_whitespace).map_or(cut, |i| cut[..i].trim_end()))
 }
 
 /// Transcribe an audio file to text using Whisper
@@ -28,18 +190,32 @@ pub struct TranscriptionWithSegments {
 /// Loads the Whisper model from disk and transcribes the audio file.
 /// The audio file should be in WAV format (16kHz, mono, 16-bit PCM is optimal).
 /// Returns both the full text and timed segments.
+///
+/// `options.use_gpu` only has an effect when the app is built with a whisper-rs GPU backend
+/// (cargo feature `metal` on macOS, `cuda` for NVIDIA, `vulkan` elsewhere); other
+/// builds always run on the CPU. If the GPU fails to initialize, transcription falls
+/// back to the CPU and the result carries a warning.
+///
+/// `on_progress` is called with increasing percentages while Whisper runs.
+///
+/// Files that aren't WAV at all (empty, or e.g. an error page saved as .wav) are
+/// rejected before the model is loaded.
 pub async fn transcribe_audio_file(
     audio_path: &Path,
     model_path: &Path,
     language: Option<&str>,
+    options: TranscriptionOptions,
+    on_progress: Option<ProgressCallback>,
 ) -> Result<TranscriptionWithSegments, TranscriptionError> {
+    check_wav_header(audio_path)?;
+
     // Run the CPU-intensive transcription in a blocking task
     let audio_path = audio_path.to_path_buf();
     let model_path = model_path.to_path_buf();
     let language = language.map(|s| s.to_string());
 
     tokio::task::spawn_blocking(move || {
-        transcribe_blocking(&audio_path, &model_path, language.as_deref())
+        transcribe_blocking(&audio_path, &model_path, language.as_deref(), &options, on_progress)
     })
     .await
     .map_err(|e| TranscriptionError::TranscriptionFailed {
@@ -47,22 +223,236 @@ pub async fn transcribe_audio_file(
     })?
 }
 
+/// Check that a file starts with a RIFF/WAVE header, so a bad file gets a clear
+/// error instead of a parser message about chunk ids
+fn check_wav_header(audio_path: &Path) -> Result<(), TranscriptionError> {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(12);
+    std::fs::File::open(audio_path)?
+        .take(12)
+        .read_to_end(&mut header)?;
+
+    if header.len() == 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE" {
+        return Ok(());
+    }
+
+    let hint = if header.is_empty() {
+        " (the file is 0 bytes)"
+    } else if header.trim_ascii_start().starts_with(b"<") {
+        " (it looks like a web page, not a recording)"
+    } else {
+        ""
+    };
+    Err(TranscriptionError::AudioReadError {
+        message: format!(
+            "{} is empty or not valid audio: expected a WAV file{}",
+            audio_path.display(),
+            hint
+        ),
+    })
+}
+
+/// Check that a file can be loaded as a Whisper (ggml) model
+///
+/// Loads the full model, so call from a blocking context.
+pub fn validate_model_file(model_path: &Path) -> Result<(), TranscriptionError> {
+    let path = model_path_str(model_path)?;
+
+    WhisperContext::new_with_params(path, WhisperContextParameters::default())
+        .map(|_| ())
+        .map_err(|e| model_load_error(model_path, e))
+}
+
+/// The model path as a string, if the file exists
+fn model_path_str(model_path: &Path) -> Result<&str, TranscriptionError> {
+    if !model_path.is_file() {
+        return Err(TranscriptionError::ModelError {
+            message: format!("Model file not found: {}. Download the model first.", model_path.display()),
+        });
+    }
+
+    model_path.to_str().ok_or_else(|| TranscriptionError::ModelError {
+        message: "Invalid model path".to_string(),
+    })
+}
+
+/// Whisper couldn't load an existing model file - almost always a truncated or
+/// corrupted download, so point the user at re-downloading it
+fn model_load_error(model_path: &Path, error: impl std::fmt::Display) -> TranscriptionError {
+    TranscriptionError::ModelError {
+        message: format!(
+            "Failed to load Whisper model {} ({}). The file may be corrupted or incompletely downloaded - delete the model and download it again.",
+            model_path.display(),
+            error
+        ),
+    }
+}
+
+/// Languages Whisper tends to fill with subtitle-style tokens ("[Música]", "(Musique)")
+/// CJK languages aren't listed: suppressing non-speech tokens there also drops 「」 quotes.
+const NON_SPEECH_PRONE_LANGUAGES: &[&str] = &["es", "pt", "fr", "it", "de", "nl", "ru"];
+
+/// Whisper's own no-speech threshold
+const DEFAULT_NO_SPEECH_THRESHOLD: f32 = 0.6;
+
+/// Token suppression and silence detection settings for one transcription
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DecodingHints {
+    suppress_blank: bool,
+    suppress_non_speech_tokens: bool,
+    no_speech_threshold: f32,
+}
+
+/// Decoding hints for a language, with anything set in the options taking precedence
+fn decoding_hints(language: Option<&str>, options: &TranscriptionOptions) -> DecodingHints {
+    let prone = language.is_some_and(|lang| NON_SPEECH_PRONE_LANGUAGES.contains(&lang));
+
+    DecodingHints {
+        suppress_blank: options.suppress_blank.unwrap_or(true),
+        suppress_non_speech_tokens: options.suppress_non_speech_tokens.unwrap_or(prone),
+        no_speech_threshold: options
+            .no_speech_threshold
+            .unwrap_or(DEFAULT_NO_SPEECH_THRESHOLD)
+            .clamp(0.0, 1.0),
+    }
+}
+
+/// Detection probability above which a different language counts as a mismatch
+/// Short or accented recordings often score a second language at 0.3-0.6.
+const LANGUAGE_MISMATCH_MIN_PROBABILITY: f32 = 0.8;
+
+/// The detected language, if it confidently differs from the one requested
+/// Nothing is reported when no language was requested (Whisper auto-detected anyway).
+fn language_mismatch(requested: Option<&str>, detected: &str, probability: f32) -> Option<String> {
+    let requested = requested?;
+    if probability >= LANGUAGE_MISMATCH_MIN_PROBABILITY && !requested.eq_ignore_ascii_case(detected) {
+        Some(detected.to_string())
+    } else {
+        None
+    }
+}
+
+/// Most threads used by default; whisper.cpp speeds up little beyond this
+const MAX_DEFAULT_THREADS: usize = 12;
+
+/// Default Whisper thread count for `cpu_cores` logical cores (what `get_system_specs`
+/// reports): one is left free so the app stays responsive, capped at `MAX_DEFAULT_THREADS`
+fn default_thread_count(cpu_cores: usize) -> usize {
+    cpu_cores.saturating_sub(1).clamp(1, MAX_DEFAULT_THREADS)
+}
+
+/// Whisper thread count for `cpu_cores` logical cores: the requested count, kept within
+/// 1..=cpu_cores since more threads than cores only slows transcription down, or the default
+fn resolve_thread_count(requested: Option<u32>, cpu_cores: usize) -> usize {
+    match requested {
+        Some(threads) => usize::try_from(threads).unwrap_or(usize::MAX).clamp(1, cpu_cores.max(1)),
+        None => default_thread_count(cpu_cores),
+    }
+}
+
+/// Threads to run Whisper with on this machine (see `resolve_thread_count`)
+fn thread_count(options: &TranscriptionOptions) -> i32 {
+    let cpu_cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    resolve_thread_count(options.n_threads, cpu_cores) as i32
+}
+
+/// Run Whisper's language detection on the audio already decoded into `state`
+/// Returns the most likely language and its probability.
+fn detect_language(state: &WhisperState, threads: i32) -> Option<(&'static str, f32)> {
+    let (lang_id, probabilities) = state
+        .lang_detect(0, threads as usize)
+        .inspect_err(|e| println!("[transcribe] Language detection failed: {}", e))
+        .ok()?;
+
+    let probability = probabilities.get(usize::try_from(lang_id).ok()?).copied()?;
+    Some((whisper_rs::get_lang_str(lang_id)?, probability))
+}
+
+/// Remove non-speech annotations Whisper still produced ("[música]", "(risas)", "♪")
+/// and collapse the whitespace they leave behind
+///
+/// Only complete bracketed groups are removed: Whisper can split a parenthetical across
+/// segments, so an opener without its closer is kept, with the text after it.
+fn strip_non_speech(text: &str) -> String {
+    let mut kept = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let closer = match c {
+            '[' => Some(']'),
+            '(' => Some(')'),
+            _ => None,
+        };
+        if let Some(end) = closer.and_then(|close| rest.find(close)) {
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        if !matches!(c, '♪' | '♫') {
+            kept.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    kept.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A segment's text as stored: annotations are only stripped when non-speech tokens
+/// are suppressed, so a caller that opted out gets Whisper's text as is
+fn segment_text(raw: &str, strip_annotations: bool) -> String {
+    if strip_annotations {
+        strip_non_speech(raw)
+    } else {
+        raw.trim().to_string()
+    }
+}
+
+/// Whether Whisper should translate to English
+/// Speech already known to be English is transcribed as usual.
+fn translate_to_english(language: Option<&str>, options: &TranscriptionOptions) -> bool {
+    options.translate_to_english && language != Some("en")
+}
+
+/// Whisper context parameters for the requested device
+fn context_params(use_gpu: bool) -> WhisperContextParameters<'static> {
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(use_gpu);
+    params
+}
+
+/// Load the model, on the GPU if requested
+/// Falls back to the CPU if GPU initialization fails, returning a warning
+fn load_context(
+    model_path: &Path,
+    use_gpu: bool,
+) -> Result<(WhisperContext, Option<String>), TranscriptionError> {
+    let path = model_path_str(model_path)?;
+
+    match WhisperContext::new_with_params(path, context_params(use_gpu)) {
+        Ok(ctx) => Ok((ctx, None)),
+        Err(e) if use_gpu => {
+            let warning = format!("GPU acceleration unavailable ({}), transcribing on CPU", e);
+            println!("[transcribe] {}", warning);
+
+            let ctx = WhisperContext::new_with_params(path, context_params(false))
+                .map_err(|e| model_load_error(model_path, e))?;
+            Ok((ctx, Some(warning)))
+        }
+        Err(e) => Err(model_load_error(model_path, e)),
+    }
+}
+
 /// Blocking implementation of transcription
 fn transcribe_blocking(
     audio_path: &Path,
     model_path: &Path,
     language: Option<&str>,
+    options: &TranscriptionOptions,
+    on_progress: Option<ProgressCallback>,
 ) -> Result<TranscriptionWithSegments, TranscriptionError> {
     // Create Whisper context
-    let ctx = WhisperContext::new_with_params(
-        model_path.to_str().ok_or_else(|| TranscriptionError::ModelError {
-            message: "Invalid model path".to_string(),
-        })?,
-        WhisperContextParameters::default(),
-    )
-    .map_err(|e| TranscriptionError::ModelError {
-        message: format!("Failed to load Whisper model: {}", e),
-    })?;
+    let (ctx, warning) = load_context(model_path, options.use_gpu)?;
 
     // Read and prepare audio file
     let audio_data = std::fs::read(audio_path)?;
@@ -73,29 +463,68 @@ fn transcribe_blocking(
     // Read the converted audio as f32 samples
     let samples = read_audio_samples(&whisper_audio)?;
 
+    // Skip silence at the start and end; segment times are shifted back by the offset
+    let range = if options.trim_silence {
+        voiced_range(&samples)
+    } else {
+        0..samples.len()
+    };
+    let offset_seconds = range.start as f32 / WHISPER_SAMPLE_RATE as f32;
+    let samples = &samples[range];
+
     // Create a state for this transcription
     let mut state = ctx.create_state().map_err(|e| TranscriptionError::ModelError {
         message: format!("Failed to create Whisper state: {}", e),
     })?;
 
     // Set up transcription parameters
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    let strategy = match options.beam_size {
+        Some(beam_size) if beam_size > 1 => SamplingStrategy::BeamSearch {
+            beam_size: beam_size as i32,
+            patience: -1.0,
+        },
+        _ => SamplingStrategy::Greedy { best_of: 1 },
+    };
+    let mut params = FullParams::new(strategy);
+    let threads = thread_count(options);
+    params.set_n_threads(threads);
 
     // Set language if provided
     if let Some(lang) = language {
         params.set_language(Some(lang));
     }
 
-    // Enable translation to English if needed
-    params.set_translate(false);
+    // Bias decoding toward expected words
+    if let Some(prompt) = effective_prompt(options) {
+        params.set_initial_prompt(prompt);
+    }
+
+    // Translate to English if requested (segments keep their timestamps)
+    params.set_translate(translate_to_english(language, options));
+
+    // Keep "[música]"-style tokens out of the transcript (and so out of vocabulary)
+    let hints = decoding_hints(language, options);
+    params.set_suppress_blank(hints.suppress_blank);
+    params.set_suppress_nst(hints.suppress_non_speech_tokens);
+    params.set_no_speech_thold(hints.no_speech_threshold);
+
     params.set_print_special(false);
     params.set_print_progress(false);
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
 
+    if let Some(mut on_progress) = on_progress {
+        let mut throttle = ProgressThrottle::default();
+        params.set_progress_callback_safe(move |raw: i32| {
+            if let Some(percent) = throttle.update(raw) {
+                on_progress(percent);
+            }
+        });
+    }
+
     // Run transcription
     state
-        .full(params, &samples)
+        .full(params, samples)
         .map_err(|e| TranscriptionError::TranscriptionFailed {
             message: format!("Transcription failed: {}", e),
         })?;
@@ -108,30 +537,53 @@ fn transcribe_blocking(
 
     for i in 0..num_segments {
         if let Some(segment) = state.get_segment(i) {
-            // Get segment text
-            let segment_text = format!("{}", segment);
+            // Get segment text, without annotations that slipped past token suppression
+            let segment_text = segment_text(&format!("{}", segment), hints.suppress_non_speech_tokens);
+            if segment_text.is_empty() {
+                continue;
+            }
 
             // Get timestamps - whisper_rs provides start/end time in the segment
             // Timestamps are in centiseconds (1/100th of a second)
-            let start_time = segment.start_timestamp() as f32 / 100.0;
-            let end_time = segment.end_timestamp() as f32 / 100.0;
+            let start_time = segment.start_timestamp() as f32 / 100.0 + offset_seconds;
+            let end_time = segment.end_timestamp() as f32 / 100.0 + offset_seconds;
+
+            // Confidence: average probability of the segment's tokens
+            let confidence = average_probability(
+                (0..segment.n_tokens())
+                    .filter_map(|j| segment.get_token(j))
+                    .map(|token| token.token_probability()),
+            );
 
             // Add to segments list
             segments.push(TranscriptSegment {
-                text: segment_text.trim().to_string(),
+                text: segment_text.clone(),
                 start_time,
                 end_time,
+                confidence: Some(confidence),
+                no_speech_prob: Some(segment.no_speech_probability().clamp(0.0, 1.0)),
             });
 
             // Build full text
-            full_text.push_str(segment_text.trim());
+            full_text.push_str(&segment_text);
             full_text.push(' ');
         }
     }
 
+    // Whisper only detects the language itself when none was given, so ask explicitly
+    let language_mismatch = match language {
+        Some(_) if options.check_language => detect_language(&state, threads).and_then(|(detected, probability)| {
+            println!("[transcribe] Detected language: {} ({:.2})", detected, probability);
+            language_mismatch(language, detected, probability)
+        }),
+        _ => None,
+    };
+
     Ok(TranscriptionWithSegments {
         text: full_text.trim().to_string(),
         segments,
+        warning,
+        language_mismatch,
     })
 }
 
@@ -174,29 +626,11 @@ fn read_audio_samples(wav_data: &[u8]) -> Result<Vec<f32>, TranscriptionError> {
     Ok(samples)
 }
 
-/// Convert audio to Whisper-compatible format (16kHz, mono, 16-bit PCM WAV)
-fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<u8>, TranscriptionError> {
-    // Parse the WAV file
-    let cursor = Cursor::new(audio_data);
-    let mut reader = WavReader::new(cursor).map_err(|e| TranscriptionError::AudioReadError {
-        message: format!("Failed to parse WAV file: {}", e),
-    })?;
-
+/// Read every sample of a WAV file as f32 (-1.0..1.0), averaging channels down to mono
+fn read_mono_samples<R: std::io::Read>(mut reader: WavReader<R>) -> Result<Vec<f32>, TranscriptionError> {
     let spec = reader.spec();
-    let sample_rate = spec.sample_rate;
     let channels = spec.channels as usize;
 
-    // Check if already in correct format
-    if spec.sample_format == hound::SampleFormat::Int
-        && spec.channels == 1
-        && spec.sample_rate == 16000
-        && spec.bits_per_sample == 16
-    {
-        // Already in correct format, return as-is
-        return Ok(audio_data.to_vec());
-    }
-
-    // Step 1: Read all samples and convert to f32
     let samples_f32: Vec<f32> = match spec.sample_format {
         hound::SampleFormat::Int => match spec.bits_per_sample {
             16 => reader
@@ -227,8 +661,7 @@ fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<u8>, Transcription
             })?,
     };
 
-    // Step 2: Convert to mono if needed
-    let mono_samples: Vec<f32> = if channels == 1 {
+    let mono_samples = if channels == 1 {
         samples_f32
     } else if channels == 2 {
         // Stereo to mono: average channels
@@ -244,29 +677,39 @@ fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<u8>, Transcription
             .collect()
     };
 
+    Ok(mono_samples)
+}
+
+/// Convert audio to Whisper-compatible format (16kHz, mono, 16-bit PCM WAV)
+fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<u8>, TranscriptionError> {
+    // Parse the WAV file
+    let cursor = Cursor::new(audio_data);
+    let reader = WavReader::new(cursor).map_err(|e| TranscriptionError::AudioReadError {
+        message: format!("Failed to parse WAV file: {}", e),
+    })?;
+
+    let spec = reader.spec();
+    let sample_rate = spec.sample_rate;
+
+    // Check if already in correct format
+    if spec.sample_format == hound::SampleFormat::Int
+        && spec.channels == 1
+        && spec.sample_rate == 16000
+        && spec.bits_per_sample == 16
+    {
+        // Already in correct format, return as-is
+        return Ok(audio_data.to_vec());
+    }
+
+    // Steps 1-2: Read all samples as f32 and mix down to mono
+    let mono_samples = read_mono_samples(reader)?;
+
     // Step 3: Resample to 16kHz if needed
     let resampled: Vec<f32> = if sample_rate != 16000 {
-        let resample_ratio = 16000.0 / sample_rate as f64;
         let chunk_size = 1024;
 
-        let params = SincInterpolationParameters {
-            sinc_len: 64,
-            f_cutoff: 0.95,
-            interpolation: SincInterpolationType::Linear,
-            oversampling_factor: 128,
-            window: WindowFunction::BlackmanHarris2,
-        };
-
-        let mut resampler = SincFixedIn::<f32>::new(
-            resample_ratio,
-            8.0,
-            params,
-            chunk_size,
-            1, // mono
-        )
-        .map_err(|e| TranscriptionError::AudioConversionError {
-            message: format!("Failed to create resampler: {}", e),
-        })?;
+        let mut resampler = sinc_resampler(sample_rate, 16000, chunk_size)
+            .map_err(|message| TranscriptionError::AudioConversionError { message })?;
 
         let mut output_samples = Vec::new();
         let mut input_pos = 0;
@@ -329,3 +772,275 @@ fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<u8>, Transcription
 
     Ok(output)
 }
+
+/// Min/max peaks of a WAV file in `buckets` equal slices, for drawing a waveform
+/// Channels are averaged and peaks are clamped to -1.0..=1.0. A file with fewer
+/// samples than `buckets` gets one bucket per sample.
+pub fn waveform_peaks(audio_path: &Path, buckets: usize) -> Result<Vec<(f32, f32)>, TranscriptionError> {
+    let reader = WavReader::open(audio_path).map_err(|e| TranscriptionError::AudioReadError {
+        message: format!("Failed to open WAV file {}: {}", audio_path.display(), e),
+    })?;
+    let samples = read_mono_samples(reader)?;
+
+    Ok(bucket_peaks(&samples, buckets))
+}
+
+fn bucket_peaks(samples: &[f32], buckets: usize) -> Vec<(f32, f32)> {
+    let buckets = buckets.min(samples.len());
+
+    (0..buckets)
+        .map(|i| {
+            let bucket = &samples[i * samples.len() / buckets..(i + 1) * samples.len() / buckets];
+            let (min, max) = bucket
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), &s| (min.min(s), max.max(s)));
+            (min.clamp(-1.0, 1.0), max.clamp(-1.0, 1.0))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform_peaks_of_stereo_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        // 1 second: a full-scale sine on the left, silence on the right
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..8000 {
+            let sample = (i as f32 / 8000.0 * 440.0 * std::f32::consts::TAU).sin();
+            writer.write_sample((sample * i16::MAX as f32) as i16).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let peaks = waveform_peaks(&path, 100).unwrap();
+        assert_eq!(peaks.len(), 100);
+        for (min, max) in &peaks {
+            assert!((-1.0..=1.0).contains(min) && (-1.0..=1.0).contains(max));
+            assert!(min <= max);
+            // Averaging with the silent channel halves the amplitude
+            assert!(*max > 0.4 && *max <= 0.5, "{}", max);
+        }
+
+        assert_eq!(waveform_peaks(&path, 0).unwrap().len(), 0);
+        assert_eq!(bucket_peaks(&[0.5, -0.25], 10), vec![(0.5, 0.5), (-0.25, -0.25)]);
+    }
+
+    #[test]
+    fn test_context_params_use_gpu() {
+        assert!(context_params(true).use_gpu);
+        assert!(!context_params(false).use_gpu);
+    }
+
+    fn with_prompt(prompt: Option<&str>) -> TranscriptionOptions {
+        TranscriptionOptions {
+            initial_prompt: prompt.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_translate_to_english_option() {
+        let translating = TranscriptionOptions {
+            translate_to_english: true,
+            ..Default::default()
+        };
+        assert!(translate_to_english(Some("es"), &translating));
+        assert!(translate_to_english(None, &translating));
+        assert!(!translate_to_english(Some("en"), &translating));
+        assert!(!translate_to_english(Some("es"), &TranscriptionOptions::default()));
+    }
+
+    #[test]
+    fn test_decoding_hints_per_language_and_overrides() {
+        let defaults = TranscriptionOptions::default();
+        assert_eq!(
+            decoding_hints(Some("es"), &defaults),
+            DecodingHints { suppress_blank: true, suppress_non_speech_tokens: true, no_speech_threshold: 0.6 }
+        );
+        assert!(!decoding_hints(Some("en"), &defaults).suppress_non_speech_tokens);
+        assert!(!decoding_hints(Some("ja"), &defaults).suppress_non_speech_tokens);
+        assert!(!decoding_hints(None, &defaults).suppress_non_speech_tokens);
+
+        let custom = TranscriptionOptions {
+            suppress_blank: Some(false),
+            suppress_non_speech_tokens: Some(false),
+            no_speech_threshold: Some(1.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            decoding_hints(Some("es"), &custom),
+            DecodingHints { suppress_blank: false, suppress_non_speech_tokens: false, no_speech_threshold: 1.0 }
+        );
+    }
+
+    #[test]
+    fn test_thread_count_option_and_default() {
+        // Leaves a core free, within 1..=MAX_DEFAULT_THREADS
+        assert_eq!(default_thread_count(0), 1);
+        assert_eq!(default_thread_count(1), 1);
+        assert_eq!(default_thread_count(2), 1);
+        assert_eq!(default_thread_count(4), 3);
+        assert_eq!(default_thread_count(8), 7);
+        assert_eq!(default_thread_count(16), MAX_DEFAULT_THREADS);
+        assert_eq!(default_thread_count(128), MAX_DEFAULT_THREADS);
+
+        // A requested count is kept within the machine's cores
+        assert_eq!(resolve_thread_count(Some(6), 8), 6);
+        assert_eq!(resolve_thread_count(Some(16), 16), 16);
+        assert_eq!(resolve_thread_count(Some(16), 8), 8);
+        assert_eq!(resolve_thread_count(Some(0), 8), 1);
+        assert_eq!(resolve_thread_count(Some(u32::MAX), 8), 8);
+        assert_eq!(resolve_thread_count(None, 8), 7);
+
+        let cpu_cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let with_threads = |n_threads| TranscriptionOptions {
+            n_threads,
+            ..Default::default()
+        };
+        assert_eq!(thread_count(&with_threads(Some(u32::MAX))), cpu_cores as i32);
+        assert_eq!(thread_count(&with_threads(None)), default_thread_count(cpu_cores) as i32);
+    }
+
+    #[test]
+    fn test_language_mismatch_only_when_confident() {
+        // Declared Spanish, clearly spoke English
+        assert_eq!(language_mismatch(Some("es"), "en", 0.93), Some("en".to_string()));
+        // Same language, or not confident enough to warn
+        assert_eq!(language_mismatch(Some("es"), "es", 0.99), None);
+        assert_eq!(language_mismatch(Some("ES"), "es", 0.99), None);
+        assert_eq!(language_mismatch(Some("es"), "pt", 0.55), None);
+        // Auto-detected sessions have nothing to mismatch against
+        assert_eq!(language_mismatch(None, "en", 0.99), None);
+    }
+
+    #[test]
+    fn test_non_wav_files_are_rejected_with_clear_error() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let page = dir.path().join("download.wav");
+        std::fs::write(&page, b"<!DOCTYPE html><html><body>404 Not Found</body></html>").unwrap();
+        let err = check_wav_header(&page).unwrap_err();
+        assert!(matches!(err, TranscriptionError::AudioReadError { .. }));
+        let message = err.to_string();
+        assert!(message.contains("empty or not valid audio"), "{}", message);
+        assert!(message.contains("web page"), "{}", message);
+
+        let empty = dir.path().join("empty.wav");
+        std::fs::write(&empty, b"").unwrap();
+        assert!(check_wav_header(&empty).unwrap_err().to_string().contains("0 bytes"));
+
+        let blob = dir.path().join("blob.wav");
+        std::fs::write(&blob, [0x1f, 0x8b, 0x08, 0x00, 0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 0, 1, 2]).unwrap();
+        assert!(check_wav_header(&blob).unwrap_err().to_string().contains("not valid audio"));
+
+        let wav = dir.path().join("clip.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+        writer.write_sample(0i16).unwrap();
+        writer.finalize().unwrap();
+        assert!(check_wav_header(&wav).is_ok());
+    }
+
+    #[test]
+    fn test_strip_non_speech() {
+        assert_eq!(strip_non_speech(" [Música] Hola, ¿qué tal? (risas)"), "Hola, ¿qué tal?");
+        assert_eq!(strip_non_speech("♪ la la ♪ y luego [MUSIC] nada"), "la la y luego nada");
+        assert_eq!(strip_non_speech(" [Musique]"), "");
+        assert_eq!(strip_non_speech("Sin nada que quitar."), "Sin nada que quitar.");
+
+        // A parenthetical split across segments keeps its text
+        assert_eq!(strip_non_speech(" Dijo (que no"), "Dijo (que no");
+        assert_eq!(strip_non_speech(" [Música] y [luego"), "y [luego");
+        assert_eq!(strip_non_speech(" lo sabía) [risas]"), "lo sabía)");
+
+        // Left alone when the caller opted out of suppression
+        assert_eq!(segment_text(" [Música] Hola (risas)", false), "[Música] Hola (risas)");
+        assert_eq!(segment_text(" [Música] Hola (risas)", true), "Hola");
+    }
+
+    #[test]
+    fn test_effective_prompt() {
+        assert_eq!(effective_prompt(&with_prompt(None)), None);
+        assert_eq!(effective_prompt(&with_prompt(Some("   "))), None);
+        assert_eq!(
+            effective_prompt(&with_prompt(Some(" Kubernetes, Grafana "))),
+            Some("Kubernetes, Grafana")
+        );
+    }
+
+    #[test]
+    fn test_effective_prompt_truncates_at_word_boundary() {
+        let long = "palabra ".repeat(200);
+        let options = with_prompt(Some(&long));
+        let prompt = effective_prompt(&options).unwrap();
+
+        assert!(prompt.len() <= MAX_PROMPT_CHARS);
+        assert!(prompt.ends_with("palabra"));
+    }
+
+    /// `seconds` of a 220 Hz tone at amplitude 0.5
+    fn tone(seconds: f32) -> Vec<f32> {
+        (0..(seconds * WHISPER_SAMPLE_RATE as f32) as usize)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / WHISPER_SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_voiced_range_trims_silence_padding() {
+        let silence = vec![0.0f32; 2 * WHISPER_SAMPLE_RATE];
+        let speech = tone(1.0);
+        let samples: Vec<f32> = silence.iter().chain(&speech).chain(&silence).copied().collect();
+
+        let range = voiced_range(&samples);
+
+        // 1s of speech plus 250ms pad on each side
+        assert_eq!(range.start, 2 * WHISPER_SAMPLE_RATE - VAD_PAD_SAMPLES);
+        assert_eq!(range.end, 3 * WHISPER_SAMPLE_RATE + VAD_PAD_SAMPLES);
+        assert_eq!(range.len(), speech.len() + 2 * VAD_PAD_SAMPLES);
+    }
+
+    #[test]
+    fn test_average_probability_is_within_unit_range() {
+        let confidence = average_probability([0.9, 0.7, 0.8]);
+        assert!((confidence - 0.8).abs() < 1e-6);
+
+        // Out-of-range and NaN values can't push the result outside [0, 1]
+        for probabilities in [vec![1.5, 1.2], vec![-0.3, 0.1], vec![f32::NAN, 0.5], vec![]] {
+            let confidence = average_probability(probabilities);
+            assert!((0.0..=1.0).contains(&confidence), "confidence {} out of range", confidence);
+        }
+    }
+
+    #[test]
+    fn test_progress_is_clamped_and_monotonic() {
+        let mut throttle = ProgressThrottle::default();
+        let reported: Vec<u8> = [-5, 0, 3, 10, 8, 12, 50, 49, 52, 98, 120, 100]
+            .into_iter()
+            .filter_map(|raw| throttle.update(raw))
+            .collect();
+
+        assert_eq!(reported, vec![0, 10, 50, 98, 100]);
+    }
+
+    #[test]
+    fn test_voiced_range_keeps_all_when_silent() {
+        let samples = vec![0.001f32; WHISPER_SAMPLE_RATE];
+        assert_eq!(voiced_range(&samples), 0..samples.len());
+    }
+}
@@ -0,0 +1,29 @@
+// Translation layer - see docs/TRANSLATION_ABSTRACTION.md
+
+mod pack_stats;
+mod pairwise_provider;
+mod provider;
+
+pub use pack_stats::{get_translation_pack_stats, TranslationPackStats};
+pub use pairwise_provider::PairwiseProvider;
+pub use provider::{CustomTranslationProvider, TranslationProvider};
+
+use anyhow::Result;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+/// Get the translation provider to use
+///
+/// With a `user_pool`, the user's custom translations take precedence over the
+/// downloaded translation databases.
+pub async fn get_translation_provider(
+    app: &AppHandle,
+    user_pool: Option<&SqlitePool>,
+) -> Result<Box<dyn TranslationProvider>> {
+    let base: Box<dyn TranslationProvider> = Box::new(PairwiseProvider::new(app.clone()));
+
+    Ok(match user_pool {
+        Some(pool) => Box::new(CustomTranslationProvider::new(base, pool.clone())),
+        None => base,
+    })
+}
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+
+use crate::db::langpack;
+
+/// Size of a pairwise translation pack, e.g. to show how much of a lemma DB it covers
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationPackStats {
+    /// Rows in the `translations` table (a lemma can have several)
+    pub total_rows: i64,
+    /// Source lemmas with at least one translation
+    pub distinct_lemmas: i64,
+}
+
+/// Count the rows and distinct source lemmas of an open translation database
+async fn translation_pack_stats(pool: &SqlitePool) -> Result<TranslationPackStats> {
+    let (total_rows, distinct_lemmas): (i64, i64) =
+        sqlx::query_as("SELECT COUNT(*), COUNT(DISTINCT lemma) FROM translations")
+            .fetch_one(pool)
+            .await
+            .context("Failed to count translations")?;
+
+    Ok(TranslationPackStats { total_rows, distinct_lemmas })
+}
+
+/// Stats of the installed {from}-{to} translation pack
+/// Fails with a "not found, please download" error if the pack isn't installed
+pub async fn get_translation_pack_stats(from: &str, to: &str, app: &AppHandle) -> Result<TranslationPackStats> {
+    let pool = langpack::open_translation_db(from, to, app).await?;
+    let stats = translation_pack_stats(&pool).await;
+    pool.close().await;
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_translation_pack_stats_counts_rows_and_lemmas() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE translations (lemma TEXT NOT NULL, translation TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            translation_pack_stats(&pool).await.unwrap(),
+            TranslationPackStats { total_rows: 0, distinct_lemmas: 0 }
+        );
+
+        for (lemma, translation) in [("correr", "run"), ("correr", "jog"), ("casa", "house"), ("hogar", "home")] {
+            sqlx::query("INSERT INTO translations VALUES (?, ?)")
+                .bind(lemma)
+                .bind(translation)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            translation_pack_stats(&pool).await.unwrap(),
+            TranslationPackStats { total_rows: 4, distinct_lemmas: 3 }
+        );
+    }
+}
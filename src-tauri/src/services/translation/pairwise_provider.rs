@@ -0,0 +1,191 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use super::provider::{extend_unique, TranslationProvider};
+use crate::db::langpack;
+
+/// Translations from per-pair databases (langpacks/translations/{from}-{to}.db)
+///
+/// Each database has a `translations` table mapping `lemma` to `translation`.
+/// When a lemma has several rows, the first one in the pack wins.
+pub struct PairwiseProvider {
+    app: AppHandle,
+}
+
+impl PairwiseProvider {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+/// Lemmas per IN query in `first_translations`, well under SQLite's variable limit
+const BATCH_QUERY_SIZE: usize = 500;
+
+/// First translation of `lemma` in a {from}-{to} database
+async fn first_translation(pool: &SqlitePool, lemma: &str) -> Result<Option<String>> {
+    Ok(
+        sqlx::query_scalar("SELECT translation FROM translations WHERE lemma = ? ORDER BY rowid ASC LIMIT 1")
+            .bind(lemma.to_lowercase())
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+/// `first_translation` for many lemmas, one IN query per `BATCH_QUERY_SIZE` lemmas
+/// Lemmas without a translation are left out of the map.
+async fn first_translations(pool: &SqlitePool, lemmas: &[String]) -> Result<HashMap<String, String>> {
+    let mut keys: Vec<String> = lemmas.iter().map(|lemma| lemma.to_lowercase()).collect();
+    keys.sort();
+    keys.dedup();
+
+    // Rows come oldest first, so the first one seen per lemma wins
+    let mut found: HashMap<String, String> = HashMap::new();
+    for chunk in keys.chunks(BATCH_QUERY_SIZE) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT lemma, translation FROM translations WHERE lemma IN ({}) ORDER BY rowid ASC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+        for key in chunk {
+            query = query.bind(key);
+        }
+        for (lemma, translation) in query.fetch_all(pool).await? {
+            found.entry(lemma).or_insert(translation);
+        }
+    }
+
+    Ok(lemmas
+        .iter()
+        .filter_map(|lemma| {
+            found
+                .get(&lemma.to_lowercase())
+                .map(|translation| (lemma.clone(), translation.clone()))
+        })
+        .collect())
+}
+
+/// Lemmas in a {from}-{to} database whose translation is `translation`
+///
+/// There is no index on `translation`, so this scans the table.
+async fn lemmas_translated_as(pool: &SqlitePool, translation: &str, limit: usize) -> Result<Vec<String>> {
+    Ok(sqlx::query_scalar(
+        "SELECT lemma FROM translations WHERE lower(translation) = lower(?) ORDER BY lemma LIMIT ?",
+    )
+    .bind(translation.trim())
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Translations of `lemma` in a {to}-{from} database, i.e. the reverse direction
+async fn translations_of(pool: &SqlitePool, lemma: &str, limit: usize) -> Result<Vec<String>> {
+    Ok(sqlx::query_scalar("SELECT translation FROM translations WHERE lemma = ? ORDER BY rowid ASC LIMIT ?")
+        .bind(lemma.trim().to_lowercase())
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await?)
+}
+
+#[async_trait]
+impl TranslationProvider for PairwiseProvider {
+    async fn get_translation(&self, lemma: &str, from: &str, to: &str) -> Result<Option<String>> {
+        let pool = langpack::open_translation_db(from, to, &self.app).await?;
+        first_translation(&pool, lemma).await
+    }
+
+    async fn translate_batch(
+        &self,
+        lemmas: &[String],
+        from: &str,
+        to: &str,
+    ) -> Result<HashMap<String, String>> {
+        // Open the database once for the whole batch
+        let pool = langpack::open_translation_db(from, to, &self.app).await?;
+        first_translations(&pool, lemmas).await
+    }
+
+    async fn reverse_translate(&self, translation: &str, from: &str, to: &str, limit: usize) -> Result<Vec<String>> {
+        let forward = langpack::open_translation_db(from, to, &self.app).await;
+        let backward = langpack::open_translation_db(to, from, &self.app).await;
+
+        let mut results = Vec::new();
+        if let Ok(pool) = &forward {
+            extend_unique(&mut results, lemmas_translated_as(pool, translation, limit).await?, limit);
+        }
+        if let Ok(pool) = &backward {
+            if results.len() < limit {
+                extend_unique(&mut results, translations_of(pool, translation, limit).await?, limit);
+            }
+        }
+
+        // Only an error if neither direction is installed
+        match (forward, backward) {
+            (Err(e), Err(_)) => Err(e),
+            _ => Ok(results),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn translation_db(rows: &[(&str, &str)]) -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE translations (lemma TEXT NOT NULL, translation TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (lemma, translation) in rows {
+            sqlx::query("INSERT INTO translations VALUES (?, ?)")
+                .bind(lemma)
+                .bind(translation)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_reverse_lookup_in_both_directions() {
+        // es-en: Spanish lemma -> English translation
+        let es_en = translation_db(&[("correr", "run"), ("casa", "house"), ("huir", "Run"), ("hogar", "home")]).await;
+        assert_eq!(lemmas_translated_as(&es_en, "run", 10).await.unwrap(), vec!["correr", "huir"]);
+        assert_eq!(lemmas_translated_as(&es_en, " house ", 10).await.unwrap(), vec!["casa"]);
+        assert_eq!(lemmas_translated_as(&es_en, "run", 1).await.unwrap(), vec!["correr"]);
+        assert!(lemmas_translated_as(&es_en, "cat", 10).await.unwrap().is_empty());
+
+        // en-es: the same lookup read from the reverse-direction database
+        let en_es = translation_db(&[("run", "correr"), ("run", "huir"), ("house", "casa")]).await;
+        assert_eq!(translations_of(&en_es, "Run", 10).await.unwrap(), vec!["correr", "huir"]);
+        assert!(translations_of(&en_es, "cat", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batched_lookup_matches_single_lookups() {
+        let es_en = translation_db(&[
+            ("banco", "bank"),
+            ("casa", "house"),
+            ("banco", "bench"),
+            ("perro", "dog"),
+        ])
+        .await;
+
+        let mut lemmas: Vec<String> = ["Banco", "casa", "gato", "perro", "banco"].iter().map(|s| s.to_string()).collect();
+        // More lemmas than fit in one IN query
+        lemmas.extend((0..BATCH_QUERY_SIZE + 10).map(|i| format!("missing{}", i)));
+
+        let batched = first_translations(&es_en, &lemmas).await.unwrap();
+        for lemma in &lemmas {
+            assert_eq!(batched.get(lemma).cloned(), first_translation(&es_en, lemma).await.unwrap(), "{}", lemma);
+        }
+        assert_eq!(batched.len(), 4);
+        assert_eq!(batched["Banco"], "bank");
+        assert!(first_translations(&es_en, &[]).await.unwrap().is_empty());
+    }
+}
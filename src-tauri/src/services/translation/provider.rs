@@ -0,0 +1,219 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::services::vocabulary::get_custom_translation;
+
+/// Anything that can translate lemmas from one language to another
+///
+/// Callers get a provider from `get_translation_provider` instead of querying
+/// translation databases directly, so the backing store can change.
+#[async_trait]
+pub trait TranslationProvider: Send + Sync {
+    /// Translate a single lemma (None if the provider has no entry)
+    async fn get_translation(&self, lemma: &str, from: &str, to: &str) -> Result<Option<String>>;
+
+    /// Translate several lemmas; lemmas without an entry are left out of the map
+    async fn translate_batch(
+        &self,
+        lemmas: &[String],
+        from: &str,
+        to: &str,
+    ) -> Result<HashMap<String, String>> {
+        let mut translations = HashMap::new();
+        for lemma in lemmas {
+            if let Some(translation) = self.get_translation(lemma, from, to).await? {
+                translations.insert(lemma.clone(), translation);
+            }
+        }
+        Ok(translations)
+    }
+
+    /// Find lemmas in `from` whose translation into `to` is `translation` (at most `limit`)
+    ///
+    /// The inverse of `get_translation`: "run" with from "es", to "en" finds "correr".
+    /// Providers that can't search by translation return nothing.
+    async fn reverse_translate(
+        &self,
+        _translation: &str,
+        _from: &str,
+        _to: &str,
+        _limit: usize,
+    ) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Add `lemmas` to `results`, skipping duplicates, until `limit` is reached
+pub(crate) fn extend_unique(results: &mut Vec<String>, lemmas: Vec<String>, limit: usize) {
+    for lemma in lemmas {
+        if results.len() >= limit {
+            break;
+        }
+        if !results.contains(&lemma) {
+            results.push(lemma);
+        }
+    }
+}
+
+/// Wraps a provider so the user's own translations (custom_translations) win
+pub struct CustomTranslationProvider {
+    inner: Box<dyn TranslationProvider>,
+    user_pool: SqlitePool,
+}
+
+impl CustomTranslationProvider {
+    pub fn new(inner: Box<dyn TranslationProvider>, user_pool: SqlitePool) -> Self {
+        Self { inner, user_pool }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for CustomTranslationProvider {
+    async fn get_translation(&self, lemma: &str, from: &str, to: &str) -> Result<Option<String>> {
+        if let Some(custom) = get_custom_translation(&self.user_pool, lemma, from, to).await? {
+            return Ok(Some(custom));
+        }
+        self.inner.get_translation(lemma, from, to).await
+    }
+
+    async fn translate_batch(
+        &self,
+        lemmas: &[String],
+        from: &str,
+        to: &str,
+    ) -> Result<HashMap<String, String>> {
+        let mut translations = HashMap::new();
+        let mut rest = Vec::new();
+        for lemma in lemmas {
+            match get_custom_translation(&self.user_pool, lemma, from, to).await? {
+                Some(custom) => {
+                    translations.insert(lemma.clone(), custom);
+                }
+                None => rest.push(lemma.clone()),
+            }
+        }
+
+        // Everything else in one batch, so the inner provider can look it up in bulk
+        if !rest.is_empty() {
+            translations.extend(self.inner.translate_batch(&rest, from, to).await?);
+        }
+
+        Ok(translations)
+    }
+
+    async fn reverse_translate(&self, translation: &str, from: &str, to: &str, limit: usize) -> Result<Vec<String>> {
+        // The user's own translations first
+        let custom: Vec<String> = sqlx::query_scalar(
+            "SELECT lemma FROM custom_translations
+             WHERE lower(custom_translation) = lower(?) AND lang_from = ? AND lang_to = ?
+             ORDER BY lemma
+             LIMIT ?",
+        )
+        .bind(translation.trim())
+        .bind(from)
+        .bind(to)
+        .bind(limit as i64)
+        .fetch_all(&self.user_pool)
+        .await?;
+
+        let mut results = Vec::new();
+        extend_unique(&mut results, custom, limit);
+
+        if results.len() < limit {
+            // Missing packs shouldn't hide the custom matches
+            match self.inner.reverse_translate(translation, from, to, limit).await {
+                Ok(lemmas) => extend_unique(&mut results, lemmas, limit),
+                Err(e) if results.is_empty() => return Err(e),
+                Err(e) => println!("[reverse_translate] Translation packs unavailable: {}", e),
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider backed by a fixed es -> en word list
+    struct FixedProvider;
+
+    #[async_trait]
+    impl TranslationProvider for FixedProvider {
+        async fn get_translation(&self, lemma: &str, _from: &str, _to: &str) -> Result<Option<String>> {
+            Ok(match lemma {
+                "casa" => Some("house".to_string()),
+                "perro" => Some("dog".to_string()),
+                _ => None,
+            })
+        }
+
+        async fn reverse_translate(&self, translation: &str, _from: &str, _to: &str, limit: usize) -> Result<Vec<String>> {
+            let entries = [("correr", "run"), ("huir", "run"), ("casa", "house")];
+            Ok(entries
+                .iter()
+                .filter(|(_, t)| *t == translation)
+                .map(|(lemma, _)| lemma.to_string())
+                .take(limit)
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reverse_translate_includes_custom_translations() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE custom_translations (lemma TEXT NOT NULL, lang_from TEXT NOT NULL,
+             lang_to TEXT NOT NULL, custom_translation TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (lemma, lang_to, translation) in [("trotar", "en", "Run"), ("huir", "en", "run"), ("correr", "fr", "run")] {
+            sqlx::query("INSERT INTO custom_translations VALUES (?, 'es', ?, ?)")
+                .bind(lemma)
+                .bind(lang_to)
+                .bind(translation)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let provider = CustomTranslationProvider::new(Box::new(FixedProvider), pool);
+
+        // Custom matches (case-insensitive) come first, pack matches are deduplicated
+        let lemmas = provider.reverse_translate("run", "es", "en", 10).await.unwrap();
+        assert_eq!(lemmas, vec!["huir", "trotar", "correr"]);
+
+        assert_eq!(provider.reverse_translate("run", "es", "en", 2).await.unwrap(), vec!["huir", "trotar"]);
+        assert_eq!(provider.reverse_translate("house", "es", "en", 10).await.unwrap(), vec!["casa"]);
+        assert!(provider.reverse_translate("cat", "es", "en", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_translate_batch_prefers_custom_translations() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE custom_translations (lemma TEXT NOT NULL, lang_from TEXT NOT NULL,
+             lang_to TEXT NOT NULL, custom_translation TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO custom_translations VALUES ('casa', 'es', 'en', 'home')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let provider = CustomTranslationProvider::new(Box::new(FixedProvider), pool);
+        let lemmas: Vec<String> = ["casa", "perro", "gato"].iter().map(|s| s.to_string()).collect();
+
+        let translations = provider.translate_batch(&lemmas, "es", "en").await.unwrap();
+        assert_eq!(translations.len(), 2);
+        assert_eq!(translations["casa"], "home");
+        assert_eq!(translations["perro"], "dog");
+    }
+}
@@ -2,17 +2,31 @@
  * Vocabulary service - manages user's discovered words
  *
  * Handles:
- * - Recording new words with forms_spoken tracking
+ * - Recording new words with forms_spoken tracking (normalized, case-insensitive)
  * - Updating usage counts
  * - Retrieving user vocabulary with filters
+ * - Counting new words by the session type they were discovered in
+ * - Finding neglected words that haven't been used recently
+ * - Re-translating saved translations into a new primary language
+ * - Defaulting translations to the profile's primary language
+ * - Counting words per tag
+ * - Tagging or untagging many words at once
+ * - Finding lemmas recorded in two languages
+ * - Clearing one language's vocabulary while keeping session history
  * - Checking if words are new
  */
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use sqlx::{Row, SqlitePool};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::{BTreeMap, BTreeSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::db::user::resolve_primary_language;
+use super::pagination::{validate_page, Page, NO_LIMIT};
+use super::translation::{get_translation_provider, TranslationProvider};
+use tauri::Emitter;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VocabWord {
     pub id: i64,
@@ -48,14 +62,45 @@ fn now() -> i64 {
         .as_secs() as i64
 }
 
+/// Normalize a spoken form for forms_spoken: lowercase, surrounding punctuation removed
+/// e.g. "¿Estoy," -> "estoy"
+pub fn normalize_form(form: &str) -> String {
+    form.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Normalize forms and drop duplicates and empty ones, keeping first-seen order
+fn dedupe_forms<I: IntoIterator<Item = S>, S: AsRef<str>>(forms: I) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for form in forms {
+        let form = normalize_form(form.as_ref());
+        if !form.is_empty() && !unique.contains(&form) {
+            unique.push(form);
+        }
+    }
+    unique
+}
+
 /// Record a word in user's vocabulary
 /// If word exists, updates usage_count and adds form to forms_spoken
-/// If new, creates new entry
+/// If new, creates new entry (remembering `session_id` as the session that introduced it)
 pub async fn record_word(
     pool: &SqlitePool,
     lemma: &str,
     language: &str,
     form_spoken: &str,
+    session_id: Option<&str>,
+) -> Result<bool> {
+    let mut conn = pool.acquire().await?;
+    record_word_in(&mut conn, lemma, language, form_spoken, session_id).await
+}
+
+/// `record_word` on an open connection, e.g. inside a caller's transaction
+pub async fn record_word_in(
+    conn: &mut SqliteConnection,
+    lemma: &str,
+    language: &str,
+    form_spoken: &str,
+    session_id: Option<&str>,
 ) -> Result<bool> {
     let timestamp = now();
 
@@ -65,7 +110,7 @@ pub async fn record_word(
     )
     .bind(language)
     .bind(lemma)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?;
 
     match existing {
@@ -79,10 +124,9 @@ pub async fn record_word(
             let mut forms: Vec<String> = serde_json::from_str(&forms_json)
                 .unwrap_or_default();
 
-            // Add new form if not already present
-            if !forms.contains(&form_spoken.to_string()) {
-                forms.push(form_spoken.to_string());
-            }
+            // Add new form if not already present ("Estoy," and "estoy" are the same form)
+            forms.push(form_spoken.to_string());
+            let forms = dedupe_forms(forms);
 
             let new_usage_count = usage_count + 1;
 
@@ -102,7 +146,7 @@ pub async fn record_word(
             .bind(new_usage_count)
             .bind(timestamp)
             .bind(id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
             // AUTO-MASTERING LOGIC: Check if word should be auto-mastered
@@ -112,7 +156,7 @@ pub async fn record_word(
                     "SELECT COALESCE(tags, '[]') FROM vocab WHERE id = ?"
                 )
                 .bind(id)
-                .fetch_one(pool)
+                .fetch_one(&mut *conn)
                 .await?;
 
                 let tags: Vec<String> = serde_json::from_str(&tags_json)
@@ -128,7 +172,7 @@ pub async fn record_word(
                     .bind(serde_json::to_string(&mastered_tags)?)
                     .bind(timestamp)
                     .bind(id)
-                    .execute(pool)
+                    .execute(&mut *conn)
                     .await?;
 
                     println!("[vocab] Auto-mastered word '{}' after {} uses", lemma, new_usage_count);
@@ -139,16 +183,16 @@ pub async fn record_word(
         }
         None => {
             // New word - insert it
-            let forms = vec![form_spoken.to_string()];
+            let forms = dedupe_forms([form_spoken]);
 
             sqlx::query(
                 r#"
                 INSERT INTO vocab (
                     language, lemma, forms_spoken,
                     first_seen_at, last_seen_at, usage_count,
-                    mastered, created_at, updated_at
+                    mastered, created_at, updated_at, first_session_id
                 )
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#
             )
             .bind(language)
@@ -160,7 +204,8 @@ pub async fn record_word(
             .bind(false)
             .bind(timestamp)
             .bind(timestamp)
-            .execute(pool)
+            .bind(session_id)
+            .execute(&mut *conn)
             .await?;
 
             Ok(true) // New word
@@ -172,6 +217,33 @@ pub async fn record_word(
 pub async fn get_user_vocab(
     pool: &SqlitePool,
     language: &str,
+) -> Result<Vec<VocabWord>> {
+    fetch_user_vocab(pool, language, NO_LIMIT, 0).await
+}
+
+/// Get one page of vocabulary (most used first) with the total word count
+pub async fn get_user_vocab_paged(
+    pool: &SqlitePool,
+    language: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Page<VocabWord>> {
+    validate_page(limit, offset)?;
+
+    let items = fetch_user_vocab(pool, language, limit, offset).await?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vocab WHERE language = ?")
+        .bind(language)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(Page { items, total, limit, offset })
+}
+
+async fn fetch_user_vocab(
+    pool: &SqlitePool,
+    language: &str,
+    limit: i64,
+    offset: i64,
 ) -> Result<Vec<VocabWord>> {
     let rows = sqlx::query(
         r#"
@@ -179,38 +251,40 @@ pub async fn get_user_vocab(
                first_seen_at, last_seen_at, usage_count, mastered, COALESCE(tags, '[]') as tags
         FROM vocab
         WHERE language = ?
-        ORDER BY usage_count DESC, last_seen_at DESC
+        ORDER BY usage_count DESC, last_seen_at DESC, id
+        LIMIT ? OFFSET ?
         "#
     )
     .bind(language)
+    .bind(limit)
+    .bind(offset)
     .fetch_all(pool)
     .await?;
 
-    let mut words = Vec::new();
+    Ok(rows.iter().map(vocab_word_from_row).collect())
+}
 
-    for row in rows {
-        let forms_json: String = row.get("forms_spoken");
-        let forms: Vec<String> = serde_json::from_str(&forms_json)
-            .unwrap_or_default();
+/// Build a VocabWord from a row selected with the columns used by `get_user_vocab`
+fn vocab_word_from_row(row: &sqlx::sqlite::SqliteRow) -> VocabWord {
+    let forms_json: String = row.get("forms_spoken");
+    let forms: Vec<String> = serde_json::from_str(&forms_json)
+        .unwrap_or_default();
 
-        let tags_json: String = row.get("tags");
-        let tags: Vec<String> = serde_json::from_str(&tags_json)
-            .unwrap_or_default();
+    let tags_json: String = row.get("tags");
+    let tags: Vec<String> = serde_json::from_str(&tags_json)
+        .unwrap_or_default();
 
-        words.push(VocabWord {
-            id: row.get("id"),
-            language: row.get("language"),
-            lemma: row.get("lemma"),
-            forms_spoken: forms,
-            first_seen_at: row.get("first_seen_at"),
-            last_seen_at: row.get("last_seen_at"),
-            usage_count: row.get("usage_count"),
-            mastered: row.get("mastered"),
-            tags,
-        });
+    VocabWord {
+        id: row.get("id"),
+        language: row.get("language"),
+        lemma: row.get("lemma"),
+        forms_spoken: forms,
+        first_seen_at: row.get("first_seen_at"),
+        last_seen_at: row.get("last_seen_at"),
+        usage_count: row.get("usage_count"),
+        mastered: row.get("mastered"),
+        tags,
     }
-
-    Ok(words)
 }
 
 /// Check if a word is new (not in vocabulary)
@@ -274,6 +348,74 @@ pub async fn get_vocab_stats(
     })
 }
 
+/// Number of words first discovered in one session type
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SessionTypeDiscovery {
+    pub session_type: String,
+    pub new_words: i32,
+}
+
+/// Count vocabulary words by the type of session that introduced them
+/// Words added outside a session (or whose session was deleted) aren't counted
+pub async fn get_vocab_discovery_by_session_type(
+    pool: &SqlitePool,
+    language: &str,
+) -> Result<Vec<SessionTypeDiscovery>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT COALESCE(s.session_type, 'free_speak') as session_type, COUNT(*) as new_words
+        FROM vocab v
+        JOIN sessions s ON s.id = v.first_session_id
+        WHERE v.language = ?
+        GROUP BY COALESCE(s.session_type, 'free_speak')
+        ORDER BY new_words DESC, session_type
+        "#
+    )
+    .bind(language)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SessionTypeDiscovery {
+            session_type: row.get("session_type"),
+            new_words: row.get("new_words"),
+        })
+        .collect())
+}
+
+/// Normalize and de-duplicate forms_spoken of every word, for rows recorded before
+/// forms were normalized
+/// Returns the number of words whose forms changed
+pub async fn dedupe_forms_spoken(pool: &SqlitePool) -> Result<i32> {
+    let timestamp = now();
+
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, COALESCE(forms_spoken, '[]') FROM vocab")
+        .fetch_all(pool)
+        .await?;
+
+    let mut changed = 0;
+    for (id, forms_json) in rows {
+        let Ok(forms) = serde_json::from_str::<Vec<String>>(&forms_json) else {
+            continue;
+        };
+
+        let deduped = dedupe_forms(&forms);
+        if deduped != forms {
+            sqlx::query("UPDATE vocab SET forms_spoken = ?, updated_at = ? WHERE id = ?")
+                .bind(serde_json::to_string(&deduped)?)
+                .bind(timestamp)
+                .bind(id)
+                .execute(pool)
+                .await?;
+            changed += 1;
+        }
+    }
+
+    println!("[dedupe_forms_spoken] Cleaned forms of {} words", changed);
+    Ok(changed)
+}
+
 /// Clean up vocabulary by removing punctuation from lemmas
 /// Returns the number of lemmas that were cleaned
 pub async fn clean_punctuation(pool: &SqlitePool) -> Result<i32> {
@@ -320,14 +462,15 @@ pub async fn clean_punctuation(pool: &SqlitePool) -> Result<i32> {
 
 /// Get recently learned vocabulary with translations
 /// Returns words learned in the last N days, with translations to primary language
+/// (the profile's primary language when `primary_language` is None)
 pub async fn get_recent_vocab(
     pool: &SqlitePool,
-    _app_handle: &tauri::AppHandle,
     language: &str,
-    primary_language: &str,
+    primary_language: Option<&str>,
     days: i32,
     limit: i32,
 ) -> Result<Vec<VocabWordWithTranslation>> {
+    let primary_language = &resolve_primary_language(pool, primary_language).await?;
     let cutoff = now() - (days as i64 * 24 * 60 * 60);
 
     // Get recent words
@@ -378,6 +521,35 @@ pub async fn get_recent_vocab(
     Ok(words)
 }
 
+/// Get words that haven't been spoken for at least `not_seen_days` ("forgetting" candidates)
+/// Oldest first; mastered words are left out
+pub async fn get_stale_words(
+    pool: &SqlitePool,
+    language: &str,
+    not_seen_days: i32,
+    limit: i32,
+) -> Result<Vec<VocabWord>> {
+    let cutoff = now() - (not_seen_days as i64 * 24 * 60 * 60);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, language, lemma, forms_spoken,
+               first_seen_at, last_seen_at, usage_count, mastered, COALESCE(tags, '[]') as tags
+        FROM vocab
+        WHERE language = ? AND last_seen_at < ? AND COALESCE(mastered, 0) = 0
+        ORDER BY last_seen_at ASC
+        LIMIT ?
+        "#
+    )
+    .bind(language)
+    .bind(cutoff)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(vocab_word_from_row).collect())
+}
+
 /// Custom translation entry for user-edited translations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomTranslation {
@@ -510,6 +682,78 @@ pub async fn remove_tag(pool: &SqlitePool, lemma: &str, language: &str, tag: &st
     Ok(tags)
 }
 
+/// Give every word in `lemmas` the tag `tag` in one transaction (replacing their
+/// current tag, like `add_tag`)
+/// Lemmas not in the language's vocabulary are skipped. Returns the number of words updated.
+pub async fn add_tag_bulk(pool: &SqlitePool, language: &str, lemmas: &[String], tag: &str) -> Result<u64> {
+    let timestamp = now();
+    let tags_json = serde_json::to_string(&[tag])?;
+    let mastered = tag == "mastered";
+    let unique: BTreeSet<&String> = lemmas.iter().collect();
+
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+    for lemma in unique {
+        updated += sqlx::query(
+            "UPDATE vocab SET tags = ?, mastered = ?, updated_at = ? WHERE lemma = ? AND language = ?"
+        )
+        .bind(&tags_json)
+        .bind(mastered)
+        .bind(timestamp)
+        .bind(lemma)
+        .bind(language)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+/// Remove `tag` from every word in `lemmas` in one transaction
+/// Returns the number of words that had the tag.
+pub async fn remove_tag_bulk(pool: &SqlitePool, language: &str, lemmas: &[String], tag: &str) -> Result<u64> {
+    let timestamp = now();
+    let unique: BTreeSet<&String> = lemmas.iter().collect();
+
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+    for lemma in unique {
+        let current_tags_json: Option<String> = sqlx::query_scalar(
+            "SELECT COALESCE(tags, '[]') FROM vocab WHERE lemma = ? AND language = ?"
+        )
+        .bind(lemma)
+        .bind(language)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current_tags_json) = current_tags_json else {
+            continue;
+        };
+        let mut tags: Vec<String> = serde_json::from_str(&current_tags_json).unwrap_or_default();
+        if !tags.iter().any(|t| t == tag) {
+            continue;
+        }
+        tags.retain(|t| t != tag);
+
+        sqlx::query(
+            "UPDATE vocab SET tags = ?, mastered = ?, updated_at = ? WHERE lemma = ? AND language = ?"
+        )
+        .bind(serde_json::to_string(&tags)?)
+        .bind(tags.iter().any(|t| t == "mastered"))
+        .bind(timestamp)
+        .bind(lemma)
+        .bind(language)
+        .execute(&mut *tx)
+        .await?;
+        updated += 1;
+    }
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
 /// Get vocabulary filtered by tag
 pub async fn get_vocab_by_tag(
     pool: &SqlitePool,
@@ -556,6 +800,78 @@ pub async fn get_vocab_by_tag(
     Ok(words)
 }
 
+/// Number of words carrying each tag, plus words with no tag at all
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCounts {
+    pub by_tag: BTreeMap<String, i64>,
+    pub untagged: i64,
+}
+
+/// Count a language's words per tag
+pub async fn get_tag_counts(pool: &SqlitePool, language: &str) -> Result<TagCounts> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT tag.value AS tag, COUNT(DISTINCT v.id) AS count
+        FROM vocab v, json_each(COALESCE(v.tags, '[]')) AS tag
+        WHERE v.language = ?
+        GROUP BY tag.value
+        "#
+    )
+    .bind(language)
+    .fetch_all(pool)
+    .await?;
+
+    let untagged: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM vocab WHERE language = ? AND json_array_length(COALESCE(tags, '[]')) = 0"
+    )
+    .bind(language)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(TagCounts {
+        by_tag: rows.into_iter().collect(),
+        untagged,
+    })
+}
+
+/// A lemma the user has recorded in two languages (cognates, loanwords)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedLemma {
+    pub lemma: String,
+    /// Usage count in the first language
+    pub usage_count_a: i64,
+    /// Usage count in the second language
+    pub usage_count_b: i64,
+}
+
+/// Lemmas present in both languages' vocabulary, most used first
+pub async fn get_cross_language_overlap(pool: &SqlitePool, lang_a: &str, lang_b: &str) -> Result<Vec<SharedLemma>> {
+    if lang_a == lang_b {
+        anyhow::bail!("Pick two different languages to compare");
+    }
+
+    let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT a.lemma, a.usage_count, b.usage_count
+        FROM vocab a
+        JOIN vocab b ON b.lemma = a.lemma AND b.language = ?
+        WHERE a.language = ?
+        ORDER BY a.usage_count + b.usage_count DESC, a.lemma
+        "#
+    )
+    .bind(lang_b)
+    .bind(lang_a)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(lemma, usage_count_a, usage_count_b)| SharedLemma { lemma, usage_count_a, usage_count_b })
+        .collect())
+}
+
 /// Fix vocabulary entries by re-lemmatizing inflected forms
 /// Returns the number of entries fixed
 pub async fn fix_vocab_lemmas(
@@ -626,6 +942,41 @@ pub async fn fix_vocab_lemmas(
     Ok(fixed_count)
 }
 
+/// Rows removed by `clear_vocabulary`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearedVocabulary {
+    pub words: u64,
+    pub custom_translations: u64,
+}
+
+/// Delete a language's vocabulary and the custom translations from that language
+///
+/// Sessions and session_words are kept, so history and per-session stats stay intact;
+/// words are recorded as new again the next time they're spoken.
+pub async fn clear_vocabulary(pool: &SqlitePool, language: &str) -> Result<ClearedVocabulary> {
+    let mut tx = pool.begin().await?;
+
+    let words = sqlx::query("DELETE FROM vocab WHERE language = ?")
+        .bind(language)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    let custom_translations = sqlx::query("DELETE FROM custom_translations WHERE lang_from = ?")
+        .bind(language)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+
+    println!(
+        "[clear_vocabulary] Removed {} words and {} custom translations for {}",
+        words, custom_translations, language
+    );
+    Ok(ClearedVocabulary { words, custom_translations })
+}
+
 /// Set a custom translation for a word (creates or updates)
 pub async fn set_custom_translation(
     pool: &SqlitePool,
@@ -702,6 +1053,175 @@ pub async fn delete_custom_translation(
     Ok(())
 }
 
+/// Words looked up per translate_batch call (and per progress event) when re-translating
+const RETRANSLATE_BATCH_SIZE: usize = 200;
+
+/// Outcome of `retranslate_vocab`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetranslateSummary {
+    pub total: usize,
+    /// Words whose translation was written
+    pub updated: usize,
+    /// Words the translation pack has no entry for (left unchanged)
+    pub not_found: usize,
+}
+
+/// Progress payload for the `retranslate_progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetranslateProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// Re-translate every word of `language` into `new_primary_language`
+///
+/// Fresh translations come from the translation packs (not the user's saved ones) and
+/// are stored as custom translations for the new language pair.
+/// Emits `retranslate_progress` after each batch.
+pub async fn retranslate_vocab(
+    pool: &SqlitePool,
+    app: &tauri::AppHandle,
+    language: &str,
+    new_primary_language: &str,
+) -> Result<RetranslateSummary> {
+    let provider = get_translation_provider(app, None).await?;
+
+    retranslate_vocab_with(pool, provider.as_ref(), language, new_primary_language, |progress| {
+        let _ = app.emit("retranslate_progress", progress);
+    })
+    .await
+}
+
+/// `retranslate_vocab` with an explicit provider and progress callback
+pub async fn retranslate_vocab_with(
+    pool: &SqlitePool,
+    provider: &dyn TranslationProvider,
+    language: &str,
+    new_primary_language: &str,
+    mut on_progress: impl FnMut(RetranslateProgress),
+) -> Result<RetranslateSummary> {
+    let lemmas: Vec<String> = sqlx::query_scalar("SELECT lemma FROM vocab WHERE language = ? ORDER BY lemma")
+        .bind(language)
+        .fetch_all(pool)
+        .await?;
+
+    let mut summary = RetranslateSummary {
+        total: lemmas.len(),
+        updated: 0,
+        not_found: 0,
+    };
+
+    for (i, batch) in lemmas.chunks(RETRANSLATE_BATCH_SIZE).enumerate() {
+        let translations = provider.translate_batch(batch, language, new_primary_language).await?;
+
+        for lemma in batch {
+            match translations.get(lemma) {
+                Some(translation) => {
+                    set_custom_translation(pool, lemma, language, new_primary_language, translation, None).await?;
+                    summary.updated += 1;
+                }
+                None => summary.not_found += 1,
+            }
+        }
+
+        on_progress(RetranslateProgress {
+            processed: (i * RETRANSLATE_BATCH_SIZE + batch.len()).min(summary.total),
+            total: summary.total,
+        });
+    }
+
+    println!(
+        "[retranslate_vocab] {} -> {}: {} updated, {} without translation",
+        language, new_primary_language, summary.updated, summary.not_found
+    );
+    Ok(summary)
+}
+
+/// A common word the user hasn't spoken yet
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedWord {
+    pub lemma: String,
+    /// Frequency from the lemma DB, or the lemma's number of inflected forms for packs
+    /// without frequency data
+    pub frequency: i64,
+    pub translation: Option<String>,
+}
+
+/// Suggest the most common lemmas of `language` that aren't in the user's vocab yet,
+/// translated into the profile's primary language
+pub async fn recommend_new_words(
+    pool: &SqlitePool,
+    app: &tauri::AppHandle,
+    language: &str,
+    limit: usize,
+) -> Result<Vec<RecommendedWord>> {
+    let primary_language = resolve_primary_language(pool, None).await?;
+    let provider = get_translation_provider(app, Some(pool)).await?;
+    let lemma_pool = crate::db::langpack::open_lemma_db(language, app).await?;
+
+    let words = recommend_new_words_with(pool, &lemma_pool, provider.as_ref(), language, &primary_language, limit).await;
+    lemma_pool.close().await;
+    words
+}
+
+/// `recommend_new_words` with explicit lemma DB and translation provider
+pub async fn recommend_new_words_with(
+    pool: &SqlitePool,
+    lemma_pool: &SqlitePool,
+    provider: &dyn TranslationProvider,
+    language: &str,
+    primary_language: &str,
+    limit: usize,
+) -> Result<Vec<RecommendedWord>> {
+    let known: std::collections::HashSet<String> = sqlx::query_scalar("SELECT lemma FROM vocab WHERE language = ?")
+        .bind(language)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    // Packs don't all ship frequency data; lemmas with many inflected forms
+    // (common verbs, nouns) stand in for frequent ones
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info('lemmas')")
+        .fetch_all(lemma_pool)
+        .await?;
+    let score = if columns.iter().any(|c| c == "frequency") {
+        "MAX(COALESCE(frequency, 0))"
+    } else {
+        "COUNT(*)"
+    };
+
+    // Fetch enough extra rows that known words can be skipped
+    let ranked: Vec<(String, i64)> = sqlx::query_as(&format!(
+        "SELECT lemma, {} AS score FROM lemmas GROUP BY lemma ORDER BY score DESC, lemma ASC LIMIT ?",
+        score
+    ))
+    .bind((limit + known.len()) as i64)
+    .fetch_all(lemma_pool)
+    .await?;
+
+    let candidates: Vec<(String, i64)> = ranked
+        .into_iter()
+        .filter(|(lemma, _)| !known.contains(lemma))
+        .take(limit)
+        .collect();
+
+    let lemmas: Vec<String> = candidates.iter().map(|(lemma, _)| lemma.clone()).collect();
+    let translations = provider.translate_batch(&lemmas, language, primary_language).await?;
+
+    Ok(candidates
+        .into_iter()
+        .map(|(lemma, frequency)| RecommendedWord {
+            translation: translations.get(&lemma).cloned(),
+            lemma,
+            frequency,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -728,6 +1248,7 @@ mod tests {
                 tags TEXT DEFAULT '[]',
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
+                first_session_id TEXT,
                 UNIQUE(language, lemma)
             )
             "#
@@ -743,7 +1264,7 @@ mod tests {
     async fn test_record_new_word() {
         let pool = setup_test_db().await;
 
-        let is_new = record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        let is_new = record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
         assert!(is_new);
 
         // Verify word was inserted
@@ -759,10 +1280,10 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Record first time
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Record again with different form
-        let is_new = record_word(&pool, "estar", "es", "estás").await.unwrap();
+        let is_new = record_word(&pool, "estar", "es", "estás", None).await.unwrap();
         assert!(!is_new);
 
         // Verify updated
@@ -782,7 +1303,7 @@ mod tests {
         assert!(is_new_word(&pool, "estar", "es").await.unwrap());
 
         // Record it
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Should not be new
         assert!(!is_new_word(&pool, "estar", "es").await.unwrap());
@@ -793,9 +1314,9 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add some words
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
-        record_word(&pool, "correr", "es", "corriendo").await.unwrap();
-        record_word(&pool, "casa", "es", "casa").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
+        record_word(&pool, "correr", "es", "corriendo", None).await.unwrap();
+        record_word(&pool, "casa", "es", "casa", None).await.unwrap();
 
         let stats = get_vocab_stats(&pool, "es").await.unwrap();
         assert_eq!(stats.total_words, 3);
@@ -803,12 +1324,49 @@ mod tests {
         assert_eq!(stats.words_this_week, 3);
     }
 
+    #[tokio::test]
+    async fn test_vocab_discovery_by_session_type() {
+        let pool = setup_test_db().await;
+        sqlx::query("CREATE TABLE sessions (id TEXT PRIMARY KEY, session_type TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (id, session_type) in [("read", Some("read_aloud")), ("speak", Some("free_speak")), ("legacy", None)] {
+            sqlx::query("INSERT INTO sessions (id, session_type) VALUES (?, ?)")
+                .bind(id)
+                .bind(session_type)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        for lemma in ["casa", "perro", "libro"] {
+            record_word(&pool, lemma, "es", lemma, Some("read")).await.unwrap();
+        }
+        record_word(&pool, "correr", "es", "corro", Some("speak")).await.unwrap();
+        record_word(&pool, "comer", "es", "como", Some("legacy")).await.unwrap();
+        // Already known: the introducing session doesn't change
+        record_word(&pool, "casa", "es", "casas", Some("speak")).await.unwrap();
+        // Added manually, not from a session
+        record_word(&pool, "gato", "es", "gato", None).await.unwrap();
+        record_word(&pool, "chat", "fr", "chat", Some("speak")).await.unwrap();
+
+        let discovery = get_vocab_discovery_by_session_type(&pool, "es").await.unwrap();
+        assert_eq!(
+            discovery,
+            vec![
+                SessionTypeDiscovery { session_type: "read_aloud".to_string(), new_words: 3 },
+                SessionTypeDiscovery { session_type: "free_speak".to_string(), new_words: 2 },
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_word() {
         let pool = setup_test_db().await;
 
         // Add a word
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Verify it exists
         let words = get_user_vocab(&pool, "es").await.unwrap();
@@ -836,7 +1394,7 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add a word (mastered defaults to false)
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Verify initial state
         let words = get_user_vocab(&pool, "es").await.unwrap();
@@ -864,8 +1422,8 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add words
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
-        record_word(&pool, "correr", "es", "corriendo").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
+        record_word(&pool, "correr", "es", "corriendo", None).await.unwrap();
 
         // Initial stats - no mastered words
         let stats = get_vocab_stats(&pool, "es").await.unwrap();
@@ -891,7 +1449,7 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add a word
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Add a tag
         let tags = add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
@@ -908,7 +1466,7 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add a word and tag it
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
         add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
 
         // Remove the tag
@@ -925,7 +1483,7 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add a word
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
 
         // Add first tag
         add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
@@ -945,7 +1503,7 @@ mod tests {
 
         // Record a word 19 times
         for i in 0..19 {
-            record_word(&pool, "estar", "es", &format!("form{}", i)).await.unwrap();
+            record_word(&pool, "estar", "es", &format!("form{}", i), None).await.unwrap();
         }
 
         // Verify not yet mastered
@@ -955,7 +1513,7 @@ mod tests {
         assert_eq!(words[0].tags.len(), 0);
 
         // Record 20th time - should auto-master
-        record_word(&pool, "estar", "es", "form19").await.unwrap();
+        record_word(&pool, "estar", "es", "form19", None).await.unwrap();
 
         // Verify auto-mastered
         let words = get_user_vocab(&pool, "es").await.unwrap();
@@ -969,12 +1527,12 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add a word with "needs-practice" tag
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
         add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
 
         // Record it 19 more times (total 20)
         for i in 1..20 {
-            record_word(&pool, "estar", "es", &format!("form{}", i)).await.unwrap();
+            record_word(&pool, "estar", "es", &format!("form{}", i), None).await.unwrap();
         }
 
         // Verify it was NOT auto-mastered due to needs-practice tag
@@ -989,13 +1547,13 @@ mod tests {
         let pool = setup_test_db().await;
 
         // Add multiple words with different tags
-        record_word(&pool, "estar", "es", "estoy").await.unwrap();
+        record_word(&pool, "estar", "es", "estoy", None).await.unwrap();
         add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
 
-        record_word(&pool, "correr", "es", "corriendo").await.unwrap();
+        record_word(&pool, "correr", "es", "corriendo", None).await.unwrap();
         add_tag(&pool, "correr", "es", "mastered").await.unwrap();
 
-        record_word(&pool, "casa", "es", "casa").await.unwrap();
+        record_word(&pool, "casa", "es", "casa", None).await.unwrap();
         // No tag for casa
 
         // Get words with "needs-practice" tag
@@ -1008,4 +1566,438 @@ mod tests {
         assert_eq!(mastered.len(), 1);
         assert_eq!(mastered[0].lemma, "correr");
     }
+
+    #[tokio::test]
+    async fn test_get_tag_counts() {
+        let pool = setup_test_db().await;
+
+        for lemma in ["estar", "correr", "comer"] {
+            record_word(&pool, lemma, "es", lemma, None).await.unwrap();
+        }
+        add_tag(&pool, "estar", "es", "needs-practice").await.unwrap();
+        add_tag(&pool, "correr", "es", "mastered").await.unwrap();
+        add_tag(&pool, "comer", "es", "mastered").await.unwrap();
+
+        record_word(&pool, "casa", "es", "casa", None).await.unwrap();
+        record_word(&pool, "perro", "es", "perro", None).await.unwrap();
+        add_tag(&pool, "perro", "es", "needs-practice").await.unwrap();
+        remove_tag(&pool, "perro", "es", "needs-practice").await.unwrap();
+
+        // Other languages aren't counted
+        record_word(&pool, "house", "en", "house", None).await.unwrap();
+        add_tag(&pool, "house", "en", "mastered").await.unwrap();
+
+        let counts = get_tag_counts(&pool, "es").await.unwrap();
+        assert_eq!(counts.by_tag.len(), 2);
+        assert_eq!(counts.by_tag["mastered"], 2);
+        assert_eq!(counts.by_tag["needs-practice"], 1);
+        assert_eq!(counts.untagged, 2);
+
+        let empty = get_tag_counts(&pool, "fr").await.unwrap();
+        assert!(empty.by_tag.is_empty());
+        assert_eq!(empty.untagged, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cross_language_overlap() {
+        let pool = setup_test_db().await;
+
+        for (lemma, language, uses) in [
+            ("chocolate", "es", 3),
+            ("hotel", "es", 1),
+            ("casa", "es", 5),
+            ("chocolate", "en", 1),
+            ("hotel", "en", 4),
+            ("house", "en", 2),
+            ("hotel", "fr", 1),
+        ] {
+            for _ in 0..uses {
+                record_word(&pool, lemma, language, lemma, None).await.unwrap();
+            }
+        }
+
+        let overlap = get_cross_language_overlap(&pool, "es", "en").await.unwrap();
+        assert_eq!(
+            overlap,
+            vec![
+                SharedLemma { lemma: "hotel".to_string(), usage_count_a: 1, usage_count_b: 4 },
+                SharedLemma { lemma: "chocolate".to_string(), usage_count_a: 3, usage_count_b: 1 },
+            ]
+        );
+
+        // Counts follow the argument order
+        let reversed = get_cross_language_overlap(&pool, "en", "es").await.unwrap();
+        assert_eq!((reversed[0].usage_count_a, reversed[0].usage_count_b), (4, 1));
+
+        assert!(get_cross_language_overlap(&pool, "es", "de").await.unwrap().is_empty());
+        assert!(get_cross_language_overlap(&pool, "es", "es").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_stale_words() {
+        let pool = setup_test_db().await;
+        let day = 24 * 60 * 60;
+
+        for lemma in ["hablar", "comer", "vivir", "ser", "ir"] {
+            record_word(&pool, lemma, "es", lemma, None).await.unwrap();
+        }
+        record_word(&pool, "old", "en", "old", None).await.unwrap();
+
+        let last_seen = [("hablar", 40), ("comer", 10), ("vivir", 60), ("ser", 90), ("ir", 1), ("old", 90)];
+        for (lemma, days_ago) in last_seen {
+            sqlx::query("UPDATE vocab SET last_seen_at = ? WHERE lemma = ?")
+                .bind(now() - days_ago * day)
+                .bind(lemma)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        add_tag(&pool, "ser", "es", "mastered").await.unwrap();
+
+        // Not seen for 30+ days, oldest first, without the mastered word or other languages
+        let stale = get_stale_words(&pool, "es", 30, 10).await.unwrap();
+        let lemmas: Vec<&str> = stale.iter().map(|w| w.lemma.as_str()).collect();
+        assert_eq!(lemmas, vec!["vivir", "hablar"]);
+
+        let stale = get_stale_words(&pool, "es", 7, 2).await.unwrap();
+        let lemmas: Vec<&str> = stale.iter().map(|w| w.lemma.as_str()).collect();
+        assert_eq!(lemmas, vec!["vivir", "hablar"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_user_vocab_paged() {
+        let pool = setup_test_db().await;
+
+        // usage counts 1..=7 so the order is deterministic
+        for (i, lemma) in ["a", "b", "c", "d", "e", "f", "g"].iter().enumerate() {
+            for _ in 0..=i {
+                record_word(&pool, lemma, "es", lemma, None).await.unwrap();
+            }
+        }
+        record_word(&pool, "other", "fr", "other", None).await.unwrap();
+
+        let page = get_user_vocab_paged(&pool, "es", 3, 0).await.unwrap();
+        assert_eq!(page.total, 7);
+        let lemmas: Vec<&str> = page.items.iter().map(|w| w.lemma.as_str()).collect();
+        assert_eq!(lemmas, vec!["g", "f", "e"]);
+
+        let page = get_user_vocab_paged(&pool, "es", 3, 6).await.unwrap();
+        let lemmas: Vec<&str> = page.items.iter().map(|w| w.lemma.as_str()).collect();
+        assert_eq!(lemmas, vec!["a"]);
+
+        let page = get_user_vocab_paged(&pool, "es", 3, 10).await.unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(page.total, 7);
+
+        assert!(get_user_vocab_paged(&pool, "es", 3, -1).await.is_err());
+    }
+
+    /// In-memory stand-in for the translation packs
+    struct MockProvider {
+        glosses: std::collections::HashMap<(String, String), String>,
+    }
+
+    #[async_trait::async_trait]
+    impl TranslationProvider for MockProvider {
+        async fn get_translation(&self, lemma: &str, _from: &str, to: &str) -> Result<Option<String>> {
+            Ok(self.glosses.get(&(lemma.to_string(), to.to_string())).cloned())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retranslate_vocab_writes_new_language_glosses() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            CREATE TABLE custom_translations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                lemma TEXT NOT NULL,
+                lang_from TEXT NOT NULL,
+                lang_to TEXT NOT NULL,
+                custom_translation TEXT NOT NULL,
+                notes TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE(lemma, lang_from, lang_to)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for lemma in ["casa", "perro", "zzz"] {
+            record_word(&pool, lemma, "es", lemma, None).await.unwrap();
+        }
+        set_custom_translation(&pool, "casa", "es", "en", "house", None).await.unwrap();
+
+        let provider = MockProvider {
+            glosses: [
+                (("casa".to_string(), "fr".to_string()), "maison".to_string()),
+                (("perro".to_string(), "fr".to_string()), "chien".to_string()),
+                (("casa".to_string(), "en".to_string()), "house".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let mut progress = Vec::new();
+        let summary = retranslate_vocab_with(&pool, &provider, "es", "fr", |p| progress.push(p.processed))
+            .await
+            .unwrap();
+
+        assert_eq!((summary.total, summary.updated, summary.not_found), (3, 2, 1));
+        assert_eq!(progress, vec![3]);
+        assert_eq!(get_custom_translation(&pool, "casa", "es", "fr").await.unwrap(), Some("maison".to_string()));
+        assert_eq!(get_custom_translation(&pool, "perro", "es", "fr").await.unwrap(), Some("chien".to_string()));
+        assert_eq!(get_custom_translation(&pool, "zzz", "es", "fr").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tagging() {
+        let pool = setup_test_db().await;
+        for lemma in ["casa", "perro", "gato", "mesa"] {
+            record_word(&pool, lemma, "es", lemma, None).await.unwrap();
+        }
+        record_word(&pool, "casa", "pt", "casa", None).await.unwrap();
+        add_tag(&pool, "mesa", "es", "mastered").await.unwrap();
+
+        let lemmas: Vec<String> = ["casa", "perro", "mesa", "casa", "nada"].iter().map(|s| s.to_string()).collect();
+        let updated = add_tag_bulk(&pool, "es", &lemmas, "needs-practice").await.unwrap();
+        assert_eq!(updated, 3);
+
+        let mut tagged: Vec<String> = get_vocab_by_tag(&pool, "es", "needs-practice")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|w| w.lemma)
+            .collect();
+        tagged.sort();
+        assert_eq!(tagged, vec!["casa", "mesa", "perro"]);
+        // Tags replace the previous one, as with add_tag
+        let mastered: bool = sqlx::query_scalar("SELECT mastered FROM vocab WHERE lemma = 'mesa'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!mastered);
+        // Other languages and unlisted words are untouched
+        assert!(get_vocab_by_tag(&pool, "pt", "needs-practice").await.unwrap().is_empty());
+
+        let untag: Vec<String> = ["casa", "gato"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(remove_tag_bulk(&pool, "es", &untag, "needs-practice").await.unwrap(), 1);
+        assert_eq!(get_vocab_by_tag(&pool, "es", "needs-practice").await.unwrap().len(), 2);
+
+        // A failure part-way leaves every word as it was
+        sqlx::query(
+            "CREATE TRIGGER fail_on_perro BEFORE UPDATE ON vocab WHEN NEW.lemma = 'perro'
+             BEGIN SELECT RAISE(ABORT, 'boom'); END"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let all: Vec<String> = ["casa", "mesa", "perro"].iter().map(|s| s.to_string()).collect();
+        assert!(add_tag_bulk(&pool, "es", &all, "favorite").await.is_err());
+        assert!(get_vocab_by_tag(&pool, "es", "favorite").await.unwrap().is_empty());
+        assert_eq!(get_vocab_by_tag(&pool, "es", "needs-practice").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_clear_vocabulary_keeps_sessions_and_other_languages() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            CREATE TABLE custom_translations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                lemma TEXT NOT NULL,
+                lang_from TEXT NOT NULL,
+                lang_to TEXT NOT NULL,
+                custom_translation TEXT NOT NULL,
+                notes TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE(lemma, lang_from, lang_to)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE sessions (id TEXT PRIMARY KEY, language TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE session_words (session_id TEXT NOT NULL, lemma TEXT NOT NULL, count INTEGER NOT NULL, is_new BOOLEAN NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO sessions VALUES ('s1', 'es'), ('s2', 'fr')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO session_words VALUES ('s1', 'casa', 2, 1), ('s2', 'maison', 1, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for lemma in ["casa", "perro"] {
+            record_word(&pool, lemma, "es", lemma, Some("s1")).await.unwrap();
+        }
+        record_word(&pool, "maison", "fr", "maison", Some("s2")).await.unwrap();
+        set_custom_translation(&pool, "casa", "es", "en", "house", None).await.unwrap();
+        set_custom_translation(&pool, "maison", "fr", "en", "house", None).await.unwrap();
+
+        let cleared = clear_vocabulary(&pool, "es").await.unwrap();
+        assert_eq!((cleared.words, cleared.custom_translations), (2, 1));
+
+        let vocab: Vec<(String,)> = sqlx::query_as("SELECT language FROM vocab")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(vocab, vec![("fr".to_string(),)]);
+        assert_eq!(get_custom_translation(&pool, "casa", "es", "en").await.unwrap(), None);
+        assert_eq!(get_custom_translation(&pool, "maison", "fr", "en").await.unwrap(), Some("house".to_string()));
+
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions").fetch_one(&pool).await.unwrap();
+        let session_words: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM session_words").fetch_one(&pool).await.unwrap();
+        assert_eq!((sessions, session_words), (2, 2));
+
+        // A cleared word is new again
+        assert!(record_word(&pool, "casa", "es", "casa", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recent_vocab_uses_profile_primary_language() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            CREATE TABLE custom_translations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                lemma TEXT NOT NULL,
+                lang_from TEXT NOT NULL,
+                lang_to TEXT NOT NULL,
+                custom_translation TEXT NOT NULL,
+                notes TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                UNIQUE(lemma, lang_from, lang_to)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at INTEGER NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        record_word(&pool, "casa", "es", "casa", None).await.unwrap();
+        set_custom_translation(&pool, "casa", "es", "en", "house", None).await.unwrap();
+        set_custom_translation(&pool, "casa", "es", "fr", "maison", None).await.unwrap();
+
+        // Nothing stored yet: English
+        let words = get_recent_vocab(&pool, "es", None, 7, 10).await.unwrap();
+        assert_eq!(words[0].translation.as_deref(), Some("house"));
+
+        crate::db::user::set_profile(&pool, &crate::db::user::UserProfile { primary_language: "fr".to_string() })
+            .await
+            .unwrap();
+        let words = get_recent_vocab(&pool, "es", None, 7, 10).await.unwrap();
+        assert_eq!(words[0].translation.as_deref(), Some("maison"));
+
+        // An explicit language still wins
+        let words = get_recent_vocab(&pool, "es", Some("en"), 7, 10).await.unwrap();
+        assert_eq!(words[0].translation.as_deref(), Some("house"));
+    }
+
+    #[test]
+    fn test_normalize_form() {
+        assert_eq!(normalize_form("Estoy"), "estoy");
+        assert_eq!(normalize_form("estoy,"), "estoy");
+        assert_eq!(normalize_form("¿Qué?"), "qué");
+        assert_eq!(normalize_form("«Él»"), "él");
+        assert_eq!(normalize_form("l'homme"), "l'homme");
+        assert_eq!(normalize_form("..."), "");
+    }
+
+    #[tokio::test]
+    async fn test_record_word_dedupes_forms_case_insensitively() {
+        let pool = setup_test_db().await;
+
+        for form in ["Estoy", "estoy,", "ESTOY", "estás", "..."] {
+            record_word(&pool, "estar", "es", form, None).await.unwrap();
+        }
+
+        let vocab = get_user_vocab(&pool, "es").await.unwrap();
+        assert_eq!(vocab[0].forms_spoken, vec!["estoy", "estás"]);
+        assert_eq!(vocab[0].usage_count, 5);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_forms_spoken_collapses_existing_rows() {
+        let pool = setup_test_db().await;
+        for (lemma, forms) in [
+            ("estar", r#"["Estoy", "estoy", "estoy,", "Estás"]"#),
+            ("casa", r#"["casa"]"#),
+        ] {
+            sqlx::query(
+                "INSERT INTO vocab (language, lemma, forms_spoken, first_seen_at, last_seen_at, created_at, updated_at)
+                 VALUES ('es', ?, ?, 0, 0, 0, 0)",
+            )
+            .bind(lemma)
+            .bind(forms)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(dedupe_forms_spoken(&pool).await.unwrap(), 1);
+        let (forms,): (String,) = sqlx::query_as("SELECT forms_spoken FROM vocab WHERE lemma = 'estar'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(forms, r#"["estoy","estás"]"#);
+
+        // Already clean
+        assert_eq!(dedupe_forms_spoken(&pool).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_recommend_new_words_skips_known_lemmas() {
+        let pool = setup_test_db().await;
+        record_word(&pool, "ser", "es", "es", None).await.unwrap();
+        record_word(&pool, "tener", "fr", "tener", None).await.unwrap();
+
+        let lemma_pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE lemmas (word TEXT PRIMARY KEY, lemma TEXT NOT NULL, frequency INTEGER)")
+            .execute(&lemma_pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO lemmas VALUES ('es', 'ser', 900), ('soy', 'ser', 500), ('tengo', 'tener', 700),
+             ('casa', 'casa', 300), ('perro', 'perro', 100), ('hablo', 'hablar', 400)",
+        )
+        .execute(&lemma_pool)
+        .await
+        .unwrap();
+
+        let provider = MockProvider {
+            glosses: [(("tener".to_string(), "en".to_string()), "to have".to_string())]
+                .into_iter()
+                .collect(),
+        };
+
+        let words = recommend_new_words_with(&pool, &lemma_pool, &provider, "es", "en", 3).await.unwrap();
+        let lemmas: Vec<&str> = words.iter().map(|w| w.lemma.as_str()).collect();
+        assert_eq!(lemmas, vec!["tener", "hablar", "casa"]);
+        assert_eq!(words[0].frequency, 700);
+        assert_eq!(words[0].translation.as_deref(), Some("to have"));
+        assert_eq!(words[1].translation, None);
+
+        // Without a frequency column, lemmas with the most forms come first
+        sqlx::query("ALTER TABLE lemmas DROP COLUMN frequency").execute(&lemma_pool).await.unwrap();
+        let words = recommend_new_words_with(&pool, &lemma_pool, &provider, "es", "en", 1).await.unwrap();
+        assert_eq!(words[0].lemma, "casa");
+        assert_eq!(words[0].frequency, 1);
+    }
 }
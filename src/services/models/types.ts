@@ -17,6 +17,8 @@ export interface DownloadProgress {
   downloadedBytes: number;
   totalBytes: number;
   percentage: number;
+  speedMbps: number;
+  etaSeconds: number | null;
   isComplete: boolean;
 }
 
@@ -20,6 +20,12 @@ export interface SessionData {
   sessionType: SessionType | null;
   textLibraryId: string | null;
   sourceText: string | null;
+  isPinned: boolean; // never removed by retention cleanup
+  isFavorite: boolean;
+  accuracyScore: number | null; // read-aloud accuracy (0-100)
+  fillerCount: number | null;
+  articulationWpm: number | null; // WPM excluding pauses
+  segments: string | null; // JSON, only loaded by getSession
 }
 
 export interface SessionStats {
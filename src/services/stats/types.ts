@@ -11,6 +11,8 @@ export interface OverallStats {
   longestStreakDays: number;
   avgUniqueWordsPerSession: number;
   avgNewWordsPerSession: number;
+  maxSessionDurationSeconds: number; // sessions without a duration aren't counted
+  medianSessionDurationSeconds: number;
 }
 
 export interface TopWord {
@@ -22,7 +24,8 @@ export interface TopWord {
 export interface DailySessionCount {
   date: string; // YYYY-MM-DD
   sessionCount: number;
-  totalMinutes: number;
+  totalMinutes: number; // rounded up
+  totalSeconds: number; // exact
 }
 
 export interface WpmTrend {
@@ -9,6 +9,10 @@ export interface SystemSpecs {
   cpu_cores: number;
   /** CPU brand/model name (e.g., "Apple M1", "Intel i7") */
   cpu_brand: string;
+  /** Whether Whisper can use a GPU in this build */
+  gpu_available: boolean;
+  /** GPU name, when the platform reports one */
+  gpu_name: string | null;
   /** Recommended Whisper model based on system specs */
   recommended_model: string;
 }
@@ -13,6 +13,7 @@ export interface DownloadProgress {
   totalBytes: number;
   percentage: number;
   speedMbps?: number;
+  etaSeconds?: number | null;
 
   // Language pack specific
   fileType?: string;        // "lemmas" or "translations"